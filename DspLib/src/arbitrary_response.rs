@@ -0,0 +1,236 @@
+use std::f64::consts::PI;
+
+use crate::fft::transform;
+use crate::{ComplexNumber, FirDesignError, Window};
+
+/// FIR design from arbitrary response sampled at given frequencies (like `fir2`), e.g. inverse
+/// of measured response for equalization. Response is linearly interpolated on dense uniform
+/// grid, transformed to impulse response with inverse FFT and windowed. Frequencies are in Hz,
+/// sorted, from 0 to Nyquist frequency. Two equal frequencies in a row give step in response.
+///
+/// Without phases the filter is symmetric (linear phase) with delay of `(N - 1) / 2` samples.
+/// Phases (in radians, unwrapped) are added to this linear phase.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ArbitraryResponse {
+    number_of_taps: usize,
+    frequencies: Vec<f64>,
+    gains: Vec<f64>,
+    phases: Option<Vec<f64>>,
+    sample_rate: f64,
+    window: Window,
+    grid_size: Option<usize>,
+}
+
+impl ArbitraryResponse {
+    pub fn new(number_of_taps: usize, frequencies: &[f64], gains: &[f64], sample_rate: f64) -> Self {
+        Self{
+            number_of_taps,
+            frequencies: frequencies.to_vec(),
+            gains: gains.to_vec(),
+            phases: None,
+            sample_rate,
+            window: Window::Hamming,
+            grid_size: None,
+        }
+    }
+
+    /// Phase (in radians) at every frequency point. Response of filter with even number of taps is
+    /// real at Nyquist frequency, so there the phase plus linear phase `-PI * (N - 1) / 2` shall be
+    /// multiple of `PI`, unless the gain is zero.
+    pub fn set_phases(mut self, phases: &[f64]) -> Self {
+        self.phases = Some(phases.to_vec());
+        self
+    }
+
+    /// Window applied to impulse response, default is Hamming.
+    pub fn set_window(mut self, window: Window) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Number of interpolated points from 0 to Nyquist frequency. By default it is `2^k + 1`,
+    /// at least 513 and more than number of taps. It is increased when FFT of `2 * (size - 1)`
+    /// points would be shorter than filter.
+    pub fn set_grid_size(mut self, size: usize) -> Self {
+        self.grid_size = Some(size);
+        self
+    }
+
+    pub fn design(&self) -> Result<Vec<f64>, FirDesignError> {
+        self.validate()?;
+        let taps = self.number_of_taps;
+        let grid_size = self.grid_size.unwrap_or_else(|| (taps.next_power_of_two() + 1).max(513)).max(taps.div_ceil(2) + 1).max(2);
+        let fft_size = 2 * (grid_size - 1);
+        let nyquist = self.sample_rate / 2.0;
+        let delay = (taps - 1) as f64 / 2.0;
+
+        let mut spectrum = vec![ComplexNumber::default(); fft_size];
+        for k in 0..grid_size {
+            let f = nyquist * k as f64 / (grid_size - 1) as f64;
+            let gain = interpolate(&self.frequencies, &self.gains, f);
+            let phase = self.phases.as_ref().map_or(0.0, |phases| interpolate(&self.frequencies, phases, f));
+            let value = ComplexNumber::from_polar(gain, phase - PI * k as f64 * delay / (grid_size - 1) as f64);
+            if k == 0 || k == grid_size - 1 {
+                // DC and Nyquist bins of real signal are real
+                spectrum[k] = ComplexNumber::new(value.re(), 0.0);
+            } else {
+                spectrum[k] = value;
+                spectrum[fft_size - k] = value.conj();
+            }
+        }
+
+        let impulse_response = transform(&spectrum, true);
+        let window = self.window.coefficients(taps);
+        Ok(impulse_response.iter().zip(window).map(|(h, w)| h.re() * w).collect())
+    }
+
+    fn validate(&self) -> Result<(), FirDesignError> {
+        if !(self.sample_rate > 0.0 && self.sample_rate.is_finite()) {
+            return Err(FirDesignError::InvalidSampleRate(self.sample_rate));
+        }
+        if self.number_of_taps == 0 || self.grid_size.is_some_and(|size| size < 2) {
+            return Err(FirDesignError::InvalidNumberOfTaps(self.number_of_taps));
+        }
+        let points = self.frequencies.len();
+        for values in [Some(&self.gains), self.phases.as_ref()].into_iter().flatten() {
+            if values.len() != points {
+                return Err(FirDesignError::PointCountMismatch{frequencies: points, values: values.len()});
+            }
+        }
+        let nyquist = self.sample_rate / 2.0;
+        let (first, last) = (self.frequencies.first().copied().unwrap_or(0.0), self.frequencies.last().copied().unwrap_or(0.0));
+        if points < 2 || first != 0.0 || last != nyquist {
+            return Err(FirDesignError::InvalidBand{start: first, end: last});
+        }
+        for (i, pair) in self.frequencies.windows(2).enumerate() {
+            let step_repeated = i > 0 && self.frequencies[i - 1] == pair[0];
+            if pair[0].is_nan() || pair[0] > pair[1] || (pair[0] == pair[1] && step_repeated) {
+                return Err(FirDesignError::InvalidBand{start: pair[0], end: pair[1]});
+            }
+        }
+        if self.number_of_taps.is_multiple_of(2) {
+            // imaginary part of Nyquist bin would be dropped
+            let gain = self.gains[points - 1];
+            let phase = self.phases.as_ref().map_or(0.0, |phases| phases[points - 1]) - PI * (self.number_of_taps - 1) as f64 / 2.0;
+            if (gain * phase.sin()).abs() > 1e-9 * gain.abs() {
+                return Err(FirDesignError::EvenNumberOfTaps(self.number_of_taps));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Linear interpolation of sorted points, value after step is taken at step frequency.
+fn interpolate(frequencies: &[f64], values: &[f64], f: f64) -> f64 {
+    let i = frequencies.partition_point(|x| *x <= f).max(1) - 1;
+    if i + 1 == frequencies.len() {
+        return values[i];
+    }
+    let x = (f - frequencies[i]) / (frequencies[i + 1] - frequencies[i]);
+    values[i] + x * (values[i + 1] - values[i])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::fir_gain;
+
+    #[test]
+    fn filter_shall_follow_sampled_response() {
+        let frequencies = [0.0, 500.0, 1500.0, 2500.0, 4000.0];
+        let gains = [1.0, 1.0, 2.0, 0.5, 0.5];
+        let fir = ArbitraryResponse::new(101, &frequencies, &gains, 8000.0).design().unwrap();
+
+        for (a, b) in fir.iter().zip(fir.iter().rev()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        for (f, expected) in [(0.0, 1.0), (1000.0, 1.5), (2000.0, 1.25), (3500.0, 0.5)] {
+            let gain = fir_gain(&fir, f, 8000.0);
+            assert!((gain - expected).abs() < 0.03, "{} {} {}", f, gain, expected);
+        }
+    }
+
+    #[test]
+    fn step_in_response_shall_give_low_pass_filter() {
+        let fir = ArbitraryResponse::new(61, &[0.0, 0.2, 0.2, 0.5], &[1.0, 1.0, 0.0, 0.0], 1.0)
+            .set_window(Window::Blackman)
+            .design()
+            .unwrap();
+
+        assert!((fir_gain(&fir, 0.05, 1.0) - 1.0).abs() < 1e-3);
+        assert!((fir_gain(&fir, 0.2, 1.0) - 0.5).abs() < 0.05);
+        assert!(fir_gain(&fir, 0.35, 1.0) < 1e-3);
+    }
+
+    #[test]
+    fn phases_shall_be_added_to_linear_phase() {
+        let frequencies = [0.0, 0.5];
+        let delay = 3.0;
+        let fir = ArbitraryResponse::new(31, &frequencies, &[1.0, 1.0], 1.0)
+            .set_phases(&[0.0, -2.0 * PI * 0.5 * delay])
+            .set_window(Window::Rectangular)
+            .design()
+            .unwrap();
+
+        for (n, h) in fir.iter().enumerate() {
+            let expected = if n == 15 + delay as usize { 1.0 } else { 0.0 };
+            assert!((h - expected).abs() < 1e-12, "{} {}", n, h);
+        }
+    }
+
+    #[test]
+    fn small_grid_shall_be_enlarged_to_number_of_taps() {
+        for taps in [6, 7, 8] {
+            let fir = ArbitraryResponse::new(taps, &[0.0, 0.5], &[1.0, 0.0], 1.0).set_grid_size(2).design().unwrap();
+            assert_eq!(fir.len(), taps);
+        }
+        let fir = ArbitraryResponse::new(7, &[0.0, 0.5], &[1.0, 1.0], 1.0)
+            .set_grid_size(2)
+            .set_window(Window::Rectangular)
+            .design()
+            .unwrap();
+        for (n, h) in fir.iter().enumerate() {
+            assert!((h - if n == 3 { 1.0 } else { 0.0 }).abs() < 1e-12, "{} {}", n, h);
+        }
+    }
+
+    #[test]
+    fn inverse_of_measured_response_shall_equalize_it() {
+        let channel = [1.0, 0.5, 0.25];
+        let frequencies = (0..=64).map(|k| k as f64 / 128.0).collect::<Vec<f64>>();
+        let gains = frequencies.iter().map(|f| 1.0 / fir_gain(&channel, *f, 1.0)).collect::<Vec<f64>>();
+        let fir = ArbitraryResponse::new(63, &frequencies, &gains, 1.0).design().unwrap();
+
+        for f in [0.0, 0.1, 0.2, 0.3, 0.4, 0.5] {
+            let gain = fir_gain(&fir, f, 1.0) * fir_gain(&channel, f, 1.0);
+            assert!((gain - 1.0).abs() < 0.01, "{} {}", f, gain);
+        }
+    }
+
+    #[test]
+    fn invalid_points_shall_be_rejected() {
+        assert_eq!(ArbitraryResponse::new(31, &[0.0, 0.3], &[1.0, 0.0], 1.0).design(),
+                   Err(FirDesignError::InvalidBand{start: 0.0, end: 0.3}));
+        assert_eq!(ArbitraryResponse::new(31, &[0.0, 0.3, 0.2, 0.5], &[1.0; 4], 1.0).design(),
+                   Err(FirDesignError::InvalidBand{start: 0.3, end: 0.2}));
+        assert_eq!(ArbitraryResponse::new(31, &[0.0, 0.5], &[1.0], 1.0).design(),
+                   Err(FirDesignError::PointCountMismatch{frequencies: 2, values: 1}));
+        assert_eq!(ArbitraryResponse::new(30, &[0.0, 0.5], &[1.0, 1.0], 1.0).design(),
+                   Err(FirDesignError::EvenNumberOfTaps(30)));
+        assert_eq!(ArbitraryResponse::new(30, &[0.0, 0.5], &[1.0, 1.0], 1.0).set_phases(&[0.0, 0.3]).design(),
+                   Err(FirDesignError::EvenNumberOfTaps(30)));
+    }
+
+    #[test]
+    fn even_number_of_taps_shall_accept_real_response_at_nyquist_frequency() {
+        // half sample of extra delay makes response at Nyquist frequency real
+        let fir = ArbitraryResponse::new(30, &[0.0, 0.5], &[1.0, 1.0], 1.0)
+            .set_phases(&[0.0, -2.0 * PI * 0.5 * 0.5])
+            .set_window(Window::Rectangular)
+            .design()
+            .unwrap();
+        assert!((fir_gain(&fir, 0.5, 1.0) - 1.0).abs() < 1e-12);
+        assert!(ArbitraryResponse::new(30, &[0.0, 0.5], &[1.0, 0.0], 1.0).set_phases(&[0.0, 0.3]).design().is_ok());
+    }
+}
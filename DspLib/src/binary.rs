@@ -0,0 +1,360 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::{ComplexNumber, ComplexSignal, DescreteSignal, SignalMetadata};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+/// Type of one sample (or one component of complex sample) in binary stream. Samples are
+/// converted to `f64`, so 64-bit integers are exact only up to 2^53 in magnitude.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RawSampleType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    F32,
+    F64,
+}
+
+macro_rules! decode_as {
+    ($t:ty, $bytes:expr, $endianness:expr) => {{
+        let bytes = $bytes.try_into().unwrap();
+        (match $endianness {
+            Endianness::Little => <$t>::from_le_bytes(bytes),
+            Endianness::Big => <$t>::from_be_bytes(bytes),
+        }) as f64
+    }};
+}
+
+macro_rules! encode_as {
+    ($t:ty, $value:expr, $endianness:expr, $out:expr) => {{
+        let value = $value as $t;
+        match $endianness {
+            Endianness::Little => $out.extend_from_slice(&value.to_le_bytes()),
+            Endianness::Big => $out.extend_from_slice(&value.to_be_bytes()),
+        }
+    }};
+}
+
+impl RawSampleType {
+    pub fn size(&self) -> usize {
+        match self {
+            RawSampleType::I8 | RawSampleType::U8 => 1,
+            RawSampleType::I16 | RawSampleType::U16 => 2,
+            RawSampleType::I32 | RawSampleType::U32 | RawSampleType::F32 => 4,
+            RawSampleType::I64 | RawSampleType::U64 | RawSampleType::F64 => 8,
+        }
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, RawSampleType::F32 | RawSampleType::F64)
+    }
+
+    /// Range of values that can be stored without clipping.
+    fn range(&self) -> (f64, f64) {
+        match self {
+            RawSampleType::I8 => (i8::MIN as f64, i8::MAX as f64),
+            RawSampleType::U8 => (0.0, u8::MAX as f64),
+            RawSampleType::I16 => (i16::MIN as f64, i16::MAX as f64),
+            RawSampleType::U16 => (0.0, u16::MAX as f64),
+            RawSampleType::I32 => (i32::MIN as f64, i32::MAX as f64),
+            RawSampleType::U32 => (0.0, u32::MAX as f64),
+            RawSampleType::I64 => (i64::MIN as f64, i64::MAX as f64),
+            RawSampleType::U64 => (0.0, u64::MAX as f64),
+            RawSampleType::F32 => (f32::MIN as f64, f32::MAX as f64),
+            RawSampleType::F64 => (f64::MIN, f64::MAX),
+        }
+    }
+
+    pub(crate) fn decode(&self, bytes: &[u8], endianness: Endianness) -> f64 {
+        match self {
+            RawSampleType::I8 => bytes[0] as i8 as f64,
+            RawSampleType::U8 => bytes[0] as f64,
+            RawSampleType::I16 => decode_as!(i16, bytes, endianness),
+            RawSampleType::U16 => decode_as!(u16, bytes, endianness),
+            RawSampleType::I32 => decode_as!(i32, bytes, endianness),
+            RawSampleType::U32 => decode_as!(u32, bytes, endianness),
+            RawSampleType::I64 => decode_as!(i64, bytes, endianness),
+            RawSampleType::U64 => decode_as!(u64, bytes, endianness),
+            RawSampleType::F32 => decode_as!(f32, bytes, endianness),
+            RawSampleType::F64 => decode_as!(f64, bytes, endianness),
+        }
+    }
+
+    /// Appends encoded value to `out`. Integer values are rounded, values out of range are
+    /// clipped. Returns true when value had to be clipped.
+    pub(crate) fn encode(&self, value: f64, endianness: Endianness, out: &mut Vec<u8>) -> bool {
+        let (min, max) = self.range();
+        let rounded = if self.is_float() { value } else { value.round() };
+        let clamped = rounded.clamp(min, max);
+        match self {
+            RawSampleType::I8 => out.push(clamped as i8 as u8),
+            RawSampleType::U8 => out.push(clamped as u8),
+            RawSampleType::I16 => encode_as!(i16, clamped, endianness, out),
+            RawSampleType::U16 => encode_as!(u16, clamped, endianness, out),
+            RawSampleType::I32 => encode_as!(i32, clamped, endianness, out),
+            RawSampleType::U32 => encode_as!(u32, clamped, endianness, out),
+            RawSampleType::I64 => encode_as!(i64, clamped, endianness, out),
+            RawSampleType::U64 => encode_as!(u64, clamped, endianness, out),
+            RawSampleType::F32 => encode_as!(f32, clamped, endianness, out),
+            RawSampleType::F64 => encode_as!(f64, clamped, endianness, out),
+        }
+        clamped != rounded
+    }
+}
+
+#[derive(Debug)]
+pub enum BinaryError {
+    Io(std::io::Error),
+    /// Data does not follow expected format.
+    InvalidFormat(String),
+    /// Data type that cannot be mapped to signal values.
+    UnsupportedType(String),
+    /// Signals written together shall have equal length.
+    ChannelLengthMismatch,
+}
+
+impl Display for BinaryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BinaryError::Io(e) => write!(f, "I/O error: {}", e),
+            BinaryError::InvalidFormat(reason) => write!(f, "invalid data: {}", reason),
+            BinaryError::UnsupportedType(t) => write!(f, "unsupported data type: {}", t),
+            BinaryError::ChannelLengthMismatch => write!(f, "all channels shall have the same number of samples"),
+        }
+    }
+}
+
+impl std::error::Error for BinaryError {}
+
+impl From<std::io::Error> for BinaryError {
+    fn from(e: std::io::Error) -> Self {
+        BinaryError::Io(e)
+    }
+}
+
+/// Description of headerless binary stream with interleaved channels. Samples are read
+/// without scaling, so integer streams can be written back without any loss, except for
+/// 64-bit integers larger than 2^53 in magnitude, which are rounded to the nearest `f64`.
+/// Complex streams hold I and Q component of every sample next to each other.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RawFormat {
+    sample_type: RawSampleType,
+    endianness: Endianness,
+    channels: usize,
+    sample_rate: f64,
+}
+
+impl RawFormat {
+    pub fn new(sample_type: RawSampleType) -> Self {
+        Self{sample_type, endianness: Endianness::Little, channels: 1, sample_rate: 1.0}
+    }
+
+    pub fn set_endianness(mut self, endianness: Endianness) -> Self {
+        self.endianness = endianness;
+        self
+    }
+
+    pub fn set_channels(mut self, channels: usize) -> Self {
+        self.channels = channels.max(1);
+        self
+    }
+
+    pub fn set_sample_rate(mut self, rate: f64) -> Self {
+        self.sample_rate = rate;
+        self
+    }
+
+    pub fn read<P: AsRef<Path>>(&self, path: P) -> Result<Vec<DescreteSignal>, BinaryError> {
+        self.read_from(BufReader::new(File::open(path)?))
+    }
+
+    /// Reads real samples, one signal per channel. Incomplete frame at the end is dropped.
+    pub fn read_from<R: Read>(&self, reader: R) -> Result<Vec<DescreteSignal>, BinaryError> {
+        let values = self.read_values(reader)?;
+        let mut signals = vec![DescreteSignal::new(); self.channels];
+
+        for (i, frame) in values.chunks_exact(self.channels).enumerate() {
+            let t = i as f64 / self.sample_rate;
+            for (signal, y) in signals.iter_mut().zip(frame) {
+                signal.push(t, *y);
+            }
+        }
+        for signal in signals.iter_mut() {
+            signal.set_metadata(self.metadata());
+        }
+        Ok(signals)
+    }
+
+    /// Reads complex samples, one signal per channel.
+    pub fn read_complex_from<R: Read>(&self, reader: R) -> Result<Vec<ComplexSignal>, BinaryError> {
+        let values = self.read_values(reader)?;
+        let mut signals = vec![ComplexSignal::new(); self.channels];
+
+        for (i, frame) in values.chunks_exact(2 * self.channels).enumerate() {
+            let t = i as f64 / self.sample_rate;
+            for (signal, iq) in signals.iter_mut().zip(frame.chunks_exact(2)) {
+                signal.push(t, ComplexNumber::new(iq[0], iq[1]));
+            }
+        }
+        for signal in signals.iter_mut() {
+            signal.set_metadata(self.metadata());
+        }
+        Ok(signals)
+    }
+
+    /// Writes signals as interleaved channels. Returns number of samples that were clipped
+    /// because they did not fit in the sample type.
+    pub fn write<P: AsRef<Path>>(&self, path: P, signals: &[DescreteSignal]) -> Result<usize, BinaryError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let clipped = self.write_to(&mut writer, signals)?;
+        writer.flush()?;
+        Ok(clipped)
+    }
+
+    pub fn write_to<W: Write>(&self, writer: W, signals: &[DescreteSignal]) -> Result<usize, BinaryError> {
+        let columns = signals.iter().map(|s| s.values().collect()).collect::<Vec<Vec<f64>>>();
+        self.write_values(writer, &columns)
+    }
+
+    pub fn write_complex_to<W: Write>(&self, writer: W, signals: &[ComplexSignal]) -> Result<usize, BinaryError> {
+        let mut columns = vec![];
+        for s in signals {
+            columns.push(s.samples().map(|y| y.re()).collect::<Vec<f64>>());
+            columns.push(s.samples().map(|y| y.im()).collect::<Vec<f64>>());
+        }
+        self.write_values(writer, &columns)
+    }
+
+    fn read_values<R: Read>(&self, mut reader: R) -> Result<Vec<f64>, BinaryError> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+        Ok(bytes.chunks_exact(self.sample_type.size())
+            .map(|b| self.sample_type.decode(b, self.endianness))
+            .collect())
+    }
+
+    fn write_values<W: Write>(&self, mut writer: W, columns: &[Vec<f64>]) -> Result<usize, BinaryError> {
+        let len = columns.first().map_or(0, Vec::len);
+        if columns.iter().any(|c| c.len() != len) {
+            return Err(BinaryError::ChannelLengthMismatch);
+        }
+        let mut bytes = Vec::with_capacity(len * columns.len() * self.sample_type.size());
+        let mut clipped = 0;
+        for i in 0..len {
+            for column in columns {
+                if self.sample_type.encode(column[i], self.endianness, &mut bytes) {
+                    clipped += 1;
+                }
+            }
+        }
+        writer.write_all(&bytes)?;
+        Ok(clipped)
+    }
+
+    fn metadata(&self) -> SignalMetadata {
+        SignalMetadata::new().set_time_unit("s").set_sample_rate(self.sample_rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_sample_type_shall_survive_round_trip_in_both_endiannesses() {
+        let types = [RawSampleType::I8, RawSampleType::U8, RawSampleType::I16, RawSampleType::U16,
+                     RawSampleType::I32, RawSampleType::U32, RawSampleType::I64, RawSampleType::U64,
+                     RawSampleType::F32, RawSampleType::F64];
+        let signal = DescreteSignal::new_from_vec(vec![(0.0, 0.0), (0.5, 1.0), (1.0, 100.0), (1.5, 127.0)]);
+
+        for t in types {
+            for e in [Endianness::Little, Endianness::Big] {
+                let format = RawFormat::new(t).set_endianness(e).set_sample_rate(2.0);
+                let mut bytes = vec![];
+                assert_eq!(format.write_to(&mut bytes, std::slice::from_ref(&signal)).unwrap(), 0);
+                assert_eq!(bytes.len(), 4 * t.size());
+                let read = format.read_from(bytes.as_slice()).unwrap();
+                assert_eq!(read[0].get_data(), signal.get_data(), "{:?} {:?}", t, e);
+            }
+        }
+    }
+
+    #[test]
+    fn large_64_bit_integers_shall_be_rounded_to_f64() {
+        let format = RawFormat::new(RawSampleType::I64);
+        let exact = (1i64 << 53) - 1;
+        let bytes = [exact.to_le_bytes(), (i64::MAX - 1).to_le_bytes()].concat();
+        let read = format.read_from(bytes.as_slice()).unwrap();
+
+        assert_eq!(read[0][0].1, exact as f64);
+        assert_eq!(read[0][1].1, 2f64.powi(63));
+        let mut written = vec![];
+        format.write_to(&mut written, &read).unwrap();
+        assert_eq!(written[..8], bytes[..8]);
+        assert_eq!(written[8..], i64::MAX.to_le_bytes());
+    }
+
+    #[test]
+    fn endianness_shall_define_byte_order() {
+        let bytes = [0x01, 0x02];
+
+        assert_eq!(RawFormat::new(RawSampleType::I16).read_from(&bytes[..]).unwrap()[0][0].1, 513.0);
+        assert_eq!(RawFormat::new(RawSampleType::I16).set_endianness(Endianness::Big)
+            .read_from(&bytes[..]).unwrap()[0][0].1, 258.0);
+    }
+
+    #[test]
+    fn interleaved_channels_shall_be_split() {
+        let bytes = [1u8, 0xFF, 2, 0xFE, 3];
+        let signals = RawFormat::new(RawSampleType::I8).set_channels(2).set_sample_rate(10.0)
+            .read_from(&bytes[..]).unwrap();
+
+        assert_eq!(signals[0].get_data(), &[(0.0, 1.0), (0.1, 2.0)]);
+        assert_eq!(signals[1].get_data(), &[(0.0, -1.0), (0.1, -2.0)]);
+        assert_eq!(signals[0].metadata().sample_rate(), Some(10.0));
+    }
+
+    #[test]
+    fn complex_samples_shall_be_read_as_iq_pairs() {
+        let format = RawFormat::new(RawSampleType::F32).set_sample_rate(4.0);
+        let signal = ComplexSignal::from_iq(&[1.0, 2.0], &[-0.5, 0.25], 4.0, 0.0);
+        let mut bytes = vec![];
+
+        format.write_complex_to(&mut bytes, std::slice::from_ref(&signal)).unwrap();
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(format.read_complex_from(bytes.as_slice()).unwrap()[0].get_data(), signal.get_data());
+    }
+
+    #[test]
+    fn writing_shall_round_and_clip_integer_samples() {
+        let signal = DescreteSignal::new_from_vec(vec![(0.0, 1.6), (1.0, 300.0), (2.0, -300.0)]);
+        let format = RawFormat::new(RawSampleType::I8);
+        let mut bytes = vec![];
+
+        assert_eq!(format.write_to(&mut bytes, &[signal]).unwrap(), 2);
+        assert_eq!(bytes, vec![2, 127, 0x80]);
+    }
+
+    #[test]
+    fn writing_channels_of_different_length_shall_fail() {
+        let signals = [DescreteSignal::new_from_vec(vec![(0.0, 1.0)]), DescreteSignal::new()];
+
+        assert!(matches!(RawFormat::new(RawSampleType::F64).write_to(vec![], &signals),
+                         Err(BinaryError::ChannelLengthMismatch)));
+    }
+}
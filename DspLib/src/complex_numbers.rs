@@ -1,7 +1,8 @@
 
-use std::{ops::{Add, Mul}, fmt::{Display, Debug}};
+use std::{ops::{Add, Div, Mul, Neg, Sub}, fmt::{Display, Debug}};
 
-#[derive(Default, Clone, PartialEq)]
+#[derive(Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ComplexNumber (f64, f64);
 
 impl ComplexNumber {
@@ -20,6 +21,36 @@ impl ComplexNumber {
     pub fn module(&self) -> f64 {
         (self.0*self.0 + self.1*self.1).sqrt()
     }
+
+    /// Creates number from its module and argument (in radians).
+    pub fn from_polar(module: f64, arg: f64) -> Self {
+        ComplexNumber(module * arg.cos(), module * arg.sin())
+    }
+
+    /// Argument (phase) of the number in radians, in range -PI..=PI.
+    pub fn arg(&self) -> f64 {
+        self.1.atan2(self.0)
+    }
+
+    pub fn conj(&self) -> Self {
+        ComplexNumber(self.0, -self.1)
+    }
+}
+
+impl Sub<&ComplexNumber> for &ComplexNumber {
+    type Output = ComplexNumber;
+
+    fn sub(self, other: &ComplexNumber) -> ComplexNumber {
+        ComplexNumber(self.0 - other.0, self.1 - other.1)
+    }
+}
+
+impl Neg for &ComplexNumber {
+    type Output = ComplexNumber;
+
+    fn neg(self) -> ComplexNumber {
+        ComplexNumber(-self.0, -self.1)
+    }
 }
 
 impl Add<&ComplexNumber> for &ComplexNumber {
@@ -56,6 +87,24 @@ impl Mul<f64> for &ComplexNumber {
     }
 }
 
+impl Div<&ComplexNumber> for &ComplexNumber {
+    type Output = ComplexNumber;
+
+    fn div(self, other: &ComplexNumber) -> ComplexNumber {
+        let den = other.0 * other.0 + other.1 * other.1;
+        ComplexNumber((self.0 * other.0 + self.1 * other.1) / den,
+                      (self.1 * other.0 - self.0 * other.1) / den)
+    }
+}
+
+impl Div<f64> for &ComplexNumber {
+    type Output = ComplexNumber;
+
+    fn div(self, other: f64) -> ComplexNumber {
+        ComplexNumber(self.0 / other, self.1 / other)
+    }
+}
+
 impl Display for ComplexNumber {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if self.1 < 0.0 {
@@ -145,6 +194,31 @@ mod tests {
         assert_eq!(c3.im(), input.1 * mul);
     }
 
+    #[test]
+    fn subtracting_shall_subtract_re_part_and_im_part_separately() {
+        let c = &ComplexNumber::new(5.0, 1.5) - &ComplexNumber::new(2.0, 4.0);
+        assert_eq!(c, ComplexNumber::new(3.0, -2.5));
+        assert_eq!(-&c, ComplexNumber::new(-3.0, 2.5));
+    }
+
+    #[test]
+    fn dividing_shall_be_inverse_of_multiplying() {
+        let c1 = ComplexNumber::new(3.0, -4.0);
+        let c2 = ComplexNumber::new(1.0, 2.0);
+        assert_eq!(&(&c1 * &c2) / &c2, c1);
+        assert_eq!(&c1 / 2.0, ComplexNumber::new(1.5, -2.0));
+    }
+
+    #[test]
+    fn polar_form_shall_be_consistent_with_module_and_argument() {
+        let c = ComplexNumber::from_polar(2.0, std::f64::consts::FRAC_PI_2);
+        assert!(c.re().abs() < 1e-15);
+        assert_eq!(c.im(), 2.0);
+        assert_eq!(c.module(), 2.0);
+        assert_eq!(ComplexNumber::new(-1.0, 0.0).arg(), std::f64::consts::PI);
+        assert_eq!(ComplexNumber::new(1.0, 1.0).conj(), ComplexNumber::new(1.0, -1.0));
+    }
+
     #[test]
     fn module_shall_return_correct_float_number() {
         let c = ComplexNumber::new(3.0, 4.0);
@@ -0,0 +1,272 @@
+use std::f64::consts::PI;
+use std::ops::{Index, IndexMut};
+use std::slice::SliceIndex;
+
+use crate::fft::transform;
+use crate::{ComplexNumber, DescreteSignal, SignalMetadata};
+
+/// Complex valued (I/Q) signal, every sample holds timestamp and complex value.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ComplexSignal {
+    data: Vec<(f64, ComplexNumber)>,
+    metadata: SignalMetadata,
+}
+
+impl<I: SliceIndex<[(f64, ComplexNumber)]>> Index<I> for ComplexSignal {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &Self::Output {
+        &self.data[index]
+    }
+}
+
+impl<I: SliceIndex<[(f64, ComplexNumber)]>> IndexMut<I> for ComplexSignal {
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        &mut self.data[index]
+    }
+}
+
+impl IntoIterator for ComplexSignal {
+    type Item = (f64, ComplexNumber);
+    type IntoIter = std::vec::IntoIter<(f64, ComplexNumber)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ComplexSignal {
+    type Item = &'a (f64, ComplexNumber);
+    type IntoIter = std::slice::Iter<'a, (f64, ComplexNumber)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter()
+    }
+}
+
+impl FromIterator<(f64, ComplexNumber)> for ComplexSignal {
+    fn from_iter<T: IntoIterator<Item = (f64, ComplexNumber)>>(iter: T) -> Self {
+        Self{data: iter.into_iter().collect(), metadata: SignalMetadata::default()}
+    }
+}
+
+impl Extend<(f64, ComplexNumber)> for ComplexSignal {
+    fn extend<T: IntoIterator<Item = (f64, ComplexNumber)>>(&mut self, iter: T) {
+        self.data.extend(iter);
+    }
+}
+
+impl ComplexSignal {
+    pub fn new() -> Self {
+        Self{data: Vec::new(), metadata: SignalMetadata::default()}
+    }
+
+    pub fn new_from_vec(data: Vec<(f64, ComplexNumber)>) -> Self {
+        Self{data, metadata: SignalMetadata::default()}
+    }
+
+    /// Creates signal from samples taken with given sampling rate, starting at `start_time`.
+    pub fn from_samples(samples: &[ComplexNumber], sampling_rate: f64, start_time: f64) -> Self {
+        let mut signal = samples.iter()
+            .enumerate()
+            .map(|(i, y)| (start_time + i as f64 / sampling_rate, *y))
+            .collect::<Self>();
+        signal.metadata = SignalMetadata::new().set_sample_rate(sampling_rate);
+        signal
+    }
+
+    /// Creates signal from separate in-phase and quadrature components.
+    pub fn from_iq(i: &[f64], q: &[f64], sampling_rate: f64, start_time: f64) -> Self {
+        assert_eq!(i.len(), q.len(), "I and Q components shall have equal length");
+        let samples = i.iter().zip(q).map(|(re, im)| ComplexNumber::new(*re, *im)).collect::<Vec<ComplexNumber>>();
+        Self::from_samples(&samples, sampling_rate, start_time)
+    }
+
+    /// Converts real signal to complex one with zero imaginary part.
+    pub fn from_real(signal: &DescreteSignal) -> Self {
+        let mut complex = signal.iter().map(|(x, y)| (*x, ComplexNumber::new(*y, 0.0))).collect::<Self>();
+        complex.metadata = signal.metadata().clone();
+        complex
+    }
+
+    /// Analytic signal of real signal, its real part is the original signal and imaginary part
+    /// is its Hilbert transform. Negative frequencies are removed in frequency domain.
+    pub fn analytic(signal: &DescreteSignal) -> Self {
+        let n = signal.len();
+        let samples = signal.values().map(|y| ComplexNumber::new(y, 0.0)).collect::<Vec<ComplexNumber>>();
+        let mut spectrum = transform(&samples, false);
+
+        for (k, bin) in spectrum.iter_mut().enumerate() {
+            let gain = if k == 0 || 2 * k == n {
+                1.0
+            } else if 2 * k < n {
+                2.0
+            } else {
+                0.0
+            };
+            *bin = &*bin * gain;
+        }
+
+        let mut analytic = signal.times().zip(transform(&spectrum, true)).collect::<Self>();
+        analytic.metadata = signal.metadata().clone();
+        analytic
+    }
+
+    pub fn metadata(&self) -> &SignalMetadata {
+        &self.metadata
+    }
+
+    pub fn set_metadata(&mut self, metadata: SignalMetadata) {
+        self.metadata = metadata;
+    }
+
+    pub fn push(&mut self, x: f64, y: ComplexNumber) {
+        self.data.push((x, y));
+    }
+
+    pub fn get_data(&self) -> &[(f64, ComplexNumber)] {
+        &self.data
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.data = vec![];
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, (f64, ComplexNumber)> {
+        self.data.iter()
+    }
+
+    pub fn times(&self) -> impl Iterator<Item = f64> + '_ {
+        self.data.iter().map(|(x, _)| *x)
+    }
+
+    pub fn samples(&self) -> impl Iterator<Item = ComplexNumber> + '_ {
+        self.data.iter().map(|(_, y)| *y)
+    }
+
+    pub fn get_sampling_period(&self) -> f64 {
+        self.data[1].0 - self.data[0].0
+    }
+
+    /// In-phase component, it is also the way back to real signal.
+    pub fn real(&self) -> DescreteSignal {
+        self.map_to_real(|y| y.re())
+    }
+
+    /// Quadrature component.
+    pub fn imag(&self) -> DescreteSignal {
+        self.map_to_real(|y| y.im())
+    }
+
+    /// Instantaneous amplitude (envelope).
+    pub fn magnitude(&self) -> DescreteSignal {
+        self.map_to_real(|y| y.module())
+    }
+
+    /// Instantaneous phase in radians, wrapped to -PI..=PI.
+    pub fn phase(&self) -> DescreteSignal {
+        self.map_to_real(|y| y.arg())
+    }
+
+    pub fn conj(&self) -> Self {
+        self.map(|_, y| y.conj())
+    }
+
+    /// Shifts spectrum of the signal by `frequency` Hz by multiplying samples
+    /// with `exp(i*2*PI*frequency*t)`.
+    pub fn frequency_shift(&self, frequency: f64) -> Self {
+        self.map(|t, y| y * &ComplexNumber::from_polar(1.0, 2.0 * PI * frequency * t))
+    }
+
+    /// Multiplies signals sample by sample. Timestamps are taken from `self`.
+    pub fn mix(&self, other: &ComplexSignal) -> Self {
+        assert_eq!(self.len(), other.len(), "mixed signals shall have equal length");
+        let mut mixed = self.data.iter()
+            .zip(other.samples())
+            .map(|((t, y), z)| (*t, y * &z))
+            .collect::<Self>();
+        mixed.metadata = self.metadata.merge(&other.metadata);
+        mixed
+    }
+
+    fn map(&self, f: impl Fn(f64, &ComplexNumber) -> ComplexNumber) -> Self {
+        let mut signal = self.data.iter().map(|(x, y)| (*x, f(*x, y))).collect::<Self>();
+        signal.metadata = self.metadata.clone();
+        signal
+    }
+
+    fn map_to_real(&self, f: impl Fn(&ComplexNumber) -> f64) -> DescreteSignal {
+        let mut signal = self.data.iter().map(|(x, y)| (*x, f(y))).collect::<DescreteSignal>();
+        signal.set_metadata(self.metadata.clone());
+        signal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Generator;
+
+    #[test]
+    fn from_iq_shall_combine_components_with_timestamps() {
+        let signal = ComplexSignal::from_iq(&[1.0, 2.0], &[-1.0, 0.5], 4.0, 1.0);
+
+        assert_eq!(signal.get_data(), &[(1.0, ComplexNumber::new(1.0, -1.0)), (1.25, ComplexNumber::new(2.0, 0.5))]);
+        assert_eq!(signal.get_sampling_period(), 0.25);
+        assert_eq!(signal.real().get_data(), &[(1.0, 1.0), (1.25, 2.0)]);
+        assert_eq!(signal.imag().get_data(), &[(1.0, -1.0), (1.25, 0.5)]);
+    }
+
+    #[test]
+    fn real_signal_shall_survive_round_trip_through_complex_signal() {
+        let sine = Generator::sine_wave(3.0).set_number_of_periods(2.0).generate();
+
+        assert_eq!(ComplexSignal::from_real(&sine).real(), sine);
+    }
+
+    #[test]
+    fn analytic_signal_of_cosine_shall_be_complex_exponential() {
+        let n = 64;
+        let cosine = (0..n)
+            .map(|i| (i as f64, (2.0 * PI * 4.0 * i as f64 / n as f64).cos()))
+            .collect::<DescreteSignal>();
+        let analytic = ComplexSignal::analytic(&cosine);
+
+        for ((t, z), (_, y)) in analytic.iter().zip(cosine.iter()) {
+            let expected = ComplexNumber::from_polar(1.0, 2.0 * PI * 4.0 * t / n as f64);
+            assert!((z.re() - y).abs() < 1e-12);
+            assert!((z - &expected).module() < 1e-12);
+        }
+        assert!(analytic.magnitude().values().all(|m| (m - 1.0).abs() < 1e-12));
+    }
+
+    #[test]
+    fn frequency_shift_shall_move_tone_to_new_frequency() {
+        let fs = 100.0;
+        let tone = ComplexSignal::from_samples(
+            &(0..100).map(|i| ComplexNumber::from_polar(1.0, 2.0 * PI * 10.0 * i as f64 / fs)).collect::<Vec<_>>(),
+            fs, 0.0);
+        let shifted = tone.frequency_shift(-10.0);
+
+        for y in shifted.samples() {
+            assert!((&y - &ComplexNumber::new(1.0, 0.0)).module() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn mixing_with_conjugate_shall_give_squared_module() {
+        let signal = ComplexSignal::from_iq(&[3.0, 1.0], &[4.0, -1.0], 1.0, 0.0);
+        let mixed = signal.mix(&signal.conj());
+
+        assert_eq!(mixed.real().values().collect::<Vec<f64>>(), vec![25.0, 2.0]);
+        assert_eq!(mixed.imag().values().collect::<Vec<f64>>(), vec![0.0, 0.0]);
+    }
+}
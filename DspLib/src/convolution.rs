@@ -0,0 +1,255 @@
+use crate::{convolve_with, ConvolutionMethod, DescreteSignal};
+
+/// Selects which part of convolution of signal (N samples) with FIR (L taps) is returned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConvolutionMode {
+    /// Whole convolution, N + L - 1 samples. Signal is padded with zeros on both sides.
+    Full,
+    /// N samples from the middle of full convolution, starting at sample (L - 1) / 2.
+    Same,
+    /// N - L + 1 samples computed without padding, the same as [`crate::fir_filter`].
+    Valid,
+    /// N samples of causal filter that starts with zeroed delay line.
+    Causal,
+    /// N samples of causal filter with delay line filled with the first sample, as if
+    /// input was constant before the signal started. It avoids start-up transient on signals
+    /// with DC offset.
+    CausalSteadyState,
+}
+
+/// Shift of output timestamps that compensates delay introduced by filter. Without
+/// compensation every output sample gets timestamp of the newest input sample it depends on.
+/// Delay is given in samples, timestamps are interpolated (or extrapolated outside of signal)
+/// from input timestamps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GroupDelay {
+    /// Timestamp of the newest input sample, as for real-time (causal) processing.
+    None,
+    /// Group delay of linear phase (symmetric or antisymmetric) FIR, (L - 1) / 2 samples.
+    /// For odd number of taps filtered signal stays aligned with input in time.
+    Linear,
+    /// Any delay in samples, e.g. L - 1 stamps output with the oldest tap as [`crate::fir_filter`] does.
+    Samples(f64),
+}
+
+/// FIR filtering with selectable output mode, timestamp alignment and algorithm.
+/// By default it returns [`ConvolutionMode::Same`] with [`GroupDelay::Linear`] compensation,
+/// calculated with [`ConvolutionMethod::Auto`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Convolution {
+    mode: ConvolutionMode,
+    group_delay: GroupDelay,
+    method: ConvolutionMethod,
+}
+
+impl Default for Convolution {
+    fn default() -> Self {
+        Self::new(ConvolutionMode::Same)
+    }
+}
+
+impl Convolution {
+    pub fn new(mode: ConvolutionMode) -> Self {
+        Self{mode, group_delay: GroupDelay::Linear, method: ConvolutionMethod::Auto}
+    }
+
+    pub fn set_mode(mut self, mode: ConvolutionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn set_group_delay(mut self, group_delay: GroupDelay) -> Self {
+        self.group_delay = group_delay;
+        self
+    }
+
+    pub fn set_method(mut self, method: ConvolutionMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    pub fn mode(&self) -> ConvolutionMode {
+        self.mode
+    }
+
+    pub fn group_delay(&self) -> GroupDelay {
+        self.group_delay
+    }
+
+    pub fn method(&self) -> ConvolutionMethod {
+        self.method
+    }
+
+    /// Group delay in samples for FIR with given number of taps.
+    pub fn delay_in_samples(&self, number_of_taps: usize) -> f64 {
+        match self.group_delay {
+            GroupDelay::None => 0.0,
+            GroupDelay::Linear => (number_of_taps.max(1) - 1) as f64 / 2.0,
+            GroupDelay::Samples(delay) => delay,
+        }
+    }
+
+    /// Filters signal with FIR taps. Output sample `y[n] = sum(h[i] * x[n - i])` is stamped with
+    /// timestamp of input sample `n` moved back by group delay. Panics when there are no taps.
+    pub fn filter(&self, signal: &DescreteSignal, fir: &[f64], output: &mut DescreteSignal) {
+        assert!(!fir.is_empty(), "FIR filter shall have at least one coefficient");
+        output.set_metadata(signal.metadata().clone());
+        if signal.is_empty() {
+            return;
+        }
+        let len = signal.len();
+        let taps = fir.len();
+        let range = match self.mode {
+            ConvolutionMode::Full => 0..len + taps - 1,
+            ConvolutionMode::Same => (taps - 1) / 2..(taps - 1) / 2 + len,
+            ConvolutionMode::Valid => taps - 1..len.max(taps - 1),
+            ConvolutionMode::Causal | ConvolutionMode::CausalSteadyState => 0..len,
+        };
+        let before_start = match self.mode {
+            ConvolutionMode::CausalSteadyState => signal[0].1,
+            _ => 0.0,
+        };
+        let delay = self.delay_in_samples(taps);
+
+        if self.method == ConvolutionMethod::Direct {
+            for n in range {
+                let mut y = 0.0;
+                for (i, h) in fir.iter().enumerate() {
+                    if i <= n {
+                        if let Some((_, x)) = signal.get_data().get(n - i) {
+                            y += h * x;
+                        }
+                    } else if before_start != 0.0 {
+                        y += h * before_start;
+                    }
+                }
+                output.push(time_at(signal, n as f64 - delay), y);
+            }
+        } else {
+            // samples before start are prepended, so full convolution is shifted by their count
+            let prefix = if before_start != 0.0 { taps - 1 } else { 0 };
+            let mut input = vec![before_start; prefix];
+            input.extend(signal.values());
+            let full = convolve_with(&input, fir, self.method);
+            for n in range {
+                output.push(time_at(signal, n as f64 - delay), full[n + prefix]);
+            }
+        }
+    }
+}
+
+/// Timestamp at fractional sample index, linearly interpolated between samples and
+/// extrapolated with sampling period outside of the signal.
+fn time_at(signal: &DescreteSignal, position: f64) -> f64 {
+    let last = signal.len() - 1;
+    if last == 0 {
+        return signal[0].0;
+    }
+    let i = (position.floor().max(0.0) as usize).min(last - 1);
+    let (t0, t1) = (signal[i].0, signal[i + 1].0);
+    let fraction = position - i as f64;
+    if fraction == 0.0 {
+        t0
+    } else if fraction == 1.0 {
+        t1
+    } else {
+        t0 + fraction * (t1 - t0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fir_filter;
+
+    fn ramp(len: usize) -> DescreteSignal {
+        DescreteSignal::new_from_vec((0..len).map(|i| (i as f64 * 0.5, 1.0 + i as f64)).collect())
+    }
+
+    #[test]
+    fn full_convolution_shall_be_longer_than_signal() {
+        let mut output = DescreteSignal::new();
+        Convolution::new(ConvolutionMode::Full).set_group_delay(GroupDelay::None)
+            .filter(&ramp(3), &[1.0, 10.0], &mut output);
+
+        assert_eq!(output.get_data(), &[(0.0, 1.0), (0.5, 12.0), (1.0, 23.0), (1.5, 30.0)]);
+    }
+
+    #[test]
+    fn same_convolution_shall_stay_aligned_with_input() {
+        let signal = ramp(5);
+        let mut output = DescreteSignal::new();
+        Convolution::default().filter(&signal, &[1.0, 2.0, 1.0], &mut output);
+
+        assert_eq!(output.times().collect::<Vec<f64>>(), signal.times().collect::<Vec<f64>>());
+        assert_eq!(output.values().collect::<Vec<f64>>(), vec![4.0, 8.0, 12.0, 16.0, 14.0]);
+    }
+
+    #[test]
+    fn valid_convolution_shall_match_fir_filter_with_oldest_tap_timestamps() {
+        let signal = ramp(10);
+        let fir = [0.1, 2.0, 10.0, -3.3];
+        let mut expected = DescreteSignal::new();
+        let mut output = DescreteSignal::new();
+
+        fir_filter(&signal, &fir, &mut expected);
+        Convolution::new(ConvolutionMode::Valid).set_group_delay(GroupDelay::Samples(3.0))
+            .filter(&signal, &fir, &mut output);
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn causal_modes_shall_differ_in_initial_conditions() {
+        let signal = DescreteSignal::new_from_vec(vec![(0.0, 2.0), (1.0, 2.0), (2.0, 2.0)]);
+        let fir = [0.25, 0.5, 0.25];
+        let mut zero_state = DescreteSignal::new();
+        let mut steady_state = DescreteSignal::new();
+
+        let causal = Convolution::new(ConvolutionMode::Causal).set_group_delay(GroupDelay::None);
+        causal.filter(&signal, &fir, &mut zero_state);
+        causal.set_mode(ConvolutionMode::CausalSteadyState).filter(&signal, &fir, &mut steady_state);
+        assert_eq!(zero_state.get_data(), &[(0.0, 0.5), (1.0, 1.5), (2.0, 2.0)]);
+        assert_eq!(steady_state.get_data(), &[(0.0, 2.0), (1.0, 2.0), (2.0, 2.0)]);
+    }
+
+    #[test]
+    fn group_delay_shall_shift_timestamps_by_fraction_of_sample() {
+        let mut output = DescreteSignal::new();
+        Convolution::new(ConvolutionMode::Causal).filter(&ramp(3), &[0.5, 0.5], &mut output);
+
+        assert_eq!(output.times().collect::<Vec<f64>>(), vec![-0.25, 0.25, 0.75]);
+    }
+
+    #[test]
+    fn valid_convolution_of_too_short_signal_shall_be_empty() {
+        let mut output = DescreteSignal::new();
+        Convolution::new(ConvolutionMode::Valid).filter(&ramp(2), &[1.0, 1.0, 1.0], &mut output);
+
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn every_method_shall_give_the_same_result_in_every_mode() {
+        let signal = DescreteSignal::new_from_vec((0..300).map(|i| (i as f64, 1.0 + (i as f64 * 0.3).sin())).collect());
+        let fir = (0..41).map(|i| (i as f64 * 0.2).cos() / 41.0).collect::<Vec<f64>>();
+        let modes = [ConvolutionMode::Full, ConvolutionMode::Same, ConvolutionMode::Valid,
+                     ConvolutionMode::Causal, ConvolutionMode::CausalSteadyState];
+        let methods = [ConvolutionMethod::Fft, ConvolutionMethod::OverlapAdd(64), ConvolutionMethod::OverlapSave(50)];
+
+        for mode in modes {
+            let mut expected = DescreteSignal::new();
+            Convolution::new(mode).set_method(ConvolutionMethod::Direct).filter(&signal, &fir, &mut expected);
+            for method in methods {
+                let mut output = DescreteSignal::new();
+                Convolution::new(mode).set_method(method).filter(&signal, &fir, &mut output);
+                assert_eq!(output.times().collect::<Vec<f64>>(), expected.times().collect::<Vec<f64>>());
+                for (y, e) in output.values().zip(expected.values()) {
+                    assert!((y - e).abs() < 1e-9, "{:?} {:?}: {} != {}", mode, method, y, e);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,551 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::{DescreteSignal, MultichannelSignal, SignalMetadata};
+
+/// Column of delimited text file, selected by its index or by its name in the header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CsvColumn {
+    Index(usize),
+    Name(String),
+}
+
+impl From<usize> for CsvColumn {
+    fn from(index: usize) -> Self {
+        CsvColumn::Index(index)
+    }
+}
+
+impl From<&str> for CsvColumn {
+    fn from(name: &str) -> Self {
+        CsvColumn::Name(name.to_string())
+    }
+}
+
+#[derive(Debug)]
+pub enum CsvError {
+    Io(std::io::Error),
+    /// Field that cannot be parsed as number. Lines and columns are counted from 1.
+    InvalidNumber { line: usize, column: usize, text: String },
+    /// Row that has fewer fields than required by selected columns.
+    MissingColumn { line: usize, column: usize },
+    /// Column name that is not present in the header.
+    UnknownColumn(String),
+    /// Signals written to one file shall have equal length.
+    ChannelLengthMismatch,
+    /// Delimiter and decimal separator are the same character.
+    SeparatorConflict(char),
+}
+
+impl CsvError {
+    /// Line of the file that caused the error, if error is related to one line.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            CsvError::InvalidNumber { line, .. } | CsvError::MissingColumn { line, .. } => Some(*line),
+            _ => None,
+        }
+    }
+}
+
+impl Display for CsvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CsvError::Io(e) => write!(f, "I/O error: {}", e),
+            CsvError::InvalidNumber { line, column, text } => {
+                write!(f, "line {}, column {}: '{}' is not a number", line, column, text)
+            }
+            CsvError::MissingColumn { line, column } => write!(f, "line {}: missing column {}", line, column),
+            CsvError::UnknownColumn(name) => write!(f, "column '{}' not found in header", name),
+            CsvError::ChannelLengthMismatch => write!(f, "all channels shall have the same number of samples"),
+            CsvError::SeparatorConflict(c) => write!(f, "delimiter and decimal separator shall differ, both are '{}'", c),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+impl From<std::io::Error> for CsvError {
+    fn from(e: std::io::Error) -> Self {
+        CsvError::Io(e)
+    }
+}
+
+/// Reader of delimited text (CSV, TSV) files. Every selected value column becomes one signal.
+/// Empty lines and lines starting with `#` are skipped, fields can be quoted with `"`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CsvReader {
+    delimiter: char,
+    decimal_separator: char,
+    header_rows: usize,
+    time_column: Option<CsvColumn>,
+    value_columns: Vec<CsvColumn>,
+    time_scale: f64,
+    value_scale: f64,
+    sample_rate: f64,
+}
+
+impl Default for CsvReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CsvReader {
+    /// Comma separated file with time in first column and no header.
+    pub fn new() -> Self {
+        Self{
+            delimiter: ',',
+            decimal_separator: '.',
+            header_rows: 0,
+            time_column: Some(CsvColumn::Index(0)),
+            value_columns: vec![],
+            time_scale: 1.0,
+            value_scale: 1.0,
+            sample_rate: 1.0,
+        }
+    }
+
+    /// Tab separated file with time in first column and no header.
+    pub fn tsv() -> Self {
+        Self::new().set_delimiter('\t')
+    }
+
+    pub fn set_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn set_decimal_separator(mut self, separator: char) -> Self {
+        self.decimal_separator = separator;
+        self
+    }
+
+    /// Number of rows at the beginning of the file that do not hold data. Column names
+    /// are taken from the last of them.
+    pub fn set_header_rows(mut self, rows: usize) -> Self {
+        self.header_rows = rows;
+        self
+    }
+
+    pub fn set_time_column<C: Into<CsvColumn>>(mut self, column: C) -> Self {
+        self.time_column = Some(column.into());
+        self
+    }
+
+    /// File has no time column, timestamps are calculated from sampling rate instead.
+    pub fn set_sample_rate(mut self, rate: f64) -> Self {
+        self.time_column = None;
+        self.sample_rate = rate;
+        self
+    }
+
+    /// Columns that hold signal values. By default all columns except time are read.
+    pub fn set_value_columns<C: Into<CsvColumn> + Clone>(mut self, columns: &[C]) -> Self {
+        self.value_columns = columns.iter().cloned().map(Into::into).collect();
+        self
+    }
+
+    /// Factor converting timestamps to seconds, e.g. 1e-3 for data in milliseconds. Without it
+    /// time unit of signals is taken from header of time column, e.g. `time [ms]`.
+    pub fn set_time_scale(mut self, scale: f64) -> Self {
+        self.time_scale = scale;
+        self
+    }
+
+    /// Factor that every value is multiplied by.
+    pub fn set_value_scale(mut self, scale: f64) -> Self {
+        self.value_scale = scale;
+        self
+    }
+
+    pub fn read<P: AsRef<Path>>(&self, path: P) -> Result<Vec<DescreteSignal>, CsvError> {
+        let mut signals = self.read_from(BufReader::new(File::open(&path)?))?;
+        let source = path.as_ref().display().to_string();
+        for s in signals.iter_mut() {
+            s.set_metadata(s.metadata().clone().set_property("source", &source));
+        }
+        Ok(signals)
+    }
+
+    pub fn read_multichannel<P: AsRef<Path>>(&self, path: P) -> Result<MultichannelSignal, CsvError> {
+        Ok(MultichannelSignal::from_signals(&self.read(path)?))
+    }
+
+    pub fn read_str(&self, text: &str) -> Result<Vec<DescreteSignal>, CsvError> {
+        self.read_from(text.as_bytes())
+    }
+
+    /// Fails when delimiter equals decimal separator.
+    pub fn read_from<R: BufRead>(&self, reader: R) -> Result<Vec<DescreteSignal>, CsvError> {
+        if self.delimiter == self.decimal_separator {
+            return Err(CsvError::SeparatorConflict(self.delimiter));
+        }
+        let mut header = vec![];
+        let mut columns: Option<(Option<usize>, Vec<usize>)> = None;
+        let mut signals = vec![];
+        let mut row = 0;
+
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line_number = i + 1;
+            if row < self.header_rows {
+                header = self.split(&line);
+                row += 1;
+                continue;
+            }
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            let fields = self.split(&line);
+            let (time_column, value_columns) = match &columns {
+                Some(c) => c,
+                None => {
+                    let c = self.resolve_columns(&header, fields.len())?;
+                    let time_unit = match c.0 {
+                        Some(_) if self.time_scale != 1.0 => Some("s"),
+                        Some(time) => header.get(time).and_then(|title| split_unit(title).1),
+                        None => None,
+                    };
+                    signals = value_columns_metadata(&header, &c.1, time_unit)
+                        .into_iter()
+                        .map(|meta| {
+                            let mut s = DescreteSignal::new();
+                            s.set_metadata(meta);
+                            s
+                        })
+                        .collect::<Vec<DescreteSignal>>();
+                    columns.insert(c)
+                }
+            };
+
+            let t = match time_column {
+                Some(c) => self.parse_field(&fields, *c, line_number)? * self.time_scale,
+                None => (row - self.header_rows) as f64 / self.sample_rate,
+            };
+            for (signal, c) in signals.iter_mut().zip(value_columns) {
+                signal.push(t, self.parse_field(&fields, *c, line_number)? * self.value_scale);
+            }
+            row += 1;
+        }
+
+        if self.time_column.is_none() {
+            for s in signals.iter_mut() {
+                s.set_metadata(s.metadata().clone().set_sample_rate(self.sample_rate));
+            }
+        }
+        Ok(signals)
+    }
+
+    fn resolve_columns(&self, header: &[String], fields: usize) -> Result<(Option<usize>, Vec<usize>), CsvError> {
+        let resolve = |column: &CsvColumn| match column {
+            CsvColumn::Index(i) => Ok(*i),
+            CsvColumn::Name(name) => header.iter()
+                .position(|h| h == name)
+                .ok_or_else(|| CsvError::UnknownColumn(name.clone())),
+        };
+        let time = self.time_column.as_ref().map(resolve).transpose()?;
+        let values = if self.value_columns.is_empty() {
+            (0..fields).filter(|c| Some(*c) != time).collect()
+        } else {
+            self.value_columns.iter().map(resolve).collect::<Result<Vec<usize>, CsvError>>()?
+        };
+
+        Ok((time, values))
+    }
+
+    fn parse_field(&self, fields: &[String], column: usize, line: usize) -> Result<f64, CsvError> {
+        let text = fields.get(column).ok_or(CsvError::MissingColumn{line, column: column + 1})?;
+        let normalized = if self.decimal_separator == '.' {
+            text.trim().to_string()
+        } else {
+            text.trim().replace(self.decimal_separator, ".")
+        };
+        normalized.parse::<f64>()
+            .map_err(|_| CsvError::InvalidNumber{line, column: column + 1, text: text.clone()})
+    }
+
+    fn split(&self, line: &str) -> Vec<String> {
+        let mut fields = vec![];
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut chars = line.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c == '"' {
+                if quoted && chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    quoted = !quoted;
+                }
+            } else if c == self.delimiter && !quoted {
+                fields.push(std::mem::take(&mut field));
+            } else {
+                field.push(c);
+            }
+        }
+        fields.push(field);
+        fields
+    }
+}
+
+/// Metadata for every value column. Header names like `voltage [V]` are split into
+/// signal name and unit.
+fn value_columns_metadata(header: &[String], columns: &[usize], time_unit: Option<&str>) -> Vec<SignalMetadata> {
+    columns.iter().map(|c| {
+        let mut meta = SignalMetadata::new().set_property("column", &(c + 1).to_string());
+        if let Some(unit) = time_unit {
+            meta = meta.set_time_unit(unit);
+        }
+        if let Some(title) = header.get(*c) {
+            let (name, unit) = split_unit(title);
+            meta = meta.set_name(name);
+            if let Some(unit) = unit {
+                meta = meta.set_value_unit(unit);
+            }
+        }
+        meta
+    }).collect()
+}
+
+fn split_unit(title: &str) -> (&str, Option<&str>) {
+    let title = title.trim();
+    match (title.rfind('['), title.ends_with(']')) {
+        (Some(open), true) => (title[..open].trim(), Some(&title[open + 1..title.len() - 1])),
+        _ => (title, None),
+    }
+}
+
+/// Writer of delimited text files. First column holds timestamps of the first signal,
+/// next columns hold values of all signals.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CsvWriter {
+    delimiter: char,
+    decimal_separator: char,
+    header: bool,
+    precision: Option<usize>,
+}
+
+impl Default for CsvWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CsvWriter {
+    pub fn new() -> Self {
+        Self{delimiter: ',', decimal_separator: '.', header: true, precision: None}
+    }
+
+    pub fn tsv() -> Self {
+        Self::new().set_delimiter('\t')
+    }
+
+    pub fn set_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Decimal separator of written numbers. It shall differ from delimiter.
+    pub fn set_decimal_separator(mut self, separator: char) -> Self {
+        self.decimal_separator = separator;
+        self
+    }
+
+    /// Whether header row with names and units from metadata shall be written.
+    pub fn set_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Number of digits after decimal point, by default shortest exact representation is used.
+    pub fn set_precision(mut self, digits: usize) -> Self {
+        self.precision = Some(digits);
+        self
+    }
+
+    pub fn write<P: AsRef<Path>>(&self, path: P, signals: &[DescreteSignal]) -> Result<(), CsvError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer, signals)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn write_multichannel<P: AsRef<Path>>(&self, path: P, signal: &MultichannelSignal) -> Result<(), CsvError> {
+        self.write(path, &signal.to_signals())
+    }
+
+    /// Fails when signals differ in length or delimiter equals decimal separator.
+    pub fn write_to<W: Write>(&self, mut writer: W, signals: &[DescreteSignal]) -> Result<(), CsvError> {
+        if self.delimiter == self.decimal_separator {
+            return Err(CsvError::SeparatorConflict(self.delimiter));
+        }
+        let len = signals.first().map_or(0, DescreteSignal::len);
+        if signals.iter().any(|s| s.len() != len) {
+            return Err(CsvError::ChannelLengthMismatch);
+        }
+        let delimiter = self.delimiter.to_string();
+
+        if self.header {
+            let time_unit = signals.first().and_then(|s| s.metadata().time_unit());
+            let mut titles = vec![column_title("time", time_unit)];
+            for (i, s) in signals.iter().enumerate() {
+                let name = s.metadata().name().map(str::to_string).unwrap_or_else(|| format!("signal {}", i + 1));
+                titles.push(column_title(&name, s.metadata().value_unit()));
+            }
+            writeln!(writer, "{}", titles.join(&delimiter))?;
+        }
+        for i in 0..len {
+            let mut fields = vec![self.format_number(signals[0][i].0)];
+            fields.extend(signals.iter().map(|s| self.format_number(s[i].1)));
+            writeln!(writer, "{}", fields.join(&delimiter))?;
+        }
+
+        Ok(())
+    }
+
+    fn format_number(&self, x: f64) -> String {
+        let text = match self.precision {
+            Some(digits) => format!("{:.*}", digits, x),
+            None => format!("{}", x),
+        };
+        if self.decimal_separator == '.' {
+            text
+        } else {
+            text.replace('.', &self.decimal_separator.to_string())
+        }
+    }
+}
+
+fn column_title(name: &str, unit: Option<&str>) -> String {
+    match unit {
+        Some(unit) => format!("{} [{}]", name, unit),
+        None => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reader_shall_read_time_and_all_value_columns() {
+        let text = "0.0,1.0,10.0\n0.5,2.0,20.0\n1.0,3.0,30.0\n";
+        let signals = CsvReader::new().read_str(text).unwrap();
+
+        assert_eq!(signals.len(), 2);
+        assert_eq!(signals[0].get_data(), &[(0.0, 1.0), (0.5, 2.0), (1.0, 3.0)]);
+        assert_eq!(signals[1].get_data(), &[(0.0, 10.0), (0.5, 20.0), (1.0, 30.0)]);
+    }
+
+    #[test]
+    fn reader_shall_use_header_names_units_and_column_selection() {
+        let text = "Scope export\nvoltage [V];time [ms];current [A]\n1,5;0;0,25\n2,5;2;0,5\n";
+        let signals = CsvReader::new()
+            .set_delimiter(';')
+            .set_decimal_separator(',')
+            .set_header_rows(2)
+            .set_time_column("time [ms]")
+            .set_value_columns(&["voltage [V]"])
+            .set_time_scale(1e-3)
+            .read_str(text)
+            .unwrap();
+
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].get_data(), &[(0.0, 1.5), (0.002, 2.5)]);
+        assert_eq!(signals[0].metadata().name(), Some("voltage"));
+        assert_eq!(signals[0].metadata().value_unit(), Some("V"));
+        assert_eq!(signals[0].metadata().time_unit(), Some("s"));
+    }
+
+    #[test]
+    fn time_unit_shall_be_set_only_when_known() {
+        let signals = CsvReader::new().read_str("0,1\n1,2\n").unwrap();
+        assert_eq!(signals[0].metadata().time_unit(), None);
+
+        let signals = CsvReader::new().set_header_rows(1).read_str("t [ms],x\n0,1\n1,2\n").unwrap();
+        assert_eq!(signals[0].metadata().time_unit(), Some("ms"));
+
+        let signals = CsvReader::new().set_sample_rate(2.0).read_str("1\n2\n").unwrap();
+        assert_eq!(signals[0].metadata().time_unit(), None);
+    }
+
+    #[test]
+    fn equal_delimiter_and_decimal_separator_shall_be_rejected() {
+        let err = CsvReader::new().set_decimal_separator(',').read_str("0,1\n").unwrap_err();
+        assert!(matches!(err, CsvError::SeparatorConflict(',')));
+
+        let signals = [DescreteSignal::new_from_vec(vec![(0.0, 1.5)])];
+        let err = CsvWriter::new().set_delimiter(';').set_decimal_separator(';').write_to(vec![], &signals).unwrap_err();
+        assert!(matches!(err, CsvError::SeparatorConflict(';')));
+    }
+
+    #[test]
+    fn reader_without_time_column_shall_use_sample_rate() {
+        let text = "1\t-1\n2\t-2\n\n# comment\n3\t-3\n";
+        let signals = CsvReader::tsv().set_sample_rate(10.0).set_value_scale(2.0).read_str(text).unwrap();
+
+        assert_eq!(signals[1].get_data(), &[(0.0, -2.0), (0.1, -4.0), (0.2, -6.0)]);
+        assert_eq!(signals[0].metadata().sample_rate(), Some(10.0));
+    }
+
+    #[test]
+    fn reader_shall_report_line_of_malformed_row() {
+        let text = "t,x\n0,1\n1,abc\n";
+        let err = CsvReader::new().set_header_rows(1).read_str(text).unwrap_err();
+
+        assert_eq!(err.line(), Some(3));
+        assert!(matches!(err, CsvError::InvalidNumber{line: 3, column: 2, ref text} if text == "abc"));
+
+        let err = CsvReader::new().read_str("0,1,2\n1,2\n").unwrap_err();
+        assert!(matches!(err, CsvError::MissingColumn{line: 2, column: 3}));
+
+        let err = CsvReader::new().set_header_rows(1).set_time_column("missing").read_str("t,x\n0,1\n").unwrap_err();
+        assert!(matches!(err, CsvError::UnknownColumn(_)));
+    }
+
+    #[test]
+    fn reader_shall_handle_quoted_fields() {
+        let text = "\"time, s\",\"value \"\"x\"\"\"\n\"0\",\"1.5\"\n";
+        let signals = CsvReader::new().set_header_rows(1).read_str(text).unwrap();
+
+        assert_eq!(signals[0].get_data(), &[(0.0, 1.5)]);
+        assert_eq!(signals[0].metadata().name(), Some("value \"x\""));
+    }
+
+    #[test]
+    fn written_signals_shall_be_read_back() {
+        let mut first = DescreteSignal::new_from_vec(vec![(0.0, 1.25), (0.1, -3.5)]);
+        first.set_metadata(SignalMetadata::new().set_name("pressure").set_value_unit("Pa").set_time_unit("s"));
+        let second = DescreteSignal::new_from_vec(vec![(0.0, 7.0), (0.1, 8.0)]);
+        let mut bytes = vec![];
+
+        CsvWriter::new().set_delimiter(';').set_decimal_separator(',').write_to(&mut bytes, &[first.clone(), second.clone()]).unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+        assert_eq!(text, "time [s];pressure [Pa];signal 2\n0;1,25;7\n0,1;-3,5;8\n");
+
+        let signals = CsvReader::new().set_delimiter(';').set_decimal_separator(',').set_header_rows(1).read_str(&text).unwrap();
+        assert_eq!(signals[0].get_data(), first.get_data());
+        assert_eq!(signals[1].get_data(), second.get_data());
+        assert_eq!(signals[0].metadata().value_unit(), Some("Pa"));
+    }
+
+    #[test]
+    fn writer_shall_respect_precision_and_header_options() {
+        let signal = DescreteSignal::new_from_vec(vec![(0.0, 1.0 / 3.0)]);
+        let mut bytes = vec![];
+
+        CsvWriter::tsv().set_header(false).set_precision(3).write_to(&mut bytes, &[signal]).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(), "0.000\t0.333\n");
+    }
+
+    #[test]
+    fn writer_shall_reject_signals_of_different_length() {
+        let signals = [DescreteSignal::new_from_vec(vec![(0.0, 0.0)]), DescreteSignal::new()];
+
+        assert!(matches!(CsvWriter::new().write_to(vec![], &signals), Err(CsvError::ChannelLengthMismatch)));
+    }
+}
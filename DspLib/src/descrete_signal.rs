@@ -1,35 +1,89 @@
-use std::ops::{Add, Index};
+use std::ops::{Add, Index, IndexMut};
+use std::slice::SliceIndex;
 
+use crate::SignalMetadata;
+
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct DescreteSignal {
     data: Vec<(f64, f64)>,
+    metadata: SignalMetadata,
 }
 
-impl Clone for DescreteSignal {
-    fn clone(&self) -> Self {
-        let mut data = vec![];
-        for d in self.data.iter() {
-            data.push(*d);
-        }
-        DescreteSignal{data}
+impl<I: SliceIndex<[(f64, f64)]>> Index<I> for DescreteSignal {
+
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &Self::Output {
+        &self.data[index]
     }
 }
 
-impl Index<usize> for DescreteSignal {
+impl<I: SliceIndex<[(f64, f64)]>> IndexMut<I> for DescreteSignal {
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        &mut self.data[index]
+    }
+}
 
-    type Output = (f64, f64);
+impl IntoIterator for DescreteSignal {
+    type Item = (f64, f64);
+    type IntoIter = std::vec::IntoIter<(f64, f64)>;
 
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.data[index]
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a DescreteSignal {
+    type Item = &'a (f64, f64);
+    type IntoIter = std::slice::Iter<'a, (f64, f64)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut DescreteSignal {
+    type Item = &'a mut (f64, f64);
+    type IntoIter = std::slice::IterMut<'a, (f64, f64)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter_mut()
+    }
+}
+
+impl FromIterator<(f64, f64)> for DescreteSignal {
+    fn from_iter<T: IntoIterator<Item = (f64, f64)>>(iter: T) -> Self {
+        Self{data: iter.into_iter().collect(), metadata: SignalMetadata::default()}
+    }
+}
+
+impl Extend<(f64, f64)> for DescreteSignal {
+    fn extend<T: IntoIterator<Item = (f64, f64)>>(&mut self, iter: T) {
+        self.data.extend(iter);
+    }
+}
+
+impl<'a> Extend<&'a (f64, f64)> for DescreteSignal {
+    fn extend<T: IntoIterator<Item = &'a (f64, f64)>>(&mut self, iter: T) {
+        self.data.extend(iter);
     }
 }
 
 impl DescreteSignal {
     pub fn new() -> Self {
-        Self{data: Vec::new()}
+        Self{data: Vec::new(), metadata: SignalMetadata::default()}
     }
 
     pub fn new_from_vec(data: Vec<(f64, f64)>) -> Self {
-        Self{data}
+        Self{data, metadata: SignalMetadata::default()}
+    }
+
+    pub fn metadata(&self) -> &SignalMetadata {
+        &self.metadata
+    }
+
+    pub fn set_metadata(&mut self, metadata: SignalMetadata) {
+        self.metadata = metadata;
     }
 
     pub fn push(&mut self, x: f64, y: f64) {
@@ -44,6 +98,33 @@ impl DescreteSignal {
         self.data.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, (f64, f64)> {
+        self.data.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, (f64, f64)> {
+        self.data.iter_mut()
+    }
+
+    /// Iterator over timestamps of all samples.
+    pub fn times(&self) -> impl Iterator<Item = f64> + '_ {
+        self.data.iter().map(|(x, _)| *x)
+    }
+
+    /// Iterator over values of all samples.
+    pub fn values(&self) -> impl Iterator<Item = f64> + '_ {
+        self.data.iter().map(|(_, y)| *y)
+    }
+
+    /// Mutable iterator over values of all samples, timestamps stay untouched.
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut f64> + '_ {
+        self.data.iter_mut().map(|(_, y)| y)
+    }
+
     pub fn clear(&mut self) {
         self.data = vec![];
     }
@@ -106,6 +187,7 @@ impl Add<&DescreteSignal> for &DescreteSignal {
 
     fn add(self, rhs: &DescreteSignal) -> Self::Output {
         let mut ret = DescreteSignal::new();
+        ret.set_metadata(self.metadata.merge(&rhs.metadata));
         let mut l = 0;
         let mut r = 0;
 
@@ -146,8 +228,8 @@ mod tests {
 
     #[test]
     fn adding_shall_extend_signal_with_data_after_last_stamp() {
-        let first = DescreteSignal{data: vec![(0.1, 0.0), (0.2, 300.0), (0.3, 45.22)]};
-        let second = DescreteSignal{data: vec![(0.5, 0.1), (0.51, 11.3), (0.52, 4.78)]};
+        let first = DescreteSignal::new_from_vec(vec![(0.1, 0.0), (0.2, 300.0), (0.3, 45.22)]);
+        let second = DescreteSignal::new_from_vec(vec![(0.5, 0.1), (0.51, 11.3), (0.52, 4.78)]);
         let sum = vec![(0.1, 0.0), (0.2, 300.0), (0.3, 45.22), (0.5, 0.1), (0.51, 11.3), (0.52, 4.78)];
         
         assert_eq!((&first + &second).data, sum);
@@ -155,8 +237,8 @@ mod tests {
 
     #[test]
     fn check_adding_to_signals_with_equal_stamps() {
-        let first = DescreteSignal{data: vec![(0.0, 0.0), (0.1, 300.0), (1.5, 45.22)]};
-        let second = DescreteSignal{data: vec![(0.0, 0.1), (0.1, 11.3), (1.5, 4.78)]};
+        let first = DescreteSignal::new_from_vec(vec![(0.0, 0.0), (0.1, 300.0), (1.5, 45.22)]);
+        let second = DescreteSignal::new_from_vec(vec![(0.0, 0.1), (0.1, 11.3), (1.5, 4.78)]);
         let sum = vec![(0.0, 0.1), (0.1, 311.3), (1.5, 50.0)];
         
         assert_eq!((&first + &second).data, sum);
@@ -164,8 +246,8 @@ mod tests {
 
     #[test]
     fn check_adding_to_signals_with_stamps_between() {
-        let first = DescreteSignal{data: vec![(0.0, 0.0), (1.0, 10.0), (2.25, 22.5), (2.5, 25.0), (2.75, 27.5)]};
-        let second = DescreteSignal{data: vec![(1.0, 2.0), (2.0, 4.0), (3.0, 6.0)]};
+        let first = DescreteSignal::new_from_vec(vec![(0.0, 0.0), (1.0, 10.0), (2.25, 22.5), (2.5, 25.0), (2.75, 27.5)]);
+        let second = DescreteSignal::new_from_vec(vec![(1.0, 2.0), (2.0, 4.0), (3.0, 6.0)]);
         let sum = vec![(0.0, 0.0), (1.0, 12.0), (2.0, 24.0), (2.25, 27.0), (2.5, 30.0), (2.75, 33.0), (3.0, 6.0)];
         
         assert_eq!((&first + &second).data, sum);
@@ -173,8 +255,8 @@ mod tests {
 
     #[test]
     fn check_adding_mixed_data() {
-        let first = DescreteSignal{data: vec![(0.0, 1.0), (1.0, 11.0), (2.0, 21.0), (3.0, 31.0), (4.0, 41.0)]};
-        let second = DescreteSignal{data: vec![(0.0, 2.0), (0.1, 3.0), (0.2, 4.0), (3.3, 35.0), (3.6, 38.0), (4.1, 43.0)]};
+        let first = DescreteSignal::new_from_vec(vec![(0.0, 1.0), (1.0, 11.0), (2.0, 21.0), (3.0, 31.0), (4.0, 41.0)]);
+        let second = DescreteSignal::new_from_vec(vec![(0.0, 2.0), (0.1, 3.0), (0.2, 4.0), (3.3, 35.0), (3.6, 38.0), (4.1, 43.0)]);
         let sum = vec![(0.0, 3.0), (0.1, 5.0), (0.2, 7.0), (1.0, 23.0), (2.0, 43.0), (3.0, 63.00000000000001), (3.3, 69.0), (3.6, 75.0), (4.0, 83.0), (4.1, 43.0)];
         
         assert_eq!((&first + &second).data, sum);
@@ -182,7 +264,7 @@ mod tests {
 
     #[test]
     fn clear_should_delete_all_data() {
-        let mut signal = DescreteSignal{data: vec![(0.0, 0.0), (1.0, 10.0), (2.25, 22.5), (2.5, 25.0), (2.75, 27.5)]};
+        let mut signal = DescreteSignal::new_from_vec(vec![(0.0, 0.0), (1.0, 10.0), (2.25, 22.5), (2.5, 25.0), (2.75, 27.5)]);
         assert_ne!(signal.data.len(), 0);
         signal.clear();
         assert_eq!(signal.data, vec![]);
@@ -213,4 +295,74 @@ mod tests {
         
         assert_eq!(signal.get_sampling_period(), ts);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn descrete_signal_shall_be_iterable_by_reference_and_value() {
+        let data = vec![(0.0, 1.0), (1.0, 2.0), (2.0, 3.0)];
+        let signal = DescreteSignal::new_from_vec(data.clone());
+
+        assert_eq!((&signal).into_iter().copied().collect::<Vec<_>>(), data);
+        assert_eq!(signal.iter().count(), data.len());
+        assert_eq!(signal.into_iter().collect::<Vec<_>>(), data);
+    }
+
+    #[test]
+    fn iterating_by_mutable_reference_shall_modify_samples() {
+        let mut signal = DescreteSignal::new_from_vec(vec![(0.0, 1.0), (1.0, 2.0)]);
+        for (_, y) in &mut signal {
+            *y *= 10.0;
+        }
+        signal[0].0 = -1.0;
+
+        assert_eq!(signal.data, vec![(-1.0, 10.0), (1.0, 20.0)]);
+    }
+
+    #[test]
+    fn descrete_signal_shall_be_collectable_and_extendable() {
+        let mut signal: DescreteSignal = (0..3).map(|i| (i as f64, 2.0 * i as f64)).collect();
+        signal.extend(vec![(3.0, 6.0)]);
+        signal.extend(&[(4.0, 8.0)]);
+
+        assert_eq!(signal.data, vec![(0.0, 0.0), (1.0, 2.0), (2.0, 4.0), (3.0, 6.0), (4.0, 8.0)]);
+    }
+
+    #[test]
+    fn range_index_shall_return_slice_of_samples() {
+        let signal = DescreteSignal::new_from_vec(vec![(0.0, 1.0), (1.0, 2.0), (2.0, 3.0), (3.0, 4.0)]);
+
+        assert_eq!(&signal[1..3], &[(1.0, 2.0), (2.0, 3.0)]);
+        assert_eq!(&signal[..1], &[(0.0, 1.0)]);
+        assert_eq!(signal[2..].len(), 2);
+    }
+
+    #[test]
+    fn times_and_values_shall_iterate_over_separate_components() {
+        let mut signal = DescreteSignal::new_from_vec(vec![(0.5, 1.0), (1.5, -2.0)]);
+
+        assert_eq!(signal.times().collect::<Vec<_>>(), vec![0.5, 1.5]);
+        assert_eq!(signal.values().collect::<Vec<_>>(), vec![1.0, -2.0]);
+        signal.values_mut().for_each(|y| *y = y.abs());
+        assert_eq!(signal.data, vec![(0.5, 1.0), (1.5, 2.0)]);
+    }
+
+    #[test]
+    fn adding_shall_merge_metadata_of_both_signals() {
+        let mut first = DescreteSignal::new_from_vec(vec![(0.0, 1.0), (1.0, 2.0)]);
+        let mut second = DescreteSignal::new_from_vec(vec![(0.0, 1.0), (1.0, 2.0)]);
+        first.set_metadata(SignalMetadata::new().set_name("first"));
+        second.set_metadata(SignalMetadata::new().set_name("second").set_value_unit("V"));
+        let sum = &first + &second;
+
+        assert_eq!(sum.metadata().name(), Some("first"));
+        assert_eq!(sum.metadata().value_unit(), Some("V"));
+    }
+
+    #[test]
+    fn default_signal_shall_be_empty_and_equal_to_new() {
+        let signal = DescreteSignal::default();
+
+        assert!(signal.is_empty());
+        assert_eq!(signal, DescreteSignal::new());
+        assert_ne!(signal, DescreteSignal::new_from_vec(vec![(0.0, 0.0)]));
+    }
+}
@@ -0,0 +1,396 @@
+use std::f64::consts::{LN_2, PI};
+use std::fmt::Display;
+
+use crate::{Biquad, DescreteSignal, SosFilter, StreamFilter};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EqError {
+    InvalidSampleRate(f64),
+    /// Frequency shall be between 0 and Nyquist frequency.
+    InvalidFrequency { frequency: f64, nyquist: f64 },
+    /// Q, bandwidth or slope shall be positive.
+    InvalidWidth(EqWidth),
+    /// Slope can be used only with shelving filters.
+    SlopeNotSupported(EqFilterType),
+    InvalidGain(f64),
+}
+
+impl Display for EqError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EqError::InvalidSampleRate(rate) => write!(f, "invalid sampling rate {}", rate),
+            EqError::InvalidFrequency{frequency, nyquist} =>
+                write!(f, "frequency {} shall be between 0 and Nyquist frequency {}", frequency, nyquist),
+            EqError::InvalidWidth(width) => write!(f, "band width {:?} shall be positive", width),
+            EqError::SlopeNotSupported(filter_type) => write!(f, "slope cannot be used with {:?} filter", filter_type),
+            EqError::InvalidGain(gain) => write!(f, "invalid gain {} dB", gain),
+        }
+    }
+}
+
+impl std::error::Error for EqError {}
+
+/// Type of biquad from Audio EQ Cookbook (R. Bristow-Johnson).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EqFilterType {
+    LowPass,
+    HighPass,
+    /// Band-pass with 0 dB gain at center frequency.
+    BandPass,
+    Notch,
+    /// Unit gain, phase goes from 0 to -2π with -π at center frequency.
+    AllPass,
+    /// Boost or cut around center frequency.
+    Peaking,
+    /// Boost or cut below frequency, gain at the frequency is half of gain in dB.
+    LowShelf,
+    /// Boost or cut above frequency, gain at the frequency is half of gain in dB.
+    HighShelf,
+}
+
+/// Width of EQ band.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EqWidth {
+    /// Quality factor.
+    Q(f64),
+    /// Bandwidth in octaves, between -3 dB points (or midpoint gain points of peaking filter).
+    Octaves(f64),
+    /// Shelf slope, 1 is the steepest slope without overshoot. Only for shelving filters.
+    Slope(f64),
+}
+
+/// Single band of equalizer: filter type, frequency in Hz, width and gain in dB. Gain is used only
+/// by peaking and shelving filters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EqBand {
+    filter_type: EqFilterType,
+    frequency: f64,
+    width: EqWidth,
+    gain: f64,
+}
+
+impl EqBand {
+    /// Band with Q of `1/sqrt(2)` (Butterworth response of pass filters) and 0 dB gain.
+    pub fn new(filter_type: EqFilterType, frequency: f64) -> Self {
+        Self{filter_type, frequency, width: EqWidth::Q(std::f64::consts::FRAC_1_SQRT_2), gain: 0.0}
+    }
+
+    pub fn peaking(frequency: f64, q: f64, gain: f64) -> Self {
+        Self::new(EqFilterType::Peaking, frequency).set_width(EqWidth::Q(q)).set_gain(gain)
+    }
+
+    pub fn low_shelf(frequency: f64, slope: f64, gain: f64) -> Self {
+        Self::new(EqFilterType::LowShelf, frequency).set_width(EqWidth::Slope(slope)).set_gain(gain)
+    }
+
+    pub fn high_shelf(frequency: f64, slope: f64, gain: f64) -> Self {
+        Self::new(EqFilterType::HighShelf, frequency).set_width(EqWidth::Slope(slope)).set_gain(gain)
+    }
+
+    pub fn set_width(mut self, width: EqWidth) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Gain in dB.
+    pub fn set_gain(mut self, gain: f64) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    pub fn filter_type(&self) -> EqFilterType {
+        self.filter_type
+    }
+
+    pub fn frequency(&self) -> f64 {
+        self.frequency
+    }
+
+    pub fn width(&self) -> EqWidth {
+        self.width
+    }
+
+    pub fn gain(&self) -> f64 {
+        self.gain
+    }
+
+    /// Normalized coefficients `([b0, b1, b2], [1, a1, a2])`. Frequency shall be between 0 and
+    /// Nyquist frequency, width shall be positive and slope can be used only by shelving filters.
+    pub fn coefficients(&self, sample_rate: f64) -> Result<([f64; 3], [f64; 3]), EqError> {
+        if !(sample_rate > 0.0 && sample_rate.is_finite()) {
+            return Err(EqError::InvalidSampleRate(sample_rate));
+        }
+        let nyquist = sample_rate / 2.0;
+        if !(self.frequency > 0.0 && self.frequency < nyquist) {
+            return Err(EqError::InvalidFrequency{frequency: self.frequency, nyquist});
+        }
+        if !self.gain.is_finite() {
+            return Err(EqError::InvalidGain(self.gain));
+        }
+        let (EqWidth::Q(width) | EqWidth::Octaves(width) | EqWidth::Slope(width)) = self.width;
+        if !(width > 0.0 && width.is_finite()) {
+            return Err(EqError::InvalidWidth(self.width));
+        }
+        if matches!(self.width, EqWidth::Slope(_)) && !matches!(self.filter_type, EqFilterType::LowShelf | EqFilterType::HighShelf) {
+            return Err(EqError::SlopeNotSupported(self.filter_type));
+        }
+        let a = 10f64.powf(self.gain / 40.0);
+        let w0 = 2.0 * PI * self.frequency / sample_rate;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = match self.width {
+            EqWidth::Q(q) => sin / (2.0 * q),
+            EqWidth::Octaves(octaves) => sin * (LN_2 / 2.0 * octaves * w0 / sin).sinh(),
+            EqWidth::Slope(slope) => sin / 2.0 * ((a + 1.0 / a) * (1.0 / slope - 1.0) + 2.0).max(0.0).sqrt(),
+        };
+        let shelf = 2.0 * a.sqrt() * alpha;
+
+        let (b, a) = match self.filter_type {
+            EqFilterType::LowPass => ([(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0], [1.0 + alpha, -2.0 * cos, 1.0 - alpha]),
+            EqFilterType::HighPass => ([(1.0 + cos) / 2.0, -1.0 - cos, (1.0 + cos) / 2.0], [1.0 + alpha, -2.0 * cos, 1.0 - alpha]),
+            EqFilterType::BandPass => ([alpha, 0.0, -alpha], [1.0 + alpha, -2.0 * cos, 1.0 - alpha]),
+            EqFilterType::Notch => ([1.0, -2.0 * cos, 1.0], [1.0 + alpha, -2.0 * cos, 1.0 - alpha]),
+            EqFilterType::AllPass => ([1.0 - alpha, -2.0 * cos, 1.0 + alpha], [1.0 + alpha, -2.0 * cos, 1.0 - alpha]),
+            EqFilterType::Peaking => ([1.0 + alpha * a, -2.0 * cos, 1.0 - alpha * a], [1.0 + alpha / a, -2.0 * cos, 1.0 - alpha / a]),
+            EqFilterType::LowShelf => (
+                [a * ((a + 1.0) - (a - 1.0) * cos + shelf), 2.0 * a * ((a - 1.0) - (a + 1.0) * cos), a * ((a + 1.0) - (a - 1.0) * cos - shelf)],
+                [(a + 1.0) + (a - 1.0) * cos + shelf, -2.0 * ((a - 1.0) + (a + 1.0) * cos), (a + 1.0) + (a - 1.0) * cos - shelf],
+            ),
+            EqFilterType::HighShelf => (
+                [a * ((a + 1.0) + (a - 1.0) * cos + shelf), -2.0 * a * ((a - 1.0) + (a + 1.0) * cos), a * ((a + 1.0) + (a - 1.0) * cos - shelf)],
+                [(a + 1.0) - (a - 1.0) * cos + shelf, 2.0 * ((a - 1.0) - (a + 1.0) * cos), (a + 1.0) - (a - 1.0) * cos - shelf],
+            ),
+        };
+        Ok((b.map(|b| b / a[0]), a.map(|x| x / a[0])))
+    }
+
+    pub fn biquad(&self, sample_rate: f64) -> Result<Biquad, EqError> {
+        let (b, a) = self.coefficients(sample_rate)?;
+        Ok(Biquad::new(b, a))
+    }
+}
+
+/// Multi-band parametric equalizer, bands are chained biquads. Equalizer keeps state between
+/// calls, so stream can be filtered block by block. Without bands signal is passed unchanged.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParametricEqualizer {
+    sample_rate: f64,
+    bands: Vec<EqBand>,
+    filters: Vec<Biquad>,
+}
+
+impl ParametricEqualizer {
+    pub fn new(sample_rate: f64) -> Result<Self, EqError> {
+        if !(sample_rate > 0.0 && sample_rate.is_finite()) {
+            return Err(EqError::InvalidSampleRate(sample_rate));
+        }
+        Ok(Self{sample_rate, bands: vec![], filters: vec![]})
+    }
+
+    /// Invalid band parameters leave the equalizer unchanged, see [`EqBand::coefficients`].
+    pub fn add_band(&mut self, band: EqBand) -> Result<(), EqError> {
+        self.filters.push(band.biquad(self.sample_rate)?);
+        self.bands.push(band);
+        Ok(())
+    }
+
+    /// Changes parameters of band while keeping its state, so it can be used while streaming.
+    /// Invalid parameters leave the band unchanged. Panics when index is out of range.
+    pub fn set_band(&mut self, index: usize, band: EqBand) -> Result<(), EqError> {
+        assert!(index < self.bands.len(), "band index {} is out of range", index);
+        let (b, a) = band.coefficients(self.sample_rate)?;
+        self.filters[index].set_coefficients(b, a);
+        self.bands[index] = band;
+        Ok(())
+    }
+
+    /// Panics when index is out of range.
+    pub fn remove_band(&mut self, index: usize) -> EqBand {
+        self.filters.remove(index);
+        self.bands.remove(index)
+    }
+
+    pub fn bands(&self) -> &[EqBand] {
+        &self.bands
+    }
+
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    /// Bands as SOS filter with zeroed state, `None` when there are no bands.
+    pub fn to_sos(&self) -> Option<SosFilter> {
+        if self.filters.is_empty() {
+            return None;
+        }
+        let mut sos = SosFilter::new(self.filters.clone());
+        sos.reset();
+        Some(sos)
+    }
+
+    /// Filters whole signal starting from zeroed state, state of this equalizer is not changed.
+    pub fn filter(&self, signal: &DescreteSignal, output: &mut DescreteSignal) {
+        let mut equalizer = self.clone();
+        equalizer.reset();
+        equalizer.process_signal(signal, output);
+    }
+}
+
+impl StreamFilter for ParametricEqualizer {
+    fn process_sample(&mut self, x: f64) -> f64 {
+        self.filters.iter_mut().fold(x, |x, filter| filter.process_sample(x))
+    }
+
+    fn reset(&mut self) {
+        for filter in self.filters.iter_mut() {
+            filter.reset();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{assert_close, gain_db};
+
+    const FS: f64 = 48000.0;
+
+    fn band_gain_db(band: &EqBand, f: f64) -> f64 {
+        gain_db(&SosFilter::new(vec![band.biquad(FS).unwrap()]), f, FS)
+    }
+
+    #[test]
+    fn coefficients_shall_match_cookbook_formulas() {
+        let (b, a) = EqBand::peaking(1000.0, 1.0, 6.0).coefficients(FS).unwrap();
+        assert_close(&b, &[1.043953086990335, -1.8953207239365961, 0.8677222847598566], 1e-12);
+        assert_close(&a, &[1.0, -1.8953207239365961, 0.9116753717501915], 1e-12);
+
+        let (b, a) = EqBand::low_shelf(200.0, 1.0, -4.0).coefficients(FS).unwrap();
+        assert_close(&b, &[0.99573762595106, -1.958620726704083, 0.9634163943098388], 1e-12);
+        assert_close(&a, &[1.0, -1.9584647668186006, 0.959309980146381], 1e-12);
+    }
+
+    #[test]
+    fn gain_bands_shall_have_specified_gain() {
+        let peaking = EqBand::peaking(1000.0, 2.0, 9.0);
+        assert!((band_gain_db(&peaking, 1000.0) - 9.0).abs() < 1e-9);
+        assert!(band_gain_db(&peaking, 20.0).abs() < 0.01);
+        assert!(band_gain_db(&peaking, 20000.0).abs() < 0.01);
+
+        let low_shelf = EqBand::low_shelf(300.0, 1.0, -6.0);
+        assert!((band_gain_db(&low_shelf, 10.0) + 6.0).abs() < 0.01);
+        assert!((band_gain_db(&low_shelf, 300.0) + 3.0).abs() < 1e-9);
+        assert!(band_gain_db(&low_shelf, 20000.0).abs() < 0.01);
+
+        let high_shelf = EqBand::high_shelf(5000.0, 0.5, 4.0);
+        assert!(band_gain_db(&high_shelf, 10.0).abs() < 0.01);
+        assert!((band_gain_db(&high_shelf, 5000.0) - 2.0).abs() < 1e-9);
+        assert!((band_gain_db(&high_shelf, 23999.0) - 4.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn pass_and_stop_bands_shall_follow_filter_type() {
+        let f = 2000.0;
+        let low_pass = EqBand::new(EqFilterType::LowPass, f);
+        assert!(band_gain_db(&low_pass, 1.0).abs() < 1e-6);
+        assert!((band_gain_db(&low_pass, f) + 3.0103).abs() < 1e-3);
+        let high_pass = EqBand::new(EqFilterType::HighPass, f);
+        assert!(band_gain_db(&high_pass, 23999.0).abs() < 1e-6);
+        assert!((band_gain_db(&high_pass, f) + 3.0103).abs() < 1e-3);
+        let band_pass = EqBand::new(EqFilterType::BandPass, f).set_width(EqWidth::Octaves(1.0));
+        assert!(band_gain_db(&band_pass, f).abs() < 1e-9);
+        assert!(band_gain_db(&band_pass, 100.0) < -20.0);
+        let notch = EqBand::new(EqFilterType::Notch, f).set_width(EqWidth::Q(10.0));
+        assert!(band_gain_db(&notch, f) < -200.0);
+        assert!(band_gain_db(&notch, 1000.0).abs() < 0.1);
+        let all_pass = EqBand::new(EqFilterType::AllPass, f);
+        for frequency in [10.0, 1000.0, 2000.0, 15000.0] {
+            assert!(band_gain_db(&all_pass, frequency).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn bandwidth_in_octaves_shall_give_band_edges() {
+        let band_pass = EqBand::new(EqFilterType::BandPass, 1000.0).set_width(EqWidth::Octaves(2.0));
+
+        // bandwidth is defined for analog filter, so edges are prewarped
+        let warp = |f: f64| (PI * f / FS).tan();
+        let unwarp = |w: f64| w.atan() * FS / PI;
+        let center = warp(1000.0);
+        let edges = [unwarp(center / 2.0), unwarp(center * 2.0)];
+        for edge in edges {
+            assert!((band_gain_db(&band_pass, edge) + 3.0103).abs() < 0.05, "{} {}", edge, band_gain_db(&band_pass, edge));
+        }
+    }
+
+    #[test]
+    fn equalizer_shall_chain_bands_and_stream() {
+        let input = (0..300).map(|i| (i as f64 * 0.05).sin() + if i % 37 == 0 { 1.0 } else { 0.0 }).collect::<Vec<f64>>();
+        let signal = DescreteSignal::new_from_vec(input.iter().enumerate().map(|(i, x)| (i as f64 / FS, *x)).collect());
+        let mut equalizer = ParametricEqualizer::new(FS).unwrap();
+        equalizer.add_band(EqBand::low_shelf(100.0, 1.0, 3.0)).unwrap();
+        equalizer.add_band(EqBand::peaking(1000.0, 1.4, -5.0)).unwrap();
+        equalizer.add_band(EqBand::new(EqFilterType::HighPass, 30.0)).unwrap();
+
+        let mut batch = DescreteSignal::new();
+        let mut sos = DescreteSignal::new();
+        equalizer.filter(&signal, &mut batch);
+        equalizer.to_sos().unwrap().filter(&signal, &mut sos);
+        assert_eq!(batch, sos);
+
+        let mut streamed = vec![];
+        for block in input.chunks(64) {
+            let mut output = vec![0.0; block.len()];
+            equalizer.process_block(block, &mut output);
+            streamed.extend(output);
+        }
+        assert_eq!(streamed, batch.values().collect::<Vec<f64>>());
+        assert_eq!(batch.times().collect::<Vec<f64>>(), signal.times().collect::<Vec<f64>>());
+    }
+
+    #[test]
+    fn band_change_shall_keep_state() {
+        let mut equalizer = ParametricEqualizer::new(FS).unwrap();
+        equalizer.add_band(EqBand::peaking(1000.0, 1.0, 6.0)).unwrap();
+        let mut reference = equalizer.clone();
+        for _ in 0..10 {
+            equalizer.process_sample(1.0);
+            reference.process_sample(1.0);
+        }
+
+        let band = EqBand::peaking(1000.0, 1.0, -6.0);
+        equalizer.set_band(0, band).unwrap();
+        let mut expected = reference.filters[0].clone();
+        let (b, a) = band.coefficients(FS).unwrap();
+        expected.set_coefficients(b, a);
+        assert_eq!(equalizer.process_sample(1.0), expected.process_sample(1.0));
+        assert_eq!(equalizer.bands(), &[band]);
+
+        assert_eq!(equalizer.remove_band(0), band);
+        assert_eq!(equalizer.process_sample(0.25), 0.25);
+        assert!(equalizer.to_sos().is_none());
+    }
+
+    #[test]
+    fn invalid_band_shall_be_rejected() {
+        assert_eq!(EqBand::peaking(1000.0, 1.0, 3.0).set_width(EqWidth::Slope(1.0)).coefficients(FS),
+                   Err(EqError::SlopeNotSupported(EqFilterType::Peaking)));
+        assert_eq!(EqBand::peaking(24000.0, 1.0, 3.0).coefficients(FS),
+                   Err(EqError::InvalidFrequency{frequency: 24000.0, nyquist: 24000.0}));
+        assert_eq!(EqBand::peaking(1000.0, 0.0, 3.0).coefficients(FS), Err(EqError::InvalidWidth(EqWidth::Q(0.0))));
+        assert!(matches!(EqBand::peaking(1000.0, 1.0, f64::NAN).coefficients(FS), Err(EqError::InvalidGain(_))));
+        assert_eq!(ParametricEqualizer::new(0.0), Err(EqError::InvalidSampleRate(0.0)));
+
+        let band = EqBand::peaking(1000.0, 1.0, 3.0);
+        let mut equalizer = ParametricEqualizer::new(FS).unwrap();
+        equalizer.add_band(band).unwrap();
+        assert_eq!(equalizer.add_band(EqBand::new(EqFilterType::LowPass, -5.0)),
+                   Err(EqError::InvalidFrequency{frequency: -5.0, nyquist: 24000.0}));
+        assert_eq!(equalizer.bands(), &[band]);
+        assert!(equalizer.set_band(0, EqBand::peaking(1000.0, -1.0, 3.0)).is_err());
+        assert_eq!(equalizer.bands(), &[band]);
+    }
+}
@@ -0,0 +1,338 @@
+use crate::fft::transform;
+use crate::{ComplexNumber, DescreteSignal};
+
+/// Algorithm used to calculate convolution. All of them return the same result up to rounding
+/// errors, they differ only in speed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConvolutionMethod {
+    /// Chooses method from signal and filter lengths, see [`convolve`].
+    Auto,
+    /// Direct summation, O(N * L).
+    Direct,
+    /// Single FFT of whole signal.
+    Fft,
+    /// FFT of blocks of given length, results of blocks are added.
+    OverlapAdd(usize),
+    /// FFT of overlapping blocks, each step produces given number of output samples.
+    OverlapSave(usize),
+}
+
+/// Full convolution (N + L - 1 samples) calculated with selected method.
+pub fn convolve_with(signal: &[f64], fir: &[f64], method: ConvolutionMethod) -> Vec<f64> {
+    match method {
+        ConvolutionMethod::Auto => convolve(signal, fir),
+        ConvolutionMethod::Direct => direct_convolve(signal, fir),
+        ConvolutionMethod::Fft => fft_convolve(signal, fir),
+        ConvolutionMethod::OverlapAdd(block_size) => overlap_add(signal, fir, block_size),
+        ConvolutionMethod::OverlapSave(block_size) => overlap_save(signal, fir, block_size),
+    }
+}
+
+/// Full convolution with method chosen from lengths of inputs: direct summation for short
+/// filters, single FFT when both inputs have similar length and overlap-add when signal
+/// is much longer than filter.
+pub fn convolve(signal: &[f64], fir: &[f64]) -> Vec<f64> {
+    convolve_with(signal, fir, choose_method(signal.len(), fir.len()))
+}
+
+/// Method that [`convolve`] uses for given lengths of signal and filter.
+pub fn choose_method(signal_len: usize, fir_len: usize) -> ConvolutionMethod {
+    let (long, short) = (signal_len.max(fir_len), signal_len.min(fir_len));
+    if short <= 32 {
+        return ConvolutionMethod::Direct;
+    }
+    let direct_cost = (long * short) as f64;
+    let fft_cost = |size: usize| 6.0 * size as f64 * (size as f64).log2();
+    let size = (long + short - 1).next_power_of_two();
+    let block_fft = (4 * short).next_power_of_two();
+    let block = block_fft - short + 1;
+    let blocks_cost = long.div_ceil(block) as f64 * fft_cost(block_fft) * 2.0 / 3.0;
+
+    if direct_cost <= fft_cost(size).min(blocks_cost) {
+        ConvolutionMethod::Direct
+    } else if blocks_cost < fft_cost(size) {
+        ConvolutionMethod::OverlapAdd(block)
+    } else {
+        ConvolutionMethod::Fft
+    }
+}
+
+pub fn direct_convolve(signal: &[f64], fir: &[f64]) -> Vec<f64> {
+    if signal.is_empty() || fir.is_empty() {
+        return vec![];
+    }
+    let mut result = vec![0.0; signal.len() + fir.len() - 1];
+    for (n, y) in result.iter_mut().enumerate() {
+        for (i, h) in fir.iter().enumerate().take(n + 1) {
+            if let Some(x) = signal.get(n - i) {
+                *y += h * x;
+            }
+        }
+    }
+    result
+}
+
+/// Full convolution calculated with one FFT of size of the result.
+pub fn fft_convolve(signal: &[f64], fir: &[f64]) -> Vec<f64> {
+    if signal.is_empty() || fir.is_empty() {
+        return vec![];
+    }
+    let len = signal.len() + fir.len() - 1;
+    let size = len.next_power_of_two();
+    let mut result = multiply_spectra(&spectrum(signal, size), &spectrum(fir, size));
+    result.truncate(len);
+    result
+}
+
+/// Full cross-correlation `r[k] = sum(a[n + k] * b[n])` for lags from `-(b.len() - 1)` to
+/// `a.len() - 1`, the same as `numpy.correlate(a, b, "full")`.
+pub fn fft_correlate(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let reversed = b.iter().rev().copied().collect::<Vec<f64>>();
+    convolve(a, &reversed)
+}
+
+/// Full convolution of long signal split into blocks of `block_size` samples. Panics when
+/// block size is zero.
+pub fn overlap_add(signal: &[f64], fir: &[f64], block_size: usize) -> Vec<f64> {
+    assert!(block_size > 0, "block size shall be greater than zero");
+    if signal.is_empty() || fir.is_empty() {
+        return vec![];
+    }
+    let size = (block_size + fir.len() - 1).next_power_of_two();
+    let fir_spectrum = spectrum(fir, size);
+    let mut result = vec![0.0; signal.len() + fir.len() - 1];
+
+    for (k, block) in signal.chunks(block_size).enumerate() {
+        let start = k * block_size;
+        let partial = multiply_spectra(&spectrum(block, size), &fir_spectrum);
+        for (y, p) in result[start..].iter_mut().zip(&partial[..block.len() + fir.len() - 1]) {
+            *y += p;
+        }
+    }
+    result
+}
+
+/// Full convolution where every FFT of overlapping input window produces `block_size`
+/// samples of output. Panics when block size is zero.
+pub fn overlap_save(signal: &[f64], fir: &[f64], block_size: usize) -> Vec<f64> {
+    assert!(block_size > 0, "block size shall be greater than zero");
+    if signal.is_empty() || fir.is_empty() {
+        return vec![];
+    }
+    let overlap = fir.len() - 1;
+    let size = (block_size + overlap).next_power_of_two();
+    let fir_spectrum = spectrum(fir, size);
+    let len = signal.len() + overlap;
+    let mut padded = vec![0.0; overlap];
+    padded.extend_from_slice(signal);
+    padded.resize(len + overlap, 0.0);
+    let mut result = Vec::with_capacity(len);
+
+    for start in (0..len).step_by(block_size) {
+        let end = (start + size).min(padded.len());
+        let window = multiply_spectra(&spectrum(&padded[start..end], size), &fir_spectrum);
+        let count = block_size.min(len - start);
+        result.extend_from_slice(&window[overlap..overlap + count]);
+    }
+    result
+}
+
+/// Cross-correlation of two signals, see [`fft_correlate`]. Lags are converted to time
+/// with sampling period of the first signal, metadata is copied from the first signal.
+pub fn cross_correlation(a: &DescreteSignal, b: &DescreteSignal, output: &mut DescreteSignal) {
+    output.set_metadata(a.metadata().clone());
+    if a.is_empty() || b.is_empty() {
+        return;
+    }
+    let period = if a.len() > 1 { a.get_sampling_period() } else { 0.0 };
+    let values = fft_correlate(&a.values().collect::<Vec<f64>>(), &b.values().collect::<Vec<f64>>());
+    let first_lag = 1 - b.len() as i64;
+    for (k, y) in values.into_iter().enumerate() {
+        output.push((first_lag + k as i64) as f64 * period, y);
+    }
+}
+
+/// Streaming convolution with long FIR split into partitions of `block_size` taps
+/// (uniformly partitioned overlap-save). Every call consumes one block of input and
+/// returns one block of output, so latency is equal to block size while cost per sample
+/// grows only with number of partitions.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PartitionedConvolver {
+    block_size: usize,
+    partitions: Vec<Vec<ComplexNumber>>,
+    /// Spectra of recent input windows, the newest is at `position`.
+    history: Vec<Vec<ComplexNumber>>,
+    position: usize,
+    window: Vec<f64>,
+}
+
+impl PartitionedConvolver {
+    /// Panics when there are no coefficients or block size is zero.
+    pub fn new(fir: &[f64], block_size: usize) -> Self {
+        assert!(!fir.is_empty(), "FIR filter shall have at least one coefficient");
+        assert!(block_size > 0, "block size shall be greater than zero");
+        let partitions = fir.chunks(block_size)
+            .map(|p| spectrum(p, 2 * block_size))
+            .collect::<Vec<Vec<ComplexNumber>>>();
+        Self{
+            block_size,
+            history: vec![vec![ComplexNumber::default(); 2 * block_size]; partitions.len()],
+            partitions,
+            position: 0,
+            window: vec![0.0; 2 * block_size],
+        }
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    pub fn number_of_partitions(&self) -> usize {
+        self.partitions.len()
+    }
+
+    /// Clears all past input, as if convolver was just created.
+    pub fn reset(&mut self) {
+        for h in self.history.iter_mut() {
+            h.fill(ComplexNumber::default());
+        }
+        self.window.fill(0.0);
+        self.position = 0;
+    }
+
+    /// Filters next block of stream. Panics when `input` or `output` is not exactly one block long.
+    pub fn process_block(&mut self, input: &[f64], output: &mut [f64]) {
+        assert_eq!(input.len(), self.block_size, "input shall be exactly one block long");
+        assert_eq!(output.len(), self.block_size, "output shall be exactly one block long");
+        self.window.copy_within(self.block_size.., 0);
+        self.window[self.block_size..].copy_from_slice(input);
+
+        let count = self.partitions.len();
+        self.position = (self.position + count - 1) % count;
+        self.history[self.position] = spectrum(&self.window, 2 * self.block_size);
+
+        let mut sum = vec![ComplexNumber::default(); 2 * self.block_size];
+        for (p, partition) in self.partitions.iter().enumerate() {
+            let past = &self.history[(self.position + p) % count];
+            for ((s, x), h) in sum.iter_mut().zip(past).zip(partition) {
+                *s = &*s + &(x * h);
+            }
+        }
+        for (y, s) in output.iter_mut().zip(&transform(&sum, true)[self.block_size..]) {
+            *y = s.re();
+        }
+    }
+}
+
+/// Spectrum of real data padded with zeros to `size` samples.
+fn spectrum(data: &[f64], size: usize) -> Vec<ComplexNumber> {
+    let mut samples = vec![ComplexNumber::default(); size];
+    for (s, x) in samples.iter_mut().zip(data) {
+        *s = ComplexNumber::new(*x, 0.0);
+    }
+    transform(&samples, false)
+}
+
+/// Real part of inverse transform of product of two spectra, i.e. circular convolution.
+fn multiply_spectra(a: &[ComplexNumber], b: &[ComplexNumber]) -> Vec<f64> {
+    let product = a.iter().zip(b).map(|(x, y)| x * y).collect::<Vec<ComplexNumber>>();
+    transform(&product, true).iter().map(ComplexNumber::re).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_close;
+    use crate::{FirFilter, StreamFilter};
+
+    fn test_signal(len: usize, seed: f64) -> Vec<f64> {
+        (0..len).map(|i| (i as f64 * seed).sin() + 0.3 * (i as f64 * seed * 2.7).cos()).collect()
+    }
+
+    #[test]
+    fn all_methods_shall_give_the_same_full_convolution() {
+        let signal = test_signal(500, 0.13);
+        let fir = test_signal(77, 0.71);
+        let expected = direct_convolve(&signal, &fir);
+
+        assert_eq!(expected.len(), 576);
+        assert_close(&fft_convolve(&signal, &fir), &expected, 1e-9);
+        for block_size in [1, 10, 64, 100, 1000] {
+            assert_close(&overlap_add(&signal, &fir, block_size), &expected, 1e-9);
+            assert_close(&overlap_save(&signal, &fir, block_size), &expected, 1e-9);
+        }
+        assert_close(&convolve(&signal, &fir), &expected, 1e-9);
+    }
+
+    #[test]
+    fn direct_convolution_shall_multiply_polynomials() {
+        assert_eq!(direct_convolve(&[1.0, 2.0], &[1.0, 3.0, 1.0]), vec![1.0, 5.0, 7.0, 2.0]);
+        assert!(fft_convolve(&[], &[1.0]).is_empty());
+    }
+
+    #[test]
+    fn method_shall_be_chosen_from_lengths() {
+        assert_eq!(choose_method(10000, 16), ConvolutionMethod::Direct);
+        assert_eq!(choose_method(4096, 4096), ConvolutionMethod::Fft);
+        assert!(matches!(choose_method(1_000_000, 2048), ConvolutionMethod::OverlapAdd(_)));
+    }
+
+    #[test]
+    fn correlation_shall_find_delay_between_signals() {
+        let b = test_signal(100, 0.37);
+        let mut a = vec![0.0; 25];
+        a.extend_from_slice(&b);
+        let correlation = fft_correlate(&a, &b);
+        let peak = correlation.iter().enumerate()
+            .max_by(|x, y| x.1.total_cmp(y.1))
+            .map(|(k, _)| k as i64 - (b.len() as i64 - 1))
+            .unwrap();
+
+        assert_eq!(correlation.len(), a.len() + b.len() - 1);
+        assert_eq!(peak, 25);
+    }
+
+    #[test]
+    fn cross_correlation_shall_use_lags_as_timestamps() {
+        let a = DescreteSignal::new_from_vec(vec![(0.0, 1.0), (0.5, 2.0), (1.0, 3.0)]);
+        let b = DescreteSignal::new_from_vec(vec![(0.0, 1.0), (0.5, 1.0)]);
+        let mut output = DescreteSignal::new();
+
+        cross_correlation(&a, &b, &mut output);
+        assert_eq!(output.times().collect::<Vec<f64>>(), vec![-0.5, 0.0, 0.5, 1.0]);
+        assert_close(&output.values().collect::<Vec<f64>>(), &[1.0, 3.0, 5.0, 3.0], 1e-9);
+    }
+
+    #[test]
+    fn partitioned_convolver_shall_match_direct_fir_filter() {
+        let fir = test_signal(300, 0.05);
+        let input = test_signal(1024, 0.21);
+        let mut expected = vec![0.0; input.len()];
+        FirFilter::new(&fir).process_block(&input, &mut expected);
+
+        for block_size in [16, 64, 512] {
+            let mut convolver = PartitionedConvolver::new(&fir, block_size);
+            let mut output = vec![];
+            for block in input.chunks(block_size) {
+                let mut y = vec![0.0; block_size];
+                convolver.process_block(block, &mut y);
+                output.extend(y);
+            }
+            assert_eq!(convolver.number_of_partitions(), 300usize.div_ceil(block_size));
+            assert_close(&output, &expected, 1e-9);
+        }
+    }
+
+    #[test]
+    fn partitioned_convolver_shall_forget_input_after_reset() {
+        let mut convolver = PartitionedConvolver::new(&[1.0, 1.0, 1.0], 2);
+        let mut output = [0.0; 2];
+        convolver.process_block(&[5.0, 5.0], &mut output);
+        convolver.reset();
+
+        convolver.process_block(&[1.0, 0.0], &mut output);
+        assert_close(&output, &[1.0, 1.0], 1e-9);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::DescreteSignal;
 use crate::ComplexNumber;
+use crate::ComplexSignal;
 use std::f64::consts::PI;
 
 /// This structure holds vectors that hold values for calculating fourier transform.
@@ -17,6 +18,7 @@ struct FftParams {
 }
 
 pub fn fft(signal: &DescreteSignal, spectrum: &mut DescreteSignal) {
+    spectrum.set_metadata(signal.metadata().to_spectrum());
     let params = FftParams::new(signal);
     let mut x = 0.0;
     let scale_factor = 2.0/params.n as f64;
@@ -34,6 +36,108 @@ pub fn fft(signal: &DescreteSignal, spectrum: &mut DescreteSignal) {
     }
 }
 
+/// Calculates complex spectrum of complex signal. Bins are ordered by frequency, starting at
+/// the most negative frequency, so spectrum spans from -fs/2 to fs/2. Values are divided by
+/// number of samples, so complex exponential with amplitude A gives value of module A.
+pub fn complex_fft(signal: &ComplexSignal, spectrum: &mut ComplexSignal) {
+    spectrum.set_metadata(signal.metadata().to_spectrum());
+    let n = signal.len();
+    let resolution = 1.0/(signal.get_sampling_period() * n as f64);
+    let bins = transform(&signal.samples().collect::<Vec<ComplexNumber>>(), false);
+    let first_negative = n.div_ceil(2);
+
+    for k in (first_negative..n).chain(0..first_negative) {
+        let index = if k >= first_negative { k as f64 - n as f64 } else { k as f64 };
+        spectrum.push(index * resolution, &bins[k] / n as f64);
+    }
+}
+
+/// Discrete fourier transform of complex samples. Lengths that are power of two are calculated
+/// with radix-2 algorithm, other lengths are turned into power of two convolution with
+/// Bluestein's algorithm. Inverse transform is scaled by 1/n, so it restores original samples.
+pub(crate) fn transform(data: &[ComplexNumber], inverse: bool) -> Vec<ComplexNumber> {
+    let n = data.len();
+    if n <= 1 {
+        return data.to_vec();
+    }
+    let mut result = if n.is_power_of_two() {
+        let mut result = data.to_vec();
+        radix2(&mut result, inverse);
+        result
+    } else {
+        bluestein(data, inverse)
+    };
+
+    if inverse {
+        for x in result.iter_mut() {
+            *x = &*x / n as f64;
+        }
+    }
+    result
+}
+
+fn radix2(data: &mut [ComplexNumber], inverse: bool) {
+    let n = data.len();
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let twiddles = (0..n/2)
+        .map(|k| ComplexNumber::from_polar(1.0, sign * 2.0 * PI * k as f64 / n as f64))
+        .collect::<Vec<ComplexNumber>>();
+
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let step = n / len;
+        for start in (0..n).step_by(len) {
+            for k in 0..len/2 {
+                let u = data[start + k];
+                let v = &data[start + k + len/2] * &twiddles[k * step];
+                data[start + k] = &u + &v;
+                data[start + k + len/2] = &u - &v;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+fn bluestein(data: &[ComplexNumber], inverse: bool) -> Vec<ComplexNumber> {
+    let n = data.len();
+    let m = (2 * n - 1).next_power_of_two();
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let chirp = (0..n)
+        .map(|k| ComplexNumber::from_polar(1.0, sign * PI * ((k * k) % (2 * n)) as f64 / n as f64))
+        .collect::<Vec<ComplexNumber>>();
+
+    let mut a = vec![ComplexNumber::default(); m];
+    let mut b = vec![ComplexNumber::default(); m];
+    for k in 0..n {
+        a[k] = &data[k] * &chirp[k];
+        b[k] = chirp[k].conj();
+        if k > 0 {
+            b[m - k] = chirp[k].conj();
+        }
+    }
+    radix2(&mut a, false);
+    radix2(&mut b, false);
+    for (x, y) in a.iter_mut().zip(&b) {
+        *x = &*x * y;
+    }
+    radix2(&mut a, true);
+
+    (0..n).map(|k| &(&a[k] * &chirp[k]) / m as f64).collect()
+}
+
 impl FftParams {
     fn new(signal: &DescreteSignal) -> Self {
         let n = signal.len();
@@ -44,7 +148,7 @@ impl FftParams {
 
         for i in 0..n {
             wn[i] = ComplexNumber::new(f64::cos(two_pi_by_n*i as f64),
-                                      -1.0*f64::sin(two_pi_by_n*i as f64));
+                                      -f64::sin(two_pi_by_n*i as f64));
             for k in 0..n {
                 snk[i][k] = &wn[i] * signal[k].1;
             }
@@ -56,6 +160,7 @@ impl FftParams {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::SignalMetadata;
 
     fn create_fft_from_vector(data: &[f64], sampling_period: f64) -> (DescreteSignal, FftParams) {
         let mut signal = vec![(0.0, 0.0); data.len()];
@@ -105,12 +210,12 @@ mod tests {
             [ComplexNumber::new(data[0], 0.0),
              ComplexNumber::new(data[1], 0.0),
              ComplexNumber::new(data[2], 0.0)],
-            [&ComplexNumber::new((2.0*PI/3.0).cos(), -1.0*(2.0*PI/3.0).sin()) * data[0],
-             &ComplexNumber::new((2.0*PI/3.0).cos(), -1.0*(2.0*PI/3.0).sin()) * data[1],
-             &ComplexNumber::new((2.0*PI/3.0).cos(), -1.0*(2.0*PI/3.0).sin()) * data[2]],
-            [&ComplexNumber::new((4.0*PI/3.0).cos(), -1.0*(4.0*PI/3.0).sin()) * data[0],
-             &ComplexNumber::new((4.0*PI/3.0).cos(), -1.0*(4.0*PI/3.0).sin()) * data[1],
-             &ComplexNumber::new((4.0*PI/3.0).cos(), -1.0*(4.0*PI/3.0).sin()) * data[2]]
+            [&ComplexNumber::new((2.0*PI/3.0).cos(), -(2.0*PI/3.0).sin()) * data[0],
+             &ComplexNumber::new((2.0*PI/3.0).cos(), -(2.0*PI/3.0).sin()) * data[1],
+             &ComplexNumber::new((2.0*PI/3.0).cos(), -(2.0*PI/3.0).sin()) * data[2]],
+            [&ComplexNumber::new((4.0*PI/3.0).cos(), -(4.0*PI/3.0).sin()) * data[0],
+             &ComplexNumber::new((4.0*PI/3.0).cos(), -(4.0*PI/3.0).sin()) * data[1],
+             &ComplexNumber::new((4.0*PI/3.0).cos(), -(4.0*PI/3.0).sin()) * data[2]]
             ];
         assert_eq!(fft_object.snk, expected_snk);
     }
@@ -128,4 +233,72 @@ mod tests {
         fft(&signal, &mut calculated_fft);
         assert_eq!(calculated_fft.get_data(), expected_fft.get_data());
     }
+
+    #[test]
+    fn fft_shall_convert_time_unit_to_frequency_unit() {
+        let mut signal = DescreteSignal::new_from_vec(vec![(0.0, 1.0), (0.5, 0.0), (1.0, 1.0), (1.5, 0.0)]);
+        signal.set_metadata(SignalMetadata::new().set_time_unit("s").set_value_unit("V").set_sample_rate(2.0));
+        let mut spectrum = DescreteSignal::new();
+
+        fft(&signal, &mut spectrum);
+        assert_eq!(spectrum.metadata().time_unit(), Some("Hz"));
+        assert_eq!(spectrum.metadata().value_unit(), Some("V"));
+        assert_eq!(spectrum.metadata().sample_rate(), None);
+    }
+
+    fn naive_dft(data: &[ComplexNumber]) -> Vec<ComplexNumber> {
+        let n = data.len();
+        (0..n).map(|k| {
+            data.iter().enumerate().fold(ComplexNumber::default(), |acc, (j, x)| {
+                let w = ComplexNumber::from_polar(1.0, -2.0 * PI * ((j * k) % n) as f64 / n as f64);
+                &acc + &(x * &w)
+            })
+        }).collect()
+    }
+
+    fn assert_close(a: &[ComplexNumber], b: &[ComplexNumber]) {
+        assert_eq!(a.len(), b.len());
+        for (x, y) in a.iter().zip(b) {
+            assert!((x - y).module() < 1e-9, "{} != {}", x, y);
+        }
+    }
+
+    #[test]
+    fn transform_shall_match_direct_dft_for_any_length() {
+        for n in [1, 2, 3, 5, 8, 12, 16, 31] {
+            let data = (0..n)
+                .map(|i| ComplexNumber::new((i as f64 * 0.7).sin(), (i as f64 * 1.3).cos()))
+                .collect::<Vec<ComplexNumber>>();
+            assert_close(&transform(&data, false), &naive_dft(&data));
+        }
+    }
+
+    #[test]
+    fn inverse_transform_shall_restore_samples() {
+        for n in [4, 7, 64, 100] {
+            let data = (0..n)
+                .map(|i| ComplexNumber::new(i as f64, -(i as f64) * 0.5))
+                .collect::<Vec<ComplexNumber>>();
+            assert_close(&transform(&transform(&data, false), true), &data);
+        }
+    }
+
+    #[test]
+    fn complex_fft_shall_place_complex_exponential_at_its_frequency() {
+        let fs = 8.0;
+        let signal = (0..8)
+            .map(|i| {
+                let t = i as f64 / fs;
+                (t, ComplexNumber::from_polar(3.0, -2.0 * PI * 2.0 * t))
+            })
+            .collect::<ComplexSignal>();
+        let mut spectrum = ComplexSignal::new();
+
+        complex_fft(&signal, &mut spectrum);
+        assert_eq!(spectrum.times().collect::<Vec<f64>>(), vec![-4.0, -3.0, -2.0, -1.0, 0.0, 1.0, 2.0, 3.0]);
+        for (f, y) in spectrum.iter() {
+            let expected = if *f == -2.0 { 3.0 } else { 0.0 };
+            assert!((y.module() - expected).abs() < 1e-12);
+        }
+    }
 }
\ No newline at end of file
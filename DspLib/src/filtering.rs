@@ -1,8 +1,11 @@
 use std::f64::consts::PI;
 
-use crate::DescreteSignal;
+use crate::{ComplexNumber, ComplexSignal, DescreteSignal, StreamFilter};
 
+/// Valid part of convolution, every output sample is stamped with timestamp of the oldest
+/// input sample it depends on. See [`crate::Convolution`] for other modes and timestamp alignment.
 pub fn fir_filter(signal: &DescreteSignal, fir: &[f64], output: &mut DescreteSignal) {
+    output.set_metadata(signal.metadata().clone());
     for n in (fir.len()-1)..signal.len(){
         let mut y = 0.0;
         for (i, h) in fir.iter().enumerate() {
@@ -12,6 +15,62 @@ pub fn fir_filter(signal: &DescreteSignal, fir: &[f64], output: &mut DescreteSig
     }
 }
 
+/// Complex counterpart of [`fir_filter`], both signal and taps can be complex.
+pub fn complex_fir_filter(signal: &ComplexSignal, fir: &[ComplexNumber], output: &mut ComplexSignal) {
+    output.set_metadata(signal.metadata().clone());
+    for n in (fir.len()-1)..signal.len(){
+        let mut y = ComplexNumber::default();
+        for (i, h) in fir.iter().enumerate() {
+            y = &y + &(h * &signal[n-i].1);
+        }
+        output.push(signal[n+1-fir.len()].0, y);
+    }
+}
+
+/// FIR filter that keeps its delay line between calls, so stream can be filtered block by block.
+/// Filter starts with zeroed delay line and produces exactly one output for every input sample.
+/// Output does not depend on how input is split into blocks.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FirFilter {
+    coefficients: Vec<f64>,
+    /// Past inputs stored twice, so newest `len` samples always form continuous slice.
+    delay_line: Vec<f64>,
+    position: usize,
+}
+
+impl FirFilter {
+    /// Panics when there are no coefficients.
+    pub fn new(coefficients: &[f64]) -> Self {
+        assert!(!coefficients.is_empty(), "FIR filter shall have at least one coefficient");
+        Self{coefficients: coefficients.to_vec(), delay_line: vec![0.0; 2 * coefficients.len()], position: 0}
+    }
+
+    pub fn coefficients(&self) -> &[f64] {
+        &self.coefficients
+    }
+}
+
+impl StreamFilter for FirFilter {
+    fn process_sample(&mut self, x: f64) -> f64 {
+        let len = self.coefficients.len();
+        self.position = if self.position == 0 { len - 1 } else { self.position - 1 };
+        self.delay_line[self.position] = x;
+        self.delay_line[self.position + len] = x;
+
+        let mut y = 0.0;
+        for (h, x) in self.coefficients.iter().zip(&self.delay_line[self.position..self.position + len]) {
+            y += h * x;
+        }
+        y
+    }
+
+    fn reset(&mut self) {
+        self.delay_line.fill(0.0);
+        self.position = 0;
+    }
+}
+
 pub fn get_averaging_fir_coefficients(size: usize, fir: &mut [f64]) {
     let len = if size >= fir.len() {
         size
@@ -19,11 +78,12 @@ pub fn get_averaging_fir_coefficients(size: usize, fir: &mut [f64]) {
         fir.len()
     };
 
-    for i in 0..len {
-        fir[i] = 1.0/len as f64;
+    for f in fir[..len].iter_mut() {
+        *f = 1.0/len as f64;
     }
 }
 
+#[deprecated(note = "it has fixed cutoff and is not normalized, use FirDesign::low_pass instead")]
 pub fn get_low_pass_fir_coefficients(size: usize, fir: &mut [f64]) {
     let freq = 1000.0;
     let len = if size > fir.len() {
@@ -42,6 +102,7 @@ pub fn get_low_pass_fir_coefficients(size: usize, fir: &mut [f64]) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::SignalMetadata;
 
     #[test]
     fn fir_filter_shall_copy_timestamps_to_output() {
@@ -54,7 +115,7 @@ mod tests {
         let mut output = DescreteSignal::new();
 
         fir_filter(&signal, &fir, &mut output);
-        for (i, &(t, _)) in output.get_data().into_iter().enumerate() {
+        for (i, &(t, _)) in output.get_data().iter().enumerate() {
             assert_eq!(t, timestamps[i]);
         }
     }
@@ -85,4 +146,83 @@ mod tests {
         fir_filter(&signal, &fir, &mut output);
         assert_eq!(output.get_data(), expected_output);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn fir_filter_shall_copy_metadata_to_output() {
+        let mut signal = DescreteSignal::new_from_vec(vec![(0.0, 1.0), (1.0, 2.0)]);
+        signal.set_metadata(SignalMetadata::new().set_name("input").set_value_unit("V"));
+        let mut output = DescreteSignal::new();
+
+        fir_filter(&signal, &[0.5], &mut output);
+        assert_eq!(output.metadata(), signal.metadata());
+    }
+
+    #[test]
+    fn complex_fir_filter_shall_match_real_filter_for_real_data() {
+        let signal = DescreteSignal::new_from_vec(
+            vec![(0.0, 0.0), (1.0, 1.0), (2.0, 0.0), (3.0, -1.0), (4.0, 0.0)]);
+        let fir = vec![0.1, 2.0, 10.0];
+        let complex_fir = fir.iter().map(|h| ComplexNumber::new(*h, 0.0)).collect::<Vec<_>>();
+        let mut expected = DescreteSignal::new();
+        let mut output = ComplexSignal::new();
+
+        fir_filter(&signal, &fir, &mut expected);
+        complex_fir_filter(&ComplexSignal::from_real(&signal), &complex_fir, &mut output);
+        assert_eq!(output.real(), expected);
+    }
+
+    #[test]
+    fn complex_fir_filter_shall_multiply_by_complex_taps() {
+        let signal = ComplexSignal::from_iq(&[1.0, 0.0, 2.0], &[0.0, 1.0, 0.0], 1.0, 0.0);
+        let fir = [ComplexNumber::new(0.0, 1.0), ComplexNumber::new(1.0, 0.0)];
+        let mut output = ComplexSignal::new();
+
+        complex_fir_filter(&signal, &fir, &mut output);
+        assert_eq!(output.get_data(), &[(0.0, ComplexNumber::new(0.0, 0.0)), (1.0, ComplexNumber::new(0.0, 3.0))]);
+    }
+
+    #[test]
+    fn fir_filter_object_shall_give_same_output_for_any_block_size() {
+        let input = (0..200).map(|i| (i as f64 * 0.37).sin() + 0.01 * i as f64).collect::<Vec<f64>>();
+        let taps = [0.1, -0.25, 0.7, 0.33, 0.05, -0.4, 0.123];
+        let mut expected = vec![0.0; input.len()];
+        FirFilter::new(&taps).process_block(&input, &mut expected);
+
+        for block_size in [1, 2, 3, 7, 64, 199] {
+            let mut filter = FirFilter::new(&taps);
+            let mut output = vec![];
+            for block in input.chunks(block_size) {
+                let mut y = vec![0.0; block.len()];
+                filter.process_block(block, &mut y);
+                output.extend(y);
+            }
+            assert_eq!(output, expected);
+        }
+    }
+
+    #[test]
+    fn fir_filter_object_shall_match_fir_filter_after_delay_line_is_filled() {
+        let signal = DescreteSignal::new_from_vec((0..50).map(|i| (i as f64, (i as f64 * 0.9).cos())).collect());
+        let taps = [0.3, 1.7, -0.2, 0.9];
+        let mut expected = DescreteSignal::new();
+        let mut output = DescreteSignal::new();
+
+        fir_filter(&signal, &taps, &mut expected);
+        FirFilter::new(&taps).process_signal(&signal, &mut output);
+        assert_eq!(output.len(), signal.len());
+        assert_eq!(output.times().collect::<Vec<f64>>(), signal.times().collect::<Vec<f64>>());
+        assert_eq!(output.values().skip(taps.len() - 1).collect::<Vec<f64>>(), expected.values().collect::<Vec<f64>>());
+    }
+
+    #[test]
+    fn fir_filter_object_shall_start_with_zero_state_after_reset() {
+        let mut filter = FirFilter::new(&[1.0, 2.0, 3.0]);
+        let impulse_response = [1.0, 0.0, 0.0, 0.0].map(|x| filter.process_sample(x));
+        assert_eq!(impulse_response, [1.0, 2.0, 3.0, 0.0]);
+
+        filter.process_sample(5.0);
+        filter.reset();
+        assert_eq!(filter.process_sample(1.0), 1.0);
+        assert_eq!(filter.process_sample(0.0), 2.0);
+    }
+}
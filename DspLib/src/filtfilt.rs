@@ -0,0 +1,419 @@
+use crate::iir::transposed_direct_form;
+use crate::linear_algebra::least_squares;
+use crate::{DescreteSignal, SosFilter};
+
+/// Extension of signal at both edges before zero-phase filtering, it reduces edge transients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EdgePadding {
+    /// Point reflection through the edge sample, `2 x[0] - x[k]`, keeps slope of signal.
+    #[default]
+    Odd,
+    /// Mirror reflection without repeating the edge sample, `x[k]`.
+    Even,
+    /// Repeated edge sample.
+    Constant,
+    /// Signal is not extended.
+    None,
+}
+
+/// How edges of signal are treated by [`FiltFilt`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FiltFiltMethod {
+    /// Signal is padded and both passes start from steady state of the edge sample.
+    Pad(EdgePadding),
+    /// No padding, initial states of both passes are chosen (least squares) so that result
+    /// matches the one of filtering in reverse order, backward and then forward
+    /// (F. Gustafsson, Determining the initial states in forward-backward filtering, 1996).
+    Gustafsson,
+}
+
+impl Default for FiltFiltMethod {
+    fn default() -> Self {
+        FiltFiltMethod::Pad(EdgePadding::Odd)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Recursion {
+    /// Normalized numerator and denominator of the same length.
+    TransferFunction(Vec<f64>, Vec<f64>),
+    /// Normalized `[b0, b1, b2, 1, a1, a2]` rows.
+    Sos(Vec<[f64; 6]>),
+}
+
+/// Zero-phase filtering: signal is filtered forward and then backward, so phase shifts cancel
+/// and magnitude response is squared. It is not causal, so it is for offline processing only.
+/// Output has the same number of samples and the same timestamps as input.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FiltFilt {
+    recursion: Recursion,
+    method: FiltFiltMethod,
+    pad_length: Option<usize>,
+}
+
+impl FiltFilt {
+    /// Filter with transfer function `b / a` (coefficients from `z^0`). Panics when `a[0]` is zero
+    /// or there are no coefficients.
+    pub fn new(b: &[f64], a: &[f64]) -> Self {
+        assert!(!b.is_empty() && !a.is_empty(), "filter shall have coefficients");
+        assert!(a[0] != 0.0, "the first denominator coefficient shall not be zero");
+        let length = b.len().max(a.len());
+        let normalize = |c: &[f64]| (0..length).map(|i| c.get(i).copied().unwrap_or(0.0) / a[0]).collect();
+        Self::with_recursion(Recursion::TransferFunction(normalize(b), normalize(a)))
+    }
+
+    pub fn fir(taps: &[f64]) -> Self {
+        Self::new(taps, &[1.0])
+    }
+
+    /// Filter given as cascade of second order sections, numerically better for high orders.
+    pub fn sos(filter: &SosFilter) -> Self {
+        Self::with_recursion(Recursion::Sos(filter.coefficients()))
+    }
+
+    fn with_recursion(recursion: Recursion) -> Self {
+        Self{recursion, method: FiltFiltMethod::default(), pad_length: None}
+    }
+
+    /// Default is odd padding.
+    pub fn set_method(mut self, method: FiltFiltMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Number of samples added at each edge. Default is 3 times number of coefficients of
+    /// transfer function (or of equivalent one for SOS). Shorter signals are padded with
+    /// one sample less than they have, as reflection needs them.
+    pub fn set_pad_length(mut self, length: usize) -> Self {
+        self.pad_length = Some(length);
+        self
+    }
+
+    pub fn method(&self) -> FiltFiltMethod {
+        self.method
+    }
+
+    pub fn pad_length(&self) -> usize {
+        self.pad_length.unwrap_or_else(|| match &self.recursion {
+            Recursion::TransferFunction(b, _) => 3 * b.len(),
+            Recursion::Sos(sos) => {
+                // sections with b2 = a2 = 0 are of the first order
+                let first_order = sos.iter().filter(|s| s[2] == 0.0).count().min(sos.iter().filter(|s| s[5] == 0.0).count());
+                3 * (2 * sos.len() + 1 - first_order)
+            }
+        })
+    }
+
+    /// Filters whole signal, output samples keep timestamps of the input ones.
+    pub fn filter(&self, signal: &DescreteSignal, output: &mut DescreteSignal) {
+        output.set_metadata(signal.metadata().clone());
+        let values = self.filter_values(&signal.values().collect::<Vec<f64>>());
+        for (t, y) in signal.times().zip(values) {
+            output.push(t, y);
+        }
+    }
+
+    /// Panics when `input` and `output` differ in length, see also [`FiltFilt::filter`].
+    pub fn filter_block(&self, input: &[f64], output: &mut [f64]) {
+        assert_eq!(input.len(), output.len(), "output block shall be as long as input block");
+        output.copy_from_slice(&self.filter_values(input));
+    }
+
+    fn filter_values(&self, input: &[f64]) -> Vec<f64> {
+        if input.is_empty() {
+            return vec![];
+        }
+        match self.method {
+            FiltFiltMethod::Pad(padding) => self.filter_padded(input, padding),
+            FiltFiltMethod::Gustafsson => self.filter_gustafsson(input),
+        }
+    }
+
+    fn filter_padded(&self, input: &[f64], padding: EdgePadding) -> Vec<f64> {
+        let length = if padding == EdgePadding::None { 0 } else { self.pad_length().min(input.len() - 1) };
+        let extended = extend(input, padding, length);
+        let steady_state = self.recursion.steady_state();
+        let scaled = |x: f64| steady_state.iter().map(|s| s * x).collect::<Vec<f64>>();
+
+        let forward = self.recursion.run(&extended, &scaled(extended[0]));
+        let reversed = forward.iter().rev().copied().collect::<Vec<f64>>();
+        let backward = self.recursion.run(&reversed, &scaled(reversed[0]));
+        backward.iter().rev().skip(length).take(input.len()).copied().collect()
+    }
+
+    fn filter_gustafsson(&self, input: &[f64]) -> Vec<f64> {
+        let order = self.recursion.state_size();
+        let reverse = |x: &[f64]| x.iter().rev().copied().collect::<Vec<f64>>();
+        let zero_state = vec![0.0; order];
+        let m = input.len();
+
+        // columns of observability matrix propagate initial state to output, S filters it reversed;
+        // both decay like impulse response, so only rows within `edge` samples of signal edges are kept
+        let observability = self.recursion.zero_input_responses(m);
+        let edge = observability.first().map_or(0, Vec::len);
+        let s = observability.iter().map(|o| self.recursion.run(&reverse(o), &zero_state)).collect::<Vec<Vec<f64>>>();
+        let rows = (0..m).filter(|i| *i < edge || *i >= m - edge).collect::<Vec<usize>>();
+        let matrix = rows.iter().map(|&i| {
+            let forward = (0..order).map(|k| if i < edge { s[k][edge - 1 - i] - observability[k][i] } else { 0.0 });
+            let backward = (0..order).map(|k| if i >= m - edge { observability[k][m - 1 - i] - s[k][i + edge - m] } else { 0.0 });
+            forward.chain(backward).collect()
+        }).collect::<Vec<Vec<f64>>>();
+
+        let forward_backward = reverse(&self.recursion.run(&reverse(&self.recursion.run(input, &zero_state)), &zero_state));
+        let backward_forward = self.recursion.run(&reverse(&self.recursion.run(&reverse(input), &zero_state)), &zero_state);
+        let delta = rows.iter().map(|&i| backward_forward[i] - forward_backward[i]).collect::<Vec<f64>>();
+        // too short signal (or filter without state) is filtered from zero state
+        let initial = least_squares(&matrix, &delta).unwrap_or_else(|| vec![0.0; 2 * order]);
+
+        let forward = self.recursion.run(input, &initial[..order]);
+        reverse(&self.recursion.run(&reverse(&forward), &initial[order..]))
+    }
+}
+
+/// Zero-phase filtering of signal with transfer function `b / a` and default settings of [`FiltFilt`].
+pub fn filtfilt(b: &[f64], a: &[f64], signal: &DescreteSignal, output: &mut DescreteSignal) {
+    FiltFilt::new(b, a).filter(signal, output);
+}
+
+impl Recursion {
+    fn state_size(&self) -> usize {
+        match self {
+            Recursion::TransferFunction(b, _) => b.len() - 1,
+            Recursion::Sos(sos) => 2 * sos.len(),
+        }
+    }
+
+    /// Filters input starting from given state (transposed direct form II).
+    fn run(&self, input: &[f64], state: &[f64]) -> Vec<f64> {
+        match self {
+            Recursion::TransferFunction(b, a) => transposed_direct_form(b, a, input, state),
+            Recursion::Sos(sos) => sos.iter().zip(state.chunks(2)).fold(input.to_vec(), |x, (section, state)| {
+                transposed_direct_form(&section[..3], &section[3..], &x, state)
+            }),
+        }
+    }
+
+    /// Output of zero input for every unit initial state, long enough for all of them to decay,
+    /// but at most `len` samples.
+    fn zero_input_responses(&self, len: usize) -> Vec<Vec<f64>> {
+        let order = self.state_size();
+        let mut length = (16 * order).max(64).min(len);
+        loop {
+            let responses = (0..order).map(|k| {
+                let mut state = vec![0.0; order];
+                state[k] = 1.0;
+                self.run(&vec![0.0; length], &state)
+            }).collect::<Vec<Vec<f64>>>();
+            let peak = responses.iter().flatten().fold(0.0, |m: f64, y| m.max(y.abs()));
+            let tail = responses.iter().flat_map(|r| &r[length / 2..]).fold(0.0, |m: f64, y| m.max(y.abs()));
+            if length == len || tail <= peak * 1e-15 {
+                return responses;
+            }
+            length = (2 * length).min(len);
+        }
+    }
+
+    /// State after long constant input of value 1.
+    fn steady_state(&self) -> Vec<f64> {
+        match self {
+            Recursion::TransferFunction(b, a) => steady_state(b, a, 1.0).0,
+            Recursion::Sos(sos) => {
+                let mut x = 1.0;
+                let mut result = vec![];
+                for section in sos {
+                    let (state, y) = steady_state(&section[..3], &section[3..], x);
+                    result.extend(state);
+                    x = y;
+                }
+                result
+            }
+        }
+    }
+}
+
+/// State and output for constant input `x`. Filter with pole at DC has no steady state,
+/// it starts from zero state then.
+fn steady_state(b: &[f64], a: &[f64], x: f64) -> (Vec<f64>, f64) {
+    let denominator = a.iter().sum::<f64>();
+    if denominator == 0.0 {
+        return (vec![0.0; b.len() - 1], 0.0);
+    }
+    let y = x * b.iter().sum::<f64>() / denominator;
+    let mut state = vec![0.0; b.len() - 1];
+    let mut sum = 0.0;
+    for i in (0..state.len()).rev() {
+        sum += b[i + 1] * x - a[i + 1] * y;
+        state[i] = sum;
+    }
+    (state, y)
+}
+
+/// Signal with `length` samples added at both edges, signal shall be longer than `length`.
+fn extend(input: &[f64], padding: EdgePadding, length: usize) -> Vec<f64> {
+    if padding == EdgePadding::None || length == 0 {
+        return input.to_vec();
+    }
+    let (first, last) = (input[0], input[input.len() - 1]);
+    let n = input.len();
+    let (left, right): (Vec<f64>, Vec<f64>) = match padding {
+        EdgePadding::Odd => ((1..=length).rev().map(|k| 2.0 * first - input[k]).collect(),
+                             (1..=length).map(|k| 2.0 * last - input[n - 1 - k]).collect()),
+        EdgePadding::Even => ((1..=length).rev().map(|k| input[k]).collect(),
+                              (1..=length).map(|k| input[n - 1 - k]).collect()),
+        EdgePadding::Constant => (vec![first; length], vec![last; length]),
+        EdgePadding::None => (vec![], vec![]),
+    };
+    left.into_iter().chain(input.iter().copied()).chain(right).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::{FilterAnalysis, IirDesign};
+
+    fn low_pass() -> SosFilter {
+        IirDesign::low_pass(50.0, 1000.0).set_order(4).design().unwrap()
+    }
+
+    fn sine(f: f64, n: usize) -> Vec<f64> {
+        (0..n).map(|i| (2.0 * PI * f * i as f64).sin()).collect()
+    }
+
+    #[test]
+    fn filtered_sine_shall_not_be_shifted() {
+        let input = sine(0.03, 400);
+        let sos = low_pass();
+        let expected = FilterAnalysis::sos(&sos, 1.0).response(0.03).module().powi(2);
+        for method in [FiltFiltMethod::Pad(EdgePadding::Odd), FiltFiltMethod::Gustafsson] {
+            let mut output = vec![0.0; input.len()];
+            FiltFilt::sos(&sos).set_method(method).filter_block(&input, &mut output);
+            // transients of Gustafsson method are longer
+            for (y, x) in output.iter().zip(&input).skip(100).take(200) {
+                assert!((y - expected * x).abs() < 1e-3, "{:?} {} {}", method, y, x);
+            }
+        }
+    }
+
+    #[test]
+    fn constant_signal_shall_pass_without_transients() {
+        let input = vec![2.5; 50];
+        let fir = [0.25, 0.5, 0.25];
+        for padding in [EdgePadding::Odd, EdgePadding::Even, EdgePadding::Constant, EdgePadding::None] {
+            for filter in [FiltFilt::sos(&low_pass()), FiltFilt::fir(&fir)] {
+                let mut output = vec![0.0; input.len()];
+                filter.set_method(FiltFiltMethod::Pad(padding)).filter_block(&input, &mut output);
+                for y in output {
+                    assert!((y - 2.5).abs() < 1e-12, "{:?} {}", padding, y);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn padding_shall_reflect_edges() {
+        let input = [1.0, 2.0, 4.0, 7.0];
+
+        assert_eq!(extend(&input, EdgePadding::Odd, 2), vec![-2.0, 0.0, 1.0, 2.0, 4.0, 7.0, 10.0, 12.0]);
+        assert_eq!(extend(&input, EdgePadding::Even, 2), vec![4.0, 2.0, 1.0, 2.0, 4.0, 7.0, 4.0, 2.0]);
+        assert_eq!(extend(&input, EdgePadding::Constant, 1), vec![1.0, 1.0, 2.0, 4.0, 7.0, 7.0]);
+        assert_eq!(extend(&input, EdgePadding::None, 2), input.to_vec());
+    }
+
+    #[test]
+    fn transfer_function_and_sos_shall_give_the_same_result() {
+        let input = (0..200).map(|i| (i as f64 * 0.1).sin() + 0.01 * i as f64 + if i == 60 { 1.0 } else { 0.0 }).collect::<Vec<f64>>();
+        let sos = low_pass();
+        let tf = sos.to_transfer_function();
+        for method in [FiltFiltMethod::Pad(EdgePadding::Even), FiltFiltMethod::Gustafsson] {
+            let mut expected = vec![0.0; input.len()];
+            let mut actual = vec![0.0; input.len()];
+            FiltFilt::sos(&sos).set_method(method).filter_block(&input, &mut expected);
+            FiltFilt::new(tf.b(), tf.a()).set_method(method).filter_block(&input, &mut actual);
+            for (a, e) in actual.iter().zip(&expected) {
+                assert!((a - e).abs() < 1e-9, "{:?} {} {}", method, a, e);
+            }
+        }
+        assert_eq!(FiltFilt::sos(&sos).pad_length(), 15);
+        assert_eq!(FiltFilt::new(tf.b(), tf.a()).pad_length(), 15);
+    }
+
+    #[test]
+    fn fir_shall_be_applied_twice_without_delay() {
+        let fir = [0.2, 0.5, 0.3];
+        let mut input = vec![0.0; 21];
+        input[10] = 1.0;
+        let mut output = vec![0.0; input.len()];
+        FiltFilt::fir(&fir).filter_block(&input, &mut output);
+
+        // autocorrelation of taps centered on impulse
+        let expected = [0.06, 0.25, 0.38, 0.25, 0.06];
+        for (i, y) in output.iter().enumerate() {
+            let e = if (8..=12).contains(&i) { expected[i - 8] } else { 0.0 };
+            assert!((y - e).abs() < 1e-12, "{} {} {}", i, y, e);
+        }
+    }
+
+    #[test]
+    fn gustafsson_result_shall_not_depend_on_direction() {
+        let input = sine(0.02, 300).iter().enumerate().map(|(i, x)| x + 1.0 + 0.002 * i as f64).collect::<Vec<f64>>();
+        let reversed = input.iter().rev().copied().collect::<Vec<f64>>();
+        let difference = |filter: FiltFilt| {
+            let (mut forward, mut backward) = (vec![0.0; input.len()], vec![0.0; input.len()]);
+            filter.filter_block(&input, &mut forward);
+            filter.filter_block(&reversed, &mut backward);
+            forward.iter().zip(backward.iter().rev()).map(|(f, b)| (f - b).abs()).fold(0.0, f64::max)
+        };
+
+        let gustafsson = difference(FiltFilt::sos(&low_pass()).set_method(FiltFiltMethod::Gustafsson));
+        let unpadded = difference(FiltFilt::sos(&low_pass()).set_method(FiltFiltMethod::Pad(EdgePadding::None)));
+        assert!(gustafsson < 1e-12, "{}", gustafsson);
+        assert!(unpadded > 1e-3, "{}", unpadded);
+    }
+
+    #[test]
+    fn gustafsson_method_shall_solve_only_edges_of_long_signal() {
+        let input = sine(0.02, 20000).iter().enumerate().map(|(i, x)| x + 1.0 + 0.0001 * i as f64).collect::<Vec<f64>>();
+        let filter = FiltFilt::sos(&low_pass()).set_method(FiltFiltMethod::Gustafsson);
+        let responses = filter.recursion.zero_input_responses(input.len());
+        assert!(responses.iter().all(|r| r.len() <= 2048), "{}", responses[0].len());
+
+        let (mut forward, mut backward) = (vec![0.0; input.len()], vec![0.0; input.len()]);
+        filter.filter_block(&input, &mut forward);
+        filter.filter_block(&input.iter().rev().copied().collect::<Vec<f64>>(), &mut backward);
+        for (f, b) in forward.iter().zip(backward.iter().rev()) {
+            assert!((f - b).abs() < 1e-10, "{} {}", f, b);
+        }
+    }
+
+    #[test]
+    fn timestamps_and_metadata_shall_be_preserved() {
+        let signal = DescreteSignal::new_from_vec((0..40).map(|i| (0.1 * (i * i) as f64, (i % 5) as f64)).collect());
+        let mut output = DescreteSignal::new();
+        filtfilt(&[0.5, 0.5], &[1.0, -0.2], &signal, &mut output);
+
+        assert_eq!(output.len(), signal.len());
+        assert_eq!(output.times().collect::<Vec<f64>>(), signal.times().collect::<Vec<f64>>());
+        assert_eq!(output.metadata(), signal.metadata());
+    }
+
+    #[test]
+    fn pad_length_shall_be_limited_by_short_signal() {
+        let input = [1.0, 3.0, 2.0, 5.0, 4.0, 0.0, 1.0, 2.0, 6.0, 3.0];
+        let taps = [0.1, 0.4, 0.4, 0.1];
+        let mut expected = [0.0; 10];
+        FiltFilt::fir(&taps).set_pad_length(9).filter_block(&input, &mut expected);
+
+        let mut output = DescreteSignal::new();
+        filtfilt(&taps, &[1.0], &DescreteSignal::new_from_vec(input.iter().enumerate().map(|(i, x)| (i as f64, *x)).collect()), &mut output);
+        assert_eq!(output.values().collect::<Vec<f64>>(), expected);
+        let mut single = [0.0];
+        FiltFilt::fir(&taps).filter_block(&[2.0], &mut single);
+        assert!((single[0] - 2.0).abs() < 1e-12);
+    }
+}
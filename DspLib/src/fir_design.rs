@@ -0,0 +1,577 @@
+use std::f64::consts::PI;
+use std::fmt::Display;
+
+/// Window applied to ideal (sinc) impulse response. All windows are symmetric.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Window {
+    Rectangular,
+    Hann,
+    Hamming,
+    Blackman,
+    /// Kaiser window with shape parameter beta, see [`kaiser_beta`].
+    Kaiser(f64),
+}
+
+impl Window {
+    /// Kaiser window with beta that gives required stopband attenuation (in dB).
+    pub fn kaiser_for_attenuation(attenuation: f64) -> Self {
+        Window::Kaiser(kaiser_beta(attenuation))
+    }
+
+    pub fn coefficients(&self, len: usize) -> Vec<f64> {
+        if len == 1 {
+            return vec![1.0];
+        }
+        let m = (len - 1) as f64;
+        (0..len).map(|n| {
+            let x = n as f64 / m;
+            match self {
+                Window::Rectangular => 1.0,
+                Window::Hann => 0.5 - 0.5 * (2.0 * PI * x).cos(),
+                Window::Hamming => 0.54 - 0.46 * (2.0 * PI * x).cos(),
+                Window::Blackman => 0.42 - 0.5 * (2.0 * PI * x).cos() + 0.08 * (4.0 * PI * x).cos(),
+                Window::Kaiser(beta) => {
+                    let r = 2.0 * x - 1.0;
+                    bessel_i0(beta * (1.0 - r * r).max(0.0).sqrt()) / bessel_i0(*beta)
+                }
+            }
+        }).collect()
+    }
+
+    /// Number of taps needed for transition band of given width (relative to sampling rate).
+    fn number_of_taps(&self, relative_width: f64) -> usize {
+        let factor = match self {
+            Window::Rectangular => 0.9,
+            Window::Hann => 3.1,
+            Window::Hamming => 3.3,
+            Window::Blackman => 5.5,
+            Window::Kaiser(beta) => return kaiser_taps(kaiser_attenuation(*beta), relative_width),
+        };
+        (factor / relative_width).ceil() as usize
+    }
+}
+
+/// Kaiser window beta for required stopband attenuation in dB (Kaiser's empirical formula).
+pub fn kaiser_beta(attenuation: f64) -> f64 {
+    if attenuation > 50.0 {
+        0.1102 * (attenuation - 8.7)
+    } else if attenuation >= 21.0 {
+        0.5842 * (attenuation - 21.0).powf(0.4) + 0.07886 * (attenuation - 21.0)
+    } else {
+        0.0
+    }
+}
+
+/// Number of taps of Kaiser windowed filter with given stopband attenuation (dB)
+/// and transition band width (Hz).
+pub fn kaiser_order(attenuation: f64, transition_width: f64, sample_rate: f64) -> usize {
+    kaiser_taps(attenuation, transition_width / sample_rate)
+}
+
+fn kaiser_taps(attenuation: f64, relative_width: f64) -> usize {
+    ((attenuation - 7.95) / (14.36 * relative_width)).ceil().max(0.0) as usize + 1
+}
+
+/// Inverse of [`kaiser_beta`], used to estimate length of filter with Kaiser window.
+fn kaiser_attenuation(beta: f64) -> f64 {
+    if beta > 4.55 {
+        beta / 0.1102 + 8.7
+    } else if beta > 0.0 {
+        let (mut low, mut high) = (21.0, 50.0);
+        for _ in 0..60 {
+            let mid = 0.5 * (low + high);
+            if kaiser_beta(mid) < beta { low = mid } else { high = mid }
+        }
+        0.5 * (low + high)
+    } else {
+        21.0
+    }
+}
+
+/// Modified Bessel function of the first kind, order zero.
+fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let y = x * x / 4.0;
+    for k in 1..200 {
+        term *= y / (k * k) as f64;
+        sum += term;
+        if term < sum * 1e-17 {
+            break;
+        }
+    }
+    sum
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FirDesignError {
+    InvalidSampleRate(f64),
+    /// Cutoff frequency shall be between 0 and half of sampling rate.
+    InvalidCutoff { cutoff: f64, nyquist: f64 },
+    /// Band edges are not sorted, bands overlap or exceed Nyquist frequency.
+    InvalidBand { start: f64, end: f64 },
+    /// Response with non-zero gain at Nyquist frequency needs odd number of taps.
+    EvenNumberOfTaps(usize),
+    InvalidTransitionWidth(f64),
+    InvalidNumberOfTaps(usize),
+    /// Neither number of taps nor transition width was given.
+    MissingLength,
+    MissingBands,
+    /// Number of desired values or weights differs from number of bands.
+    BandCountMismatch { bands: usize, values: usize },
+    InvalidWeight(f64),
+    /// Desired gain is not finite.
+    InvalidGain(f64),
+    /// Bands do not constrain response enough to find unique solution.
+    IllConditioned,
+    /// Number of gains or phases differs from number of frequency points.
+    PointCountMismatch { frequencies: usize, values: usize },
+}
+
+impl Display for FirDesignError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FirDesignError::InvalidSampleRate(rate) => write!(f, "invalid sampling rate {}", rate),
+            FirDesignError::InvalidCutoff{cutoff, nyquist} =>
+                write!(f, "cutoff frequency {} shall be between 0 and Nyquist frequency {}", cutoff, nyquist),
+            FirDesignError::InvalidBand{start, end} => write!(f, "invalid band from {} to {}", start, end),
+            FirDesignError::EvenNumberOfTaps(taps) =>
+                write!(f, "response with non-zero gain at Nyquist frequency needs odd number of taps, got {}", taps),
+            FirDesignError::InvalidTransitionWidth(width) => write!(f, "invalid transition width {}", width),
+            FirDesignError::InvalidNumberOfTaps(taps) => write!(f, "invalid number of taps {}", taps),
+            FirDesignError::MissingLength => write!(f, "either number of taps or transition width shall be set"),
+            FirDesignError::MissingBands => write!(f, "at least one band shall be given"),
+            FirDesignError::BandCountMismatch{bands, values} =>
+                write!(f, "there are {} bands, but {} values are given for them", bands, values),
+            FirDesignError::InvalidWeight(weight) => write!(f, "weight {} shall be positive", weight),
+            FirDesignError::InvalidGain(gain) => write!(f, "desired gain {} shall be finite", gain),
+            FirDesignError::IllConditioned => write!(f, "design equations are singular, bands are too narrow for filter length"),
+            FirDesignError::PointCountMismatch{frequencies, values} =>
+                write!(f, "there are {} frequency points, but {} values are given for them", frequencies, values),
+        }
+    }
+}
+
+impl std::error::Error for FirDesignError {}
+
+/// Method used to turn desired response into FIR coefficients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DesignMethod {
+    /// Ideal (sinc) impulse response multiplied by window.
+    WindowedSinc,
+    /// Desired response sampled at `k * fs / N` and transformed back with inverse DFT, then windowed.
+    /// Response exactly matches desired one at sampled frequencies when rectangular window is used.
+    FrequencySampling,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Response {
+    LowPass(f64),
+    HighPass(f64),
+    BandPass(f64, f64),
+    BandStop(f64, f64),
+    /// Bands as (start, end, gain), there is no gain between bands.
+    Multiband(Vec<(f64, f64, f64)>),
+}
+
+/// Design of linear phase FIR filters. Length of filter is given directly or calculated
+/// from transition band width, in the latter case it is always odd. Default window is Hamming
+/// and default method is windowed sinc. Cutoff frequencies are -6 dB points.
+///
+/// Filters with non-zero gain at Nyquist frequency (high-pass, band-stop) need odd number
+/// of taps, because symmetric filter with even number of taps has always zero there.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FirDesign {
+    response: Response,
+    sample_rate: f64,
+    number_of_taps: Option<usize>,
+    transition_width: Option<f64>,
+    window: Window,
+    method: DesignMethod,
+}
+
+impl FirDesign {
+    fn new(response: Response, sample_rate: f64) -> Self {
+        Self{
+            response,
+            sample_rate,
+            number_of_taps: None,
+            transition_width: None,
+            window: Window::Hamming,
+            method: DesignMethod::WindowedSinc,
+        }
+    }
+
+    /// Low-pass filter with cutoff frequency (in Hz) and sampling rate. Its DC gain is 1.
+    pub fn low_pass(cutoff: f64, sample_rate: f64) -> Self {
+        Self::new(Response::LowPass(cutoff), sample_rate)
+    }
+
+    /// High-pass filter with gain 1 at Nyquist frequency. Windowed sinc design is made by
+    /// spectral inversion of low-pass filter, so its DC gain is exactly zero.
+    pub fn high_pass(cutoff: f64, sample_rate: f64) -> Self {
+        Self::new(Response::HighPass(cutoff), sample_rate)
+    }
+
+    /// Band-pass filter with gain 1 in the center of the pass band.
+    pub fn band_pass(low_cutoff: f64, high_cutoff: f64, sample_rate: f64) -> Self {
+        Self::new(Response::BandPass(low_cutoff, high_cutoff), sample_rate)
+    }
+
+    /// Band-stop (notch) filter with DC gain 1.
+    pub fn band_stop(low_cutoff: f64, high_cutoff: f64, sample_rate: f64) -> Self {
+        Self::new(Response::BandStop(low_cutoff, high_cutoff), sample_rate)
+    }
+
+    /// Filter with piecewise constant response given as `(start, end, gain)` bands in Hz.
+    /// Bands shall be sorted and shall not overlap, response between bands is zero.
+    /// Filter is scaled to have exact gain of the first band with non-zero gain in its center.
+    pub fn multiband(bands: &[(f64, f64, f64)], sample_rate: f64) -> Self {
+        Self::new(Response::Multiband(bands.to_vec()), sample_rate)
+    }
+
+    pub fn set_number_of_taps(mut self, taps: usize) -> Self {
+        self.number_of_taps = Some(taps);
+        self
+    }
+
+    /// Width of transition band in Hz, it is used when number of taps is not set.
+    pub fn set_transition_width(mut self, width: f64) -> Self {
+        self.transition_width = Some(width);
+        self
+    }
+
+    pub fn set_window(mut self, window: Window) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Selects Kaiser window that gives required stopband attenuation (in dB).
+    pub fn set_attenuation(mut self, attenuation: f64) -> Self {
+        self.window = Window::kaiser_for_attenuation(attenuation);
+        self
+    }
+
+    pub fn set_method(mut self, method: DesignMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    pub fn design(&self) -> Result<Vec<f64>, FirDesignError> {
+        if !(self.sample_rate > 0.0 && self.sample_rate.is_finite()) {
+            return Err(FirDesignError::InvalidSampleRate(self.sample_rate));
+        }
+        let bands = self.bands()?;
+        let taps = self.number_of_taps()?;
+        let nyquist = self.sample_rate / 2.0;
+        if taps % 2 == 0 && bands.iter().any(|(_, end, gain)| *end == nyquist && *gain != 0.0) {
+            return Err(FirDesignError::EvenNumberOfTaps(taps));
+        }
+
+        let window = self.window.coefficients(taps);
+        let mut fir = match (&self.method, &self.response) {
+            (DesignMethod::WindowedSinc, Response::HighPass(cutoff)) => {
+                let mut fir = FirDesign::low_pass(*cutoff, self.sample_rate)
+                    .set_number_of_taps(taps)
+                    .set_window(self.window)
+                    .design()?;
+                for h in fir.iter_mut() {
+                    *h = -*h;
+                }
+                fir[taps / 2] += 1.0;
+                return Ok(fir);
+            }
+            (DesignMethod::WindowedSinc, _) => self.windowed_sinc(&bands, taps),
+            (DesignMethod::FrequencySampling, _) => self.frequency_sampling(&bands, taps),
+        };
+        for (h, w) in fir.iter_mut().zip(&window) {
+            *h *= w;
+        }
+
+        let (frequency, gain) = reference_point(&bands, nyquist);
+        let scale = gain / amplitude(&fir, frequency / self.sample_rate);
+        for h in fir.iter_mut() {
+            *h *= scale;
+        }
+        Ok(fir)
+    }
+
+    /// Desired response as sorted list of `(start, end, gain)` bands, validated.
+    fn bands(&self) -> Result<Vec<(f64, f64, f64)>, FirDesignError> {
+        let nyquist = self.sample_rate / 2.0;
+        let check = |cutoff: f64| if cutoff > 0.0 && cutoff < nyquist {
+            Ok(cutoff)
+        } else {
+            Err(FirDesignError::InvalidCutoff{cutoff, nyquist})
+        };
+        let check_pair = |low: f64, high: f64| if low < high {
+            Ok((check(low)?, check(high)?))
+        } else {
+            Err(FirDesignError::InvalidBand{start: low, end: high})
+        };
+
+        Ok(match &self.response {
+            Response::LowPass(cutoff) => vec![(0.0, check(*cutoff)?, 1.0)],
+            Response::HighPass(cutoff) => vec![(check(*cutoff)?, nyquist, 1.0)],
+            Response::BandPass(low, high) => {
+                let (low, high) = check_pair(*low, *high)?;
+                vec![(low, high, 1.0)]
+            }
+            Response::BandStop(low, high) => {
+                let (low, high) = check_pair(*low, *high)?;
+                vec![(0.0, low, 1.0), (high, nyquist, 1.0)]
+            }
+            Response::Multiband(bands) => {
+                let mut previous_end = 0.0;
+                for &(start, end, gain) in bands {
+                    if !(start >= previous_end && start < end && end <= nyquist && gain.is_finite()) {
+                        return Err(FirDesignError::InvalidBand{start, end});
+                    }
+                    previous_end = end;
+                }
+                if bands.iter().all(|(_, _, gain)| *gain == 0.0) {
+                    return Err(FirDesignError::InvalidBand{start: 0.0, end: nyquist});
+                }
+                bands.clone()
+            }
+        })
+    }
+
+    /// Ideal impulse response, every band is difference of two ideal low-pass filters.
+    fn windowed_sinc(&self, bands: &[(f64, f64, f64)], taps: usize) -> Vec<f64> {
+        let center = (taps - 1) as f64 / 2.0;
+        let ideal_low_pass = |f: f64, m: f64| 2.0 * f * sinc(2.0 * f * m);
+        (0..taps).map(|n| {
+            let m = n as f64 - center;
+            bands.iter()
+                .map(|(start, end, gain)| {
+                    gain * (ideal_low_pass(end / self.sample_rate, m) - ideal_low_pass(start / self.sample_rate, m))
+                })
+                .sum()
+        }).collect()
+    }
+
+    /// Linear phase inverse DFT of desired amplitude sampled at `k * fs / N`.
+    fn frequency_sampling(&self, bands: &[(f64, f64, f64)], taps: usize) -> Vec<f64> {
+        let center = (taps - 1) as f64 / 2.0;
+        let n = taps as f64;
+        let desired = |f: f64| {
+            let mut result = 0.0;
+            for (start, end, gain) in bands {
+                if (f > *start && f < *end) || (f == 0.0 && *start == 0.0) {
+                    return *gain;
+                } else if f == *start || f == *end {
+                    result += gain / 2.0;
+                }
+            }
+            result
+        };
+        let amplitudes = (0..=(taps - 1) / 2)
+            .map(|k| desired(k as f64 * self.sample_rate / n))
+            .collect::<Vec<f64>>();
+
+        (0..taps).map(|i| {
+            let mut h = amplitudes[0];
+            for (k, a) in amplitudes.iter().enumerate().skip(1) {
+                h += 2.0 * a * (2.0 * PI * k as f64 * (i as f64 - center) / n).cos();
+            }
+            h / n
+        }).collect()
+    }
+
+    fn number_of_taps(&self) -> Result<usize, FirDesignError> {
+        match (self.number_of_taps, self.transition_width) {
+            (Some(0), _) => Err(FirDesignError::InvalidNumberOfTaps(0)),
+            (Some(taps), _) => Ok(taps),
+            (None, Some(width)) if width > 0.0 && width < self.sample_rate / 2.0 => {
+                Ok(self.window.number_of_taps(width / self.sample_rate).max(1) | 1)
+            }
+            (None, Some(width)) => Err(FirDesignError::InvalidTransitionWidth(width)),
+            (None, None) => Err(FirDesignError::MissingLength),
+        }
+    }
+}
+
+/// Frequency where filter is scaled to have desired gain: DC or Nyquist frequency when the first
+/// band with non-zero gain starts or ends there, otherwise center of this band.
+fn reference_point(bands: &[(f64, f64, f64)], nyquist: f64) -> (f64, f64) {
+    let (start, end, gain) = bands.iter().find(|(_, _, gain)| *gain != 0.0).copied().unwrap_or((0.0, nyquist, 1.0));
+    if start == 0.0 {
+        (0.0, gain)
+    } else if end == nyquist {
+        (nyquist, gain)
+    } else {
+        ((start + end) / 2.0, gain)
+    }
+}
+
+/// Zero-phase amplitude of symmetric filter at frequency relative to sampling rate.
+fn amplitude(fir: &[f64], frequency: f64) -> f64 {
+    let center = (fir.len() - 1) as f64 / 2.0;
+    fir.iter().enumerate()
+        .map(|(n, h)| h * (2.0 * PI * frequency * (n as f64 - center)).cos())
+        .sum()
+}
+
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::fir_gain;
+
+    #[test]
+    fn low_pass_shall_be_symmetric_with_unity_dc_gain() {
+        for taps in [1, 2, 10, 51] {
+            let fir = FirDesign::low_pass(100.0, 1000.0).set_number_of_taps(taps).design().unwrap();
+            assert_eq!(fir.len(), taps);
+            assert!((fir.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+            for (a, b) in fir.iter().zip(fir.iter().rev()) {
+                assert!((a - b).abs() < 1e-15);
+            }
+        }
+    }
+
+    #[test]
+    fn low_pass_shall_attenuate_half_at_cutoff() {
+        let fir = FirDesign::low_pass(1000.0, 8000.0).set_number_of_taps(101).set_window(Window::Blackman).design().unwrap();
+
+        assert!((fir_gain(&fir, 1000.0, 8000.0) - 0.5).abs() < 0.01);
+        assert!((fir_gain(&fir, 200.0, 8000.0) - 1.0).abs() < 0.001);
+        assert!(fir_gain(&fir, 2000.0, 8000.0) < 0.001);
+    }
+
+    #[test]
+    fn kaiser_design_shall_meet_attenuation_in_stopband() {
+        let fir = FirDesign::low_pass(1000.0, 8000.0)
+            .set_transition_width(400.0)
+            .set_attenuation(60.0)
+            .design()
+            .unwrap();
+
+        assert_eq!(fir.len() % 2, 1);
+        assert_eq!(fir.len(), kaiser_order(60.0, 400.0, 8000.0) | 1);
+        for f in (1200..4000).step_by(25) {
+            assert!(20.0 * fir_gain(&fir, f as f64, 8000.0).log10() < -59.0, "{} Hz", f);
+        }
+    }
+
+    #[test]
+    fn number_of_taps_shall_grow_with_narrower_transition_band() {
+        let taps = |width: f64| FirDesign::low_pass(100.0, 1000.0).set_transition_width(width).design().unwrap().len();
+
+        assert_eq!(taps(100.0), 33);
+        assert!(taps(10.0) > 10 * 31);
+    }
+
+    #[test]
+    fn kaiser_window_shall_match_reference_values() {
+        assert!((kaiser_beta(60.0) - 5.65326).abs() < 1e-5);
+        assert!((kaiser_beta(30.0) - 2.11662).abs() < 1e-5);
+        assert_eq!(kaiser_beta(10.0), 0.0);
+        assert!((kaiser_attenuation(kaiser_beta(35.0)) - 35.0).abs() < 1e-9);
+
+        let w = Window::Kaiser(5.0).coefficients(5);
+        assert!((w[0] - 0.03671089).abs() < 1e-7);
+        assert!((w[1] - 0.55285177).abs() < 1e-7);
+        assert_eq!(w[2], 1.0);
+        assert_eq!(Window::Hann.coefficients(3), vec![0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn invalid_parameters_shall_be_rejected() {
+        assert_eq!(FirDesign::low_pass(600.0, 1000.0).set_number_of_taps(5).design(),
+                   Err(FirDesignError::InvalidCutoff{cutoff: 600.0, nyquist: 500.0}));
+        assert_eq!(FirDesign::low_pass(100.0, 0.0).design(), Err(FirDesignError::InvalidSampleRate(0.0)));
+        assert_eq!(FirDesign::low_pass(100.0, 1000.0).design(), Err(FirDesignError::MissingLength));
+        assert_eq!(FirDesign::low_pass(100.0, 1000.0).set_number_of_taps(0).design(),
+                   Err(FirDesignError::InvalidNumberOfTaps(0)));
+        assert_eq!(FirDesign::low_pass(100.0, 1000.0).set_transition_width(-1.0).design(),
+                   Err(FirDesignError::InvalidTransitionWidth(-1.0)));
+    }
+
+    #[test]
+    fn high_pass_shall_be_spectral_inversion_of_low_pass() {
+        let low = FirDesign::low_pass(300.0, 2000.0).set_number_of_taps(31).design().unwrap();
+        let high = FirDesign::high_pass(300.0, 2000.0).set_number_of_taps(31).design().unwrap();
+
+        for (n, (l, h)) in low.iter().zip(&high).enumerate() {
+            let delta = if n == 15 { 1.0 } else { 0.0 };
+            assert_eq!(*h, delta - l);
+        }
+        assert!(fir_gain(&high, 0.0, 2000.0) < 1e-12);
+        assert!((fir_gain(&high, 1000.0, 2000.0) - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn band_filters_shall_pass_and_stop_selected_band() {
+        let band_pass = FirDesign::band_pass(1000.0, 2000.0, 8000.0).set_transition_width(300.0)
+            .set_window(Window::Blackman).design().unwrap();
+        let band_stop = FirDesign::band_stop(1000.0, 2000.0, 8000.0).set_transition_width(300.0)
+            .set_window(Window::Blackman).design().unwrap();
+
+        assert!((fir_gain(&band_pass, 1500.0, 8000.0) - 1.0).abs() < 1e-12);
+        assert!(fir_gain(&band_pass, 300.0, 8000.0) < 0.001);
+        assert!(fir_gain(&band_pass, 3000.0, 8000.0) < 0.001);
+        assert!((fir_gain(&band_stop, 0.0, 8000.0) - 1.0).abs() < 1e-12);
+        assert!(fir_gain(&band_stop, 1500.0, 8000.0) < 0.001);
+        assert!((fir_gain(&band_stop, 3500.0, 8000.0) - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn multiband_filter_shall_follow_band_gains() {
+        let bands = [(0.0, 1000.0, 1.0), (1500.0, 2500.0, 0.5), (3000.0, 4000.0, 0.25)];
+        for method in [DesignMethod::WindowedSinc, DesignMethod::FrequencySampling] {
+            let fir = FirDesign::multiband(&bands, 8000.0).set_number_of_taps(201)
+                .set_window(Window::Blackman).set_method(method).design().unwrap();
+
+            assert!((fir_gain(&fir, 500.0, 8000.0) - 1.0).abs() < 0.01, "{:?}", method);
+            assert!((fir_gain(&fir, 2000.0, 8000.0) - 0.5).abs() < 0.01, "{:?}", method);
+            assert!(fir_gain(&fir, 1250.0, 8000.0) < 0.3, "{:?}", method);
+            assert!((fir_gain(&fir, 3500.0, 8000.0) - 0.25).abs() < 0.01, "{:?}", method);
+        }
+    }
+
+    #[test]
+    fn frequency_sampling_shall_match_desired_response_at_sampled_frequencies() {
+        let taps = 32;
+        let fir = FirDesign::low_pass(1000.0, 8000.0).set_number_of_taps(taps)
+            .set_window(Window::Rectangular).set_method(DesignMethod::FrequencySampling).design().unwrap();
+
+        for k in 0..taps / 2 {
+            let f = k as f64 * 8000.0 / taps as f64;
+            let expected = if f < 1000.0 { 1.0 } else if f == 1000.0 { 0.5 } else { 0.0 };
+            assert!((fir_gain(&fir, f, 8000.0) - expected).abs() < 1e-12, "{} Hz", f);
+        }
+    }
+
+    #[test]
+    fn even_number_of_taps_shall_be_rejected_when_nyquist_gain_is_not_zero() {
+        assert_eq!(FirDesign::high_pass(100.0, 1000.0).set_number_of_taps(10).design(),
+                   Err(FirDesignError::EvenNumberOfTaps(10)));
+        assert_eq!(FirDesign::band_stop(100.0, 200.0, 1000.0).set_number_of_taps(10).design(),
+                   Err(FirDesignError::EvenNumberOfTaps(10)));
+        assert!(FirDesign::band_pass(100.0, 200.0, 1000.0).set_number_of_taps(10).design().is_ok());
+        assert_eq!(FirDesign::high_pass(100.0, 1000.0).set_transition_width(50.0).design().unwrap().len() % 2, 1);
+    }
+
+    #[test]
+    fn invalid_bands_shall_be_rejected() {
+        assert_eq!(FirDesign::band_pass(200.0, 100.0, 1000.0).set_number_of_taps(11).design(),
+                   Err(FirDesignError::InvalidBand{start: 200.0, end: 100.0}));
+        assert_eq!(FirDesign::multiband(&[(0.0, 200.0, 1.0), (150.0, 300.0, 0.5)], 1000.0).set_number_of_taps(11).design(),
+                   Err(FirDesignError::InvalidBand{start: 150.0, end: 300.0}));
+        assert_eq!(FirDesign::multiband(&[(0.0, 600.0, 1.0)], 1000.0).set_number_of_taps(11).design(),
+                   Err(FirDesignError::InvalidBand{start: 0.0, end: 600.0}));
+    }
+}
@@ -0,0 +1,275 @@
+use std::f64::consts::PI;
+
+use crate::iir::transposed_direct_form;
+use crate::{ComplexNumber, DescreteSignal, SosFilter};
+
+/// Analysis of FIR, IIR (transfer function) or SOS filter without filtering test signals.
+/// Filter is kept as cascade of normalized rational sections, frequencies are in Hz.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FilterAnalysis {
+    /// Numerator and denominator (from `z^0`) of the same length.
+    sections: Vec<(Vec<f64>, Vec<f64>)>,
+    sample_rate: f64,
+}
+
+impl FilterAnalysis {
+    /// Panics when there are no taps or sampling rate is not positive.
+    pub fn fir(taps: &[f64], sample_rate: f64) -> Self {
+        Self::transfer_function(taps, &[1.0], sample_rate)
+    }
+
+    /// Filter `b / a`, panics when `a[0]` is zero, there are no coefficients or sampling rate
+    /// is not positive.
+    pub fn transfer_function(b: &[f64], a: &[f64], sample_rate: f64) -> Self {
+        assert!(!b.is_empty() && !a.is_empty(), "filter shall have coefficients");
+        assert!(a[0] != 0.0, "the first denominator coefficient shall not be zero");
+        let length = b.len().max(a.len());
+        let normalize = |c: &[f64]| (0..length).map(|i| c.get(i).copied().unwrap_or(0.0) / a[0]).collect();
+        Self::new(vec![(normalize(b), normalize(a))], sample_rate)
+    }
+
+    /// Panics when sampling rate is not positive.
+    pub fn sos(filter: &SosFilter, sample_rate: f64) -> Self {
+        let sections = filter.sections().iter().map(|s| (s.b().to_vec(), s.a().to_vec())).collect();
+        Self::new(sections, sample_rate)
+    }
+
+    fn new(sections: Vec<(Vec<f64>, Vec<f64>)>, sample_rate: f64) -> Self {
+        assert!(sample_rate > 0.0, "sampling rate shall be positive");
+        Self{sections, sample_rate}
+    }
+
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    /// Complex response at frequency in Hz.
+    pub fn response(&self, frequency: f64) -> ComplexNumber {
+        let z = ComplexNumber::from_polar(1.0, -2.0 * PI * frequency / self.sample_rate);
+        self.sections.iter().fold(ComplexNumber::new(1.0, 0.0), |h, (b, a)| {
+            &h * &(&evaluate(b, &z).0 / &evaluate(a, &z).0)
+        })
+    }
+
+    /// Group delay in seconds at frequency in Hz. It is not defined at zeros on unit circle,
+    /// where it is NaN or infinite.
+    pub fn group_delay(&self, frequency: f64) -> f64 {
+        let z = ComplexNumber::from_polar(1.0, -2.0 * PI * frequency / self.sample_rate);
+        // every polynomial contributes with Re(sum(k c_k z^-k) / sum(c_k z^-k)) samples
+        let delay = |c: &[f64]| {
+            let (value, weighted) = evaluate(c, &z);
+            (&weighted / &value).re()
+        };
+        self.sections.iter().map(|(b, a)| delay(b) - delay(a)).sum::<f64>() / self.sample_rate
+    }
+
+    /// Response at sorted frequencies. They shall be dense enough for phase unwrapping,
+    /// e.g. [`frequency_grid`].
+    pub fn frequency_response(&self, frequencies: &[f64]) -> FrequencyResponse {
+        FrequencyResponse{
+            frequencies: frequencies.to_vec(),
+            response: frequencies.iter().map(|f| self.response(*f)).collect(),
+            group_delay: frequencies.iter().map(|f| self.group_delay(*f)).collect(),
+        }
+    }
+
+    /// First `length` samples of response to unit impulse at time 0.
+    pub fn impulse_response(&self, length: usize) -> DescreteSignal {
+        let mut input = vec![0.0; length];
+        if let Some(x) = input.first_mut() {
+            *x = 1.0;
+        }
+        self.simulate(input)
+    }
+
+    /// First `length` samples of response to unit step at time 0.
+    pub fn step_response(&self, length: usize) -> DescreteSignal {
+        self.simulate(vec![1.0; length])
+    }
+
+    fn simulate(&self, input: Vec<f64>) -> DescreteSignal {
+        let output = self.sections.iter().fold(input, |x, (b, a)| {
+            transposed_direct_form(b, a, &x, &vec![0.0; b.len() - 1])
+        });
+        DescreteSignal::new_from_vec(output.iter().enumerate().map(|(i, y)| (i as f64 / self.sample_rate, *y)).collect())
+    }
+}
+
+/// Response of filter at given frequencies (in Hz).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrequencyResponse {
+    frequencies: Vec<f64>,
+    response: Vec<ComplexNumber>,
+    group_delay: Vec<f64>,
+}
+
+impl FrequencyResponse {
+    pub fn frequencies(&self) -> &[f64] {
+        &self.frequencies
+    }
+
+    /// Complex response.
+    pub fn response(&self) -> &[ComplexNumber] {
+        &self.response
+    }
+
+    pub fn magnitude(&self) -> Vec<f64> {
+        self.response.iter().map(|h| h.module()).collect()
+    }
+
+    /// Magnitude in dB, zeros of response give negative infinity.
+    pub fn magnitude_db(&self) -> Vec<f64> {
+        self.response.iter().map(|h| 20.0 * h.module().log10()).collect()
+    }
+
+    /// Phase in radians, unwrapped so that difference of neighbouring values is at most π.
+    pub fn phase(&self) -> Vec<f64> {
+        let mut offset = 0.0;
+        let mut previous: Option<f64> = None;
+        self.response.iter().map(|h| {
+            let phase = h.arg();
+            if let Some(previous) = previous {
+                offset -= 2.0 * PI * ((phase - previous) / (2.0 * PI)).round();
+            }
+            previous = Some(phase);
+            phase + offset
+        }).collect()
+    }
+
+    /// Group delay in seconds.
+    pub fn group_delay(&self) -> &[f64] {
+        &self.group_delay
+    }
+
+    /// Phase delay `-phase / (2π f)` in seconds, at 0 Hz it is the group delay.
+    pub fn phase_delay(&self) -> Vec<f64> {
+        self.phase().iter().zip(&self.frequencies).zip(&self.group_delay).map(|((phase, f), delay)| {
+            if *f == 0.0 { *delay } else { -phase / (2.0 * PI * f) }
+        }).collect()
+    }
+
+    /// Pairs of frequency and magnitude in dB, e.g. for plotting.
+    pub fn magnitude_db_data(&self) -> Vec<(f64, f64)> {
+        self.frequencies.iter().copied().zip(self.magnitude_db()).collect()
+    }
+}
+
+/// `count` equally spaced frequencies from 0 to Nyquist frequency (both included).
+pub fn frequency_grid(count: usize, sample_rate: f64) -> Vec<f64> {
+    match count {
+        0 => vec![],
+        1 => vec![0.0],
+        _ => (0..count).map(|i| sample_rate / 2.0 * i as f64 / (count - 1) as f64).collect(),
+    }
+}
+
+/// Values of `sum(c_k z^k)` and `sum(k c_k z^k)`, where `z` stands for `z^-1`.
+fn evaluate(coefficients: &[f64], z: &ComplexNumber) -> (ComplexNumber, ComplexNumber) {
+    let mut power = ComplexNumber::new(1.0, 0.0);
+    let mut value = ComplexNumber::default();
+    let mut weighted = ComplexNumber::default();
+    for (k, c) in coefficients.iter().enumerate() {
+        let term = &power * *c;
+        value = &value + &term;
+        weighted = &weighted + &(&term * k as f64);
+        power = &power * z;
+    }
+    (value, weighted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FirDesign, IirDesign, StreamFilter};
+
+    #[test]
+    fn linear_phase_fir_shall_have_constant_delay() {
+        let fs = 1000.0;
+        let fir = FirDesign::low_pass(100.0, fs).set_number_of_taps(11).design().unwrap();
+        let analysis = FilterAnalysis::fir(&fir, fs);
+        let frequencies = frequency_grid(101, fs).into_iter().filter(|f| *f < 150.0).collect::<Vec<f64>>();
+        let response = analysis.frequency_response(&frequencies);
+
+        for ((phase, f), (group, phase_delay)) in response.phase().iter().zip(&frequencies)
+            .zip(response.group_delay().iter().zip(response.phase_delay())) {
+            assert!((phase + 2.0 * PI * f * 0.005).abs() < 1e-9, "{} {}", f, phase);
+            assert!((group - 0.005).abs() < 1e-12 && (phase_delay - 0.005).abs() < 1e-12, "{} {} {}", f, group, phase_delay);
+        }
+        let dtft = fir.iter().enumerate().fold(ComplexNumber::default(), |sum, (n, h)| {
+            &sum + &ComplexNumber::from_polar(*h, -2.0 * PI * 0.1 * n as f64)
+        });
+        assert!((analysis.response(100.0).module() - dtft.module()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn first_order_iir_shall_match_analytic_response() {
+        let p: f64 = 0.8;
+        let analysis = FilterAnalysis::transfer_function(&[1.0 - p], &[1.0, -p], 1.0);
+        let response = analysis.frequency_response(&frequency_grid(51, 1.0));
+
+        for ((f, h), delay) in response.frequencies().iter().zip(response.magnitude()).zip(response.group_delay()) {
+            let w = 2.0 * PI * f;
+            let denominator = 1.0 - 2.0 * p * w.cos() + p * p;
+            assert!((h - (1.0 - p) / denominator.sqrt()).abs() < 1e-12);
+            assert!((delay - (p * w.cos() - p * p) / denominator).abs() < 1e-12);
+        }
+        assert_eq!(response.magnitude_db()[0], 0.0);
+        assert!((response.phase_delay()[0] - p / (1.0 - p)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn sos_and_transfer_function_shall_have_the_same_response() {
+        let fs = 8000.0;
+        let sos = IirDesign::band_pass(500.0, 1500.0, fs).set_order(3).design().unwrap();
+        let tf = sos.to_transfer_function();
+        let cascade = FilterAnalysis::sos(&sos, fs);
+        let direct = FilterAnalysis::transfer_function(tf.b(), tf.a(), fs);
+        // group delay is not defined at zeros at 0 Hz and Nyquist frequency
+        let frequencies = &frequency_grid(400, fs)[1..399];
+        let (expected, actual) = (direct.frequency_response(frequencies), cascade.frequency_response(frequencies));
+
+        for (a, e) in actual.response().iter().zip(expected.response()) {
+            assert!((a - e).module() < 1e-9);
+        }
+        for (a, e) in actual.group_delay().iter().zip(expected.group_delay()) {
+            assert!((a - e).abs() < 1e-9, "{} {}", a, e);
+        }
+        for (a, e) in actual.phase().iter().zip(expected.phase()) {
+            assert!((a - e).abs() < 1e-9, "{} {}", a, e);
+        }
+        assert!((cascade.response(500.0).module() - 0.5f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn impulse_and_step_responses_shall_match_filtering() {
+        let fs = 100.0;
+        let mut sos = IirDesign::low_pass(10.0, fs).set_order(4).design().unwrap();
+        let analysis = FilterAnalysis::sos(&sos, fs);
+        let impulse = analysis.impulse_response(200);
+        let step = analysis.step_response(200);
+
+        let mut expected = vec![0.0; 200];
+        let mut input = vec![0.0; 200];
+        input[0] = 1.0;
+        sos.process_block(&input, &mut expected);
+        assert_eq!(impulse.values().collect::<Vec<f64>>(), expected);
+        assert_eq!(impulse.times().nth(3), Some(0.03));
+
+        let mut sum = 0.0;
+        for (s, h) in step.values().zip(impulse.values()) {
+            sum += h;
+            assert!((s - sum).abs() < 1e-12);
+        }
+        assert!((step.values().last().unwrap() - 1.0).abs() < 1e-6);
+        assert!(analysis.step_response(0).is_empty());
+    }
+
+    #[test]
+    fn frequency_grid_shall_include_nyquist_frequency() {
+        assert_eq!(frequency_grid(5, 8.0), vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(frequency_grid(1, 8.0), vec![0.0]);
+        assert!(frequency_grid(0, 8.0).is_empty());
+    }
+}
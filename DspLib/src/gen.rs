@@ -1,5 +1,5 @@
 use std::f64::consts::PI;
-use crate::DescreteSignal;
+use crate::{DescreteSignal, MultichannelSignal, SignalMetadata};
 
 pub trait SignalShape {
     fn function(&mut self, x: f64) -> f64;
@@ -24,32 +24,36 @@ pub trait SignalShape {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TriangleWave {
     period: f64,
     slope: f64,
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SineWave {
     frequency: f64,
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RectangleWave {
     period: f64,
     duty_cycle: f64,
     high_phase: f64,
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DiracDelta {}
 
 impl SignalShape for TriangleWave {
     fn function(&mut self, x: f64) -> f64 {
         let phase = x % self.period;
         if phase < self.period * 0.25 {
-            return phase * self.slope;
+            phase * self.slope
         } else if phase < self.period * 0.75 {
-            return 2.0 - phase * self.slope;
+            2.0 - phase * self.slope
         } else {
-            return phase * self.slope - 4.0;
+            phase * self.slope - 4.0
         }
     }
 }
@@ -74,14 +78,16 @@ impl SignalShape for RectangleWave {
 impl SignalShape for DiracDelta {
     fn function(&mut self, x: f64) -> f64 {
         if x == 0.0 {
-            return 1.0;
+            1.0
         } else {
-            return 0.0;
+            0.0
         }
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Generator<S: SignalShape> {
+    #[cfg_attr(feature = "serde", serde(skip))]
     signal: DescreteSignal,
     amplitude: f64,
     frequency: f64,
@@ -99,7 +105,7 @@ impl Generator<SineWave> {
         Self {
             signal: DescreteSignal::new(),
             amplitude: 1.0,
-            frequency: frequency,
+            frequency,
             periods: 1.0,
             phase: 0.0,
             sampling_rate: DEFAULT_SAMPLING * frequency,
@@ -122,7 +128,7 @@ impl Generator<RectangleWave> {
         Generator {
             signal: DescreteSignal::new(),
             amplitude: 1.0,
-            frequency: frequency,
+            frequency,
             periods: 1.0,
             phase: 0.0,
             sampling_rate: DEFAULT_SAMPLING * frequency,
@@ -151,7 +157,7 @@ impl Generator<TriangleWave> {
         Generator {
             signal: DescreteSignal::new(),
             amplitude: 1.0,
-            frequency: frequency,
+            frequency,
             periods: 1.0,
             phase: 0.0,
             sampling_rate: DEFAULT_SAMPLING * frequency,
@@ -191,9 +197,18 @@ impl<S: SignalShape> Generator<S> {
                                             self.sampling_rate,
                                             self.phase,
                                             self.offset);
+        self.signal.set_metadata(SignalMetadata::new()
+            .set_time_unit("s")
+            .set_sample_rate(self.sampling_rate));
         self.signal.clone()
     }
 
+    /// Generates signal and copies it to every channel of multichannel signal.
+    pub fn generate_multichannel(&mut self, number_of_channels: usize) -> MultichannelSignal {
+        let signal = self.generate();
+        MultichannelSignal::from_signals(&vec![signal; number_of_channels])
+    }
+
     pub fn set_amplitude(mut self, amp: f64) -> Self {
         self.amplitude = amp;
         self
@@ -322,6 +337,14 @@ mod tests {
         }
     }
 
+    #[test]
+    fn generated_signal_shall_carry_sampling_rate_and_time_unit() {
+        let signal = Generator::sine_wave(2.0).set_sampling_rate(64.0).generate();
+
+        assert_eq!(signal.metadata().sample_rate(), Some(64.0));
+        assert_eq!(signal.metadata().time_unit(), Some("s"));
+    }
+
     #[test]
     fn check_triangle_shape_parameters() {
         let freq = 1213.432;
@@ -0,0 +1,270 @@
+use crate::{DescreteSignal, StreamFilter};
+
+/// Structure used to compute biquad. All forms have the same transfer function and differ
+/// in numerical behaviour and memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BiquadForm {
+    /// Separate delay lines of input and output (4 values), no internal overflow.
+    DirectFormI,
+    /// Single shared delay line (2 values), recursive part computed first.
+    DirectFormII,
+    /// Transposed form with 2 state values, numerically the best for floating point.
+    #[default]
+    TransposedDirectFormII,
+}
+
+/// Second order IIR section `H(z) = (b0 + b1 z^-1 + b2 z^-2) / (1 + a1 z^-1 + a2 z^-2)`.
+/// Filter keeps its state between calls, so stream can be filtered block by block.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Biquad {
+    b: [f64; 3],
+    a: [f64; 3],
+    form: BiquadForm,
+    state: [f64; 4],
+}
+
+impl Biquad {
+    /// Coefficients are normalized, so `a[0]` becomes 1. Panics when `a[0]` is zero.
+    pub fn new(b: [f64; 3], a: [f64; 3]) -> Self {
+        assert!(a[0] != 0.0, "the first denominator coefficient shall not be zero");
+        let b = b.map(|b| b / a[0]);
+        let a = a.map(|x| x / a[0]);
+        Self{b, a, form: BiquadForm::default(), state: [0.0; 4]}
+    }
+
+    /// Section with coefficients `[b0, b1, b2, a0, a1, a2]`, the same order as SOS matrix row.
+    pub fn from_coefficients(coefficients: [f64; 6]) -> Self {
+        let [b0, b1, b2, a0, a1, a2] = coefficients;
+        Self::new([b0, b1, b2], [a0, a1, a2])
+    }
+
+    /// Replaces coefficients (normalized like in [`Biquad::new`]) and keeps state, so
+    /// parameters can be changed while streaming.
+    pub fn set_coefficients(&mut self, b: [f64; 3], a: [f64; 3]) {
+        let form = self.form;
+        let state = self.state;
+        *self = Self::new(b, a);
+        self.form = form;
+        self.state = state;
+    }
+
+    /// Changes form and clears state.
+    pub fn set_form(mut self, form: BiquadForm) -> Self {
+        self.form = form;
+        self.reset();
+        self
+    }
+
+    pub fn form(&self) -> BiquadForm {
+        self.form
+    }
+
+    /// Numerator coefficients.
+    pub fn b(&self) -> &[f64; 3] {
+        &self.b
+    }
+
+    /// Normalized denominator coefficients, `a[0]` is 1.
+    pub fn a(&self) -> &[f64; 3] {
+        &self.a
+    }
+
+    /// Coefficients as `[b0, b1, b2, a0, a1, a2]`.
+    pub fn coefficients(&self) -> [f64; 6] {
+        [self.b[0], self.b[1], self.b[2], self.a[0], self.a[1], self.a[2]]
+    }
+}
+
+impl StreamFilter for Biquad {
+    fn process_sample(&mut self, x: f64) -> f64 {
+        let [b0, b1, b2] = self.b;
+        let [_, a1, a2] = self.a;
+        let s = &mut self.state;
+        match self.form {
+            BiquadForm::DirectFormI => {
+                let y = b0 * x + b1 * s[0] + b2 * s[1] - a1 * s[2] - a2 * s[3];
+                *s = [x, s[0], y, s[2]];
+                y
+            }
+            BiquadForm::DirectFormII => {
+                let w = x - a1 * s[0] - a2 * s[1];
+                let y = b0 * w + b1 * s[0] + b2 * s[1];
+                s[1] = s[0];
+                s[0] = w;
+                y
+            }
+            BiquadForm::TransposedDirectFormII => {
+                let y = b0 * x + s[0];
+                s[0] = b1 * x - a1 * y + s[1];
+                s[1] = b2 * x - a2 * y;
+                y
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.state = [0.0; 4];
+    }
+}
+
+/// Transposed direct form II of any order starting from given state, `b` and `a` are normalized
+/// and have the same length.
+pub(crate) fn transposed_direct_form(b: &[f64], a: &[f64], input: &[f64], state: &[f64]) -> Vec<f64> {
+    let mut z = state.to_vec();
+    let mut output = Vec::with_capacity(input.len());
+    for x in input {
+        let y = b[0] * x + z.first().copied().unwrap_or(0.0);
+        for i in 0..z.len() {
+            let next = z.get(i + 1).copied().unwrap_or(0.0);
+            z[i] = b[i + 1] * x - a[i + 1] * y + next;
+        }
+        output.push(y);
+    }
+    output
+}
+
+/// IIR filter as cascade of second order sections (SOS). High order filters shall be
+/// computed this way, because direct form of long transfer function is numerically unstable.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SosFilter {
+    sections: Vec<Biquad>,
+}
+
+impl SosFilter {
+    /// Panics when there are no sections.
+    pub fn new(sections: Vec<Biquad>) -> Self {
+        assert!(!sections.is_empty(), "SOS filter shall have at least one section");
+        Self{sections}
+    }
+
+    /// Filter from SOS matrix, one `[b0, b1, b2, a0, a1, a2]` row per section.
+    pub fn from_coefficients(sos: &[[f64; 6]]) -> Self {
+        Self::new(sos.iter().map(|row| Biquad::from_coefficients(*row)).collect())
+    }
+
+    /// Changes form of all sections and clears their state.
+    pub fn set_form(mut self, form: BiquadForm) -> Self {
+        self.sections = self.sections.into_iter().map(|s| s.set_form(form)).collect();
+        self
+    }
+
+    pub fn sections(&self) -> &[Biquad] {
+        &self.sections
+    }
+
+    /// SOS matrix, one `[b0, b1, b2, a0, a1, a2]` row per section.
+    pub fn coefficients(&self) -> Vec<[f64; 6]> {
+        self.sections.iter().map(|s| s.coefficients()).collect()
+    }
+
+    /// Filters whole signal starting from zeroed state, state of this filter is not changed.
+    pub fn filter(&self, signal: &DescreteSignal, output: &mut DescreteSignal) {
+        let mut filter = self.clone();
+        filter.reset();
+        filter.process_signal(signal, output);
+    }
+}
+
+impl StreamFilter for SosFilter {
+    fn process_sample(&mut self, x: f64) -> f64 {
+        self.sections.iter_mut().fold(x, |x, section| section.process_sample(x))
+    }
+
+    fn reset(&mut self) {
+        for section in self.sections.iter_mut() {
+            section.reset();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Direct implementation of difference equation.
+    fn difference_equation(b: &[f64], a: &[f64], input: &[f64]) -> Vec<f64> {
+        let mut output: Vec<f64> = vec![];
+        for n in 0..input.len() {
+            let mut y = 0.0;
+            for (i, b) in b.iter().enumerate().filter(|(i, _)| *i <= n) {
+                y += b * input[n - i];
+            }
+            for (i, a) in a.iter().enumerate().skip(1).filter(|(i, _)| *i <= n) {
+                y -= a * output[n - i];
+            }
+            output.push(y / a[0]);
+        }
+        output
+    }
+
+    fn input() -> Vec<f64> {
+        (0..100).map(|i| (i as f64 * 0.3).sin() + if i == 10 { 1.0 } else { 0.0 }).collect()
+    }
+
+    #[test]
+    fn every_form_shall_compute_difference_equation() {
+        let (b, a) = ([0.2, 0.3, -0.1], [2.0, -0.8, 0.3]);
+        let expected = difference_equation(&b, &a, &input());
+
+        for form in [BiquadForm::DirectFormI, BiquadForm::DirectFormII, BiquadForm::TransposedDirectFormII] {
+            let mut biquad = Biquad::new(b, a).set_form(form);
+            let mut output = vec![0.0; expected.len()];
+            biquad.process_block(&input(), &mut output);
+            for (y, e) in output.iter().zip(&expected) {
+                assert!((y - e).abs() < 1e-12, "{:?} {} {}", form, y, e);
+            }
+        }
+    }
+
+    #[test]
+    fn biquad_shall_be_normalized() {
+        let biquad = Biquad::from_coefficients([1.0, 2.0, 3.0, 2.0, 1.0, 0.5]);
+
+        assert_eq!(biquad.b(), &[0.5, 1.0, 1.5]);
+        assert_eq!(biquad.a(), &[1.0, 0.5, 0.25]);
+        assert_eq!(biquad.coefficients(), [0.5, 1.0, 1.5, 1.0, 0.5, 0.25]);
+    }
+
+    #[test]
+    fn cascade_shall_give_the_same_output_for_any_block_size() {
+        let sos = [[0.1, 0.2, 0.1, 1.0, -0.9, 0.4], [1.0, -1.0, 0.0, 1.0, 0.5, 0.0]];
+        let mut expected = vec![0.0; 100];
+        SosFilter::from_coefficients(&sos).process_block(&input(), &mut expected);
+        let first = difference_equation(&sos[0][..3], &sos[0][3..], &input());
+        let cascade = difference_equation(&sos[1][..3], &sos[1][3..], &first);
+
+        for (y, e) in expected.iter().zip(&cascade) {
+            assert!((y - e).abs() < 1e-12);
+        }
+        for block_size in [1, 3, 64] {
+            let mut filter = SosFilter::from_coefficients(&sos);
+            let mut output = vec![];
+            for block in input().chunks(block_size) {
+                let mut y = vec![0.0; block.len()];
+                filter.process_block(block, &mut y);
+                output.extend(y);
+            }
+            assert_eq!(output, expected);
+        }
+    }
+
+    #[test]
+    fn batch_filtering_shall_start_from_zero_state_and_keep_timestamps() {
+        let signal = DescreteSignal::new_from_vec(input().iter().enumerate().map(|(i, x)| (0.5 * i as f64, *x)).collect());
+        let mut filter = SosFilter::from_coefficients(&[[0.5, 0.5, 0.0, 1.0, -0.5, 0.0]]);
+        let mut streamed = DescreteSignal::new();
+        let mut batch = DescreteSignal::new();
+        let mut again = DescreteSignal::new();
+
+        filter.process_signal(&signal, &mut streamed);
+        filter.filter(&signal, &mut batch);
+        filter.reset();
+        filter.process_signal(&signal, &mut again);
+        assert_eq!(batch, streamed);
+        assert_eq!(again, streamed);
+        assert_eq!(batch.times().collect::<Vec<f64>>(), signal.times().collect::<Vec<f64>>());
+    }
+}
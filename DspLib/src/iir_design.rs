@@ -0,0 +1,765 @@
+use std::f64::consts::PI;
+use std::fmt::Display;
+
+use crate::{ComplexNumber, SosFilter, ZeroPoleGain};
+
+/// Analog prototype of IIR filter. Ripple and attenuation are in dB.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IirPrototype {
+    /// Maximally flat pass band, cutoff is -3 dB point.
+    Butterworth,
+    /// Equiripple pass band, cutoff is the end of pass band where gain is `-ripple`.
+    ChebyshevI { ripple: f64 },
+    /// Equiripple stop band, cutoff is the start of stop band where gain is `-attenuation`.
+    ChebyshevII { attenuation: f64 },
+    /// Equiripple pass and stop bands (Cauer), cutoff is the end of pass band.
+    Elliptic { ripple: f64, attenuation: f64 },
+    /// Maximally flat group delay, cutoff is frequency where phase reaches half of its final value.
+    Bessel,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IirDesignError {
+    InvalidSampleRate(f64),
+    /// Cutoff frequency shall be between 0 and half of sampling rate.
+    InvalidCutoff { cutoff: f64, nyquist: f64 },
+    /// Band edges are not sorted.
+    InvalidBand { start: f64, end: f64 },
+    MissingOrder,
+    InvalidOrder(usize),
+    InvalidRipple(f64),
+    /// Attenuation shall be positive and larger than pass band ripple.
+    InvalidAttenuation(f64),
+}
+
+impl Display for IirDesignError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IirDesignError::InvalidSampleRate(rate) => write!(f, "invalid sampling rate {}", rate),
+            IirDesignError::InvalidCutoff{cutoff, nyquist} =>
+                write!(f, "cutoff frequency {} shall be between 0 and Nyquist frequency {}", cutoff, nyquist),
+            IirDesignError::InvalidBand{start, end} => write!(f, "invalid band from {} to {}", start, end),
+            IirDesignError::MissingOrder => write!(f, "order of filter shall be set"),
+            IirDesignError::InvalidOrder(order) => write!(f, "invalid order {}", order),
+            IirDesignError::InvalidRipple(ripple) => write!(f, "pass band ripple {} dB shall be positive", ripple),
+            IirDesignError::InvalidAttenuation(attenuation) =>
+                write!(f, "stop band attenuation {} dB shall be positive and larger than ripple", attenuation),
+        }
+    }
+}
+
+impl std::error::Error for IirDesignError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Response {
+    LowPass(f64),
+    HighPass(f64),
+    BandPass(f64, f64),
+    BandStop(f64, f64),
+}
+
+/// Design of IIR filters from analog prototype with bilinear transform. Cutoff frequencies
+/// (in Hz) are prewarped, so digital filter has exactly the prototype response at them.
+/// Order is order of the prototype, band-pass and band-stop filters have twice as many poles.
+/// Default prototype is Butterworth.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IirDesign {
+    response: Response,
+    sample_rate: f64,
+    order: Option<usize>,
+    prototype: IirPrototype,
+}
+
+type Zpk = (Vec<ComplexNumber>, Vec<ComplexNumber>, f64);
+
+impl IirDesign {
+    fn new(response: Response, sample_rate: f64) -> Self {
+        Self{response, sample_rate, order: None, prototype: IirPrototype::Butterworth}
+    }
+
+    pub fn low_pass(cutoff: f64, sample_rate: f64) -> Self {
+        Self::new(Response::LowPass(cutoff), sample_rate)
+    }
+
+    pub fn high_pass(cutoff: f64, sample_rate: f64) -> Self {
+        Self::new(Response::HighPass(cutoff), sample_rate)
+    }
+
+    pub fn band_pass(low_cutoff: f64, high_cutoff: f64, sample_rate: f64) -> Self {
+        Self::new(Response::BandPass(low_cutoff, high_cutoff), sample_rate)
+    }
+
+    pub fn band_stop(low_cutoff: f64, high_cutoff: f64, sample_rate: f64) -> Self {
+        Self::new(Response::BandStop(low_cutoff, high_cutoff), sample_rate)
+    }
+
+    pub fn set_order(mut self, order: usize) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    pub fn set_prototype(mut self, prototype: IirPrototype) -> Self {
+        self.prototype = prototype;
+        self
+    }
+
+    /// Filter as cascade of second order sections.
+    pub fn design(&self) -> Result<SosFilter, IirDesignError> {
+        Ok(self.design_zpk()?.to_sos())
+    }
+
+    /// Zeros, poles and gain of digital filter.
+    pub fn design_zpk(&self) -> Result<ZeroPoleGain, IirDesignError> {
+        self.validate()?;
+        let order = self.order.ok_or(IirDesignError::MissingOrder)?;
+        if order == 0 {
+            return Err(IirDesignError::InvalidOrder(order));
+        }
+        let prototype = match self.prototype {
+            IirPrototype::Butterworth => butterworth(order),
+            IirPrototype::ChebyshevI{ripple} => chebyshev1(order, ripple),
+            IirPrototype::ChebyshevII{attenuation} => chebyshev2(order, attenuation),
+            IirPrototype::Elliptic{ripple, attenuation} => elliptic(order, ripple, attenuation),
+            IirPrototype::Bessel => bessel(order),
+        };
+        let prototype = snap_to_real_axis(prototype);
+        let warp = |f: f64| 2.0 * self.sample_rate * (PI * f / self.sample_rate).tan();
+        let analog = match self.response {
+            Response::LowPass(cutoff) => low_pass_to_low_pass(prototype, warp(cutoff)),
+            Response::HighPass(cutoff) => low_pass_to_high_pass(prototype, warp(cutoff)),
+            Response::BandPass(low, high) => {
+                let (low, high) = (warp(low), warp(high));
+                low_pass_to_band_pass(prototype, (low * high).sqrt(), high - low)
+            }
+            Response::BandStop(low, high) => {
+                let (low, high) = (warp(low), warp(high));
+                low_pass_to_band_stop(prototype, (low * high).sqrt(), high - low)
+            }
+        };
+        let (zeros, poles, gain) = bilinear(analog, self.sample_rate);
+        Ok(ZeroPoleGain::new(&zeros, &poles, gain))
+    }
+
+    fn validate(&self) -> Result<(), IirDesignError> {
+        if !(self.sample_rate > 0.0 && self.sample_rate.is_finite()) {
+            return Err(IirDesignError::InvalidSampleRate(self.sample_rate));
+        }
+        let nyquist = self.sample_rate / 2.0;
+        let check = |cutoff: f64| if cutoff > 0.0 && cutoff < nyquist {
+            Ok(())
+        } else {
+            Err(IirDesignError::InvalidCutoff{cutoff, nyquist})
+        };
+        match self.response {
+            Response::LowPass(cutoff) | Response::HighPass(cutoff) => check(cutoff)?,
+            Response::BandPass(low, high) | Response::BandStop(low, high) => {
+                check(low)?;
+                check(high)?;
+                if low >= high {
+                    return Err(IirDesignError::InvalidBand{start: low, end: high});
+                }
+            }
+        }
+        let check_ripple = |ripple: f64| if ripple > 0.0 && ripple.is_finite() {
+            Ok(())
+        } else {
+            Err(IirDesignError::InvalidRipple(ripple))
+        };
+        match self.prototype {
+            IirPrototype::ChebyshevI{ripple} => check_ripple(ripple),
+            IirPrototype::ChebyshevII{attenuation} if !(attenuation > 0.0 && attenuation.is_finite()) => {
+                Err(IirDesignError::InvalidAttenuation(attenuation))
+            }
+            IirPrototype::Elliptic{ripple, attenuation} => {
+                check_ripple(ripple)?;
+                if attenuation > ripple && attenuation.is_finite() {
+                    Ok(())
+                } else {
+                    Err(IirDesignError::InvalidAttenuation(attenuation))
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Minimal order of Butterworth filter with at most `passband_ripple` dB loss in pass band
+/// and at least `stopband_attenuation` dB in stop band, with its -3 dB cutoff frequencies.
+/// Edges are in Hz, one for low-pass and high-pass (high-pass when pass band edge is above
+/// stop band edge), two for band-pass and band-stop (band-stop when stop band is inside
+/// pass band). Returned cutoffs can be used directly in [`IirDesign`].
+pub fn butterworth_order(passband: &[f64], stopband: &[f64], passband_ripple: f64, stopband_attenuation: f64,
+                         sample_rate: f64) -> Result<(usize, Vec<f64>), IirDesignError> {
+    let spec = Specification::new(passband, stopband, passband_ripple, stopband_attenuation, sample_rate)?;
+    let (gpass, gstop) = spec.gains();
+    let order = ((gstop / gpass).log10() / (2.0 * spec.selectivity.log10())).ceil().max(1.0) as usize;
+    // natural frequency of prototype, where the loss is 3 dB
+    let w0 = gpass.powf(-1.0 / (2.0 * order as f64));
+    let p = &spec.passband;
+    let cutoffs = match spec.kind {
+        Kind::LowPass => vec![w0 * p[0]],
+        Kind::HighPass => vec![p[0] / w0],
+        Kind::BandPass => {
+            let width = p[1] - p[0];
+            [-w0, w0].map(|w| (-w * width / 2.0 + (w * w / 4.0 * width * width + p[0] * p[1]).sqrt()).abs()).to_vec()
+        }
+        Kind::BandStop => {
+            let width = p[1] - p[0];
+            [-w0, w0].map(|w| (-width / (2.0 * w) + (width * width / (4.0 * w * w) + p[0] * p[1]).sqrt()).abs()).to_vec()
+        }
+    };
+    Ok((order, spec.unwarp(cutoffs)))
+}
+
+/// Minimal order of Chebyshev type I filter, see [`butterworth_order`]. Cutoffs are pass band edges.
+pub fn chebyshev1_order(passband: &[f64], stopband: &[f64], passband_ripple: f64, stopband_attenuation: f64,
+                        sample_rate: f64) -> Result<(usize, Vec<f64>), IirDesignError> {
+    let spec = Specification::new(passband, stopband, passband_ripple, stopband_attenuation, sample_rate)?;
+    let (gpass, gstop) = spec.gains();
+    let order = ((gstop / gpass).sqrt().acosh() / spec.selectivity.acosh()).ceil().max(1.0) as usize;
+    Ok((order, passband.to_vec()))
+}
+
+/// Minimal order of elliptic filter, see [`butterworth_order`]. Cutoffs are pass band edges.
+pub fn elliptic_order(passband: &[f64], stopband: &[f64], passband_ripple: f64, stopband_attenuation: f64,
+                      sample_rate: f64) -> Result<(usize, Vec<f64>), IirDesignError> {
+    let spec = Specification::new(passband, stopband, passband_ripple, stopband_attenuation, sample_rate)?;
+    let (gpass, gstop) = spec.gains();
+    let m0 = 1.0 / (spec.selectivity * spec.selectivity);
+    let m1 = gpass / gstop;
+    let order = (ellipkm1(1.0 - m0) * ellipkm1(m1) / (ellipkm1(m0) * ellipkm1(1.0 - m1)))
+        .ceil().max(1.0) as usize;
+    Ok((order, passband.to_vec()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    LowPass,
+    HighPass,
+    BandPass,
+    BandStop,
+}
+
+/// Filter specification converted to prewarped analog frequencies (`tan(pi f / fs)`).
+struct Specification {
+    kind: Kind,
+    passband: Vec<f64>,
+    /// Ratio of stop band edge to pass band edge of equivalent low-pass prototype.
+    selectivity: f64,
+    passband_ripple: f64,
+    stopband_attenuation: f64,
+    sample_rate: f64,
+}
+
+impl Specification {
+    fn new(passband: &[f64], stopband: &[f64], passband_ripple: f64, stopband_attenuation: f64,
+           sample_rate: f64) -> Result<Self, IirDesignError> {
+        if !(sample_rate > 0.0 && sample_rate.is_finite()) {
+            return Err(IirDesignError::InvalidSampleRate(sample_rate));
+        }
+        if !(passband_ripple > 0.0 && passband_ripple.is_finite()) {
+            return Err(IirDesignError::InvalidRipple(passband_ripple));
+        }
+        if !(stopband_attenuation > passband_ripple && stopband_attenuation.is_finite()) {
+            return Err(IirDesignError::InvalidAttenuation(stopband_attenuation));
+        }
+        let nyquist = sample_rate / 2.0;
+        if let Some(cutoff) = passband.iter().chain(stopband).find(|f| !(**f > 0.0 && **f < nyquist)) {
+            return Err(IirDesignError::InvalidCutoff{cutoff: *cutoff, nyquist});
+        }
+        let kind = match (passband, stopband) {
+            ([p], [s]) if p < s => Kind::LowPass,
+            ([p], [s]) if p > s => Kind::HighPass,
+            ([p0, p1], [s0, s1]) if s0 < p0 && p0 < p1 && p1 < s1 => Kind::BandPass,
+            ([p0, p1], [s0, s1]) if p0 < s0 && s0 < s1 && s1 < p1 => Kind::BandStop,
+            _ => {
+                let start = passband.first().copied().unwrap_or(0.0);
+                return Err(IirDesignError::InvalidBand{start, end: stopband.first().copied().unwrap_or(start)});
+            }
+        };
+
+        let warp = |f: &f64| (PI * f / sample_rate).tan();
+        let p = passband.iter().map(warp).collect::<Vec<f64>>();
+        let s = stopband.iter().map(warp).collect::<Vec<f64>>();
+        let selectivity = match kind {
+            Kind::LowPass => s[0] / p[0],
+            Kind::HighPass => p[0] / s[0],
+            Kind::BandPass => s.iter().map(|s| ((s * s - p[0] * p[1]) / (s * (p[0] - p[1]))).abs()).fold(f64::INFINITY, f64::min),
+            Kind::BandStop => s.iter().map(|s| ((s * (p[0] - p[1])) / (s * s - p[0] * p[1])).abs()).fold(f64::INFINITY, f64::min),
+        };
+        Ok(Self{kind, passband: p, selectivity, passband_ripple, stopband_attenuation, sample_rate})
+    }
+
+    /// `10^(loss / 10) - 1` for pass band and stop band.
+    fn gains(&self) -> (f64, f64) {
+        ((0.1 * self.passband_ripple * std::f64::consts::LN_10).exp_m1(),
+         (0.1 * self.stopband_attenuation * std::f64::consts::LN_10).exp_m1())
+    }
+
+    fn unwarp(&self, frequencies: Vec<f64>) -> Vec<f64> {
+        let mut frequencies = frequencies.iter().map(|w| w.atan() * self.sample_rate / PI).collect::<Vec<f64>>();
+        frequencies.sort_by(f64::total_cmp);
+        frequencies
+    }
+}
+
+fn complex(re: f64, im: f64) -> ComplexNumber {
+    ComplexNumber::new(re, im)
+}
+
+fn product(values: &[ComplexNumber]) -> ComplexNumber {
+    values.iter().fold(complex(1.0, 0.0), |p, v| &p * v)
+}
+
+/// Principal square root, exact for real arguments.
+fn sqrt(z: &ComplexNumber) -> ComplexNumber {
+    let module = z.module();
+    let re = ((module + z.re()) / 2.0).sqrt();
+    let im = ((module - z.re()) / 2.0).sqrt();
+    complex(re, if z.im() < 0.0 { -im } else { im })
+}
+
+/// Removes rounding errors from imaginary part of real roots of prototype.
+fn snap_to_real_axis((zeros, poles, gain): Zpk) -> Zpk {
+    let snap = |roots: Vec<ComplexNumber>| roots.into_iter()
+        .map(|z| if z.im().abs() < 1e-12 * z.module().max(1.0) { complex(z.re(), 0.0) } else { z })
+        .collect::<Vec<ComplexNumber>>();
+    (snap(zeros), snap(poles), gain)
+}
+
+fn butterworth(order: usize) -> Zpk {
+    let n = order as f64;
+    let poles = (0..order).map(|k| ComplexNumber::from_polar(1.0, PI * (2.0 * k as f64 + n + 1.0) / (2.0 * n))).collect();
+    (vec![], poles, 1.0)
+}
+
+fn chebyshev1(order: usize, ripple: f64) -> Zpk {
+    let n = order as f64;
+    let epsilon = (0.1 * ripple * std::f64::consts::LN_10).exp_m1().sqrt();
+    let mu = (1.0 / epsilon).asinh() / n;
+    let poles = (0..order).map(|k| {
+        let theta = PI * (2.0 * k as f64 - n + 1.0) / (2.0 * n);
+        complex(-mu.sinh() * theta.cos(), -mu.cosh() * theta.sin())
+    }).collect::<Vec<ComplexNumber>>();
+    let mut gain = (&product(&poles) * if order.is_multiple_of(2) { 1.0 } else { -1.0 }).re();
+    if order.is_multiple_of(2) {
+        gain /= (1.0 + epsilon * epsilon).sqrt();
+    }
+    (vec![], poles, gain)
+}
+
+fn chebyshev2(order: usize, attenuation: f64) -> Zpk {
+    let n = order as f64;
+    let delta = 1.0 / (0.1 * attenuation * std::f64::consts::LN_10).exp_m1().sqrt();
+    let mu = (1.0 / delta).asinh() / n;
+    let m = (0..order).map(|k| 2.0 * k as f64 - n + 1.0).collect::<Vec<f64>>();
+    let zeros = m.iter().filter(|m| **m != 0.0)
+        .map(|m| complex(0.0, 1.0 / (m * PI / (2.0 * n)).sin()))
+        .collect::<Vec<ComplexNumber>>();
+    let poles = m.iter().map(|m| {
+        let p = ComplexNumber::from_polar(-1.0, m * PI / (2.0 * n));
+        &complex(1.0, 0.0) / &complex(mu.sinh() * p.re(), mu.cosh() * p.im())
+    }).collect::<Vec<ComplexNumber>>();
+    let negated = |roots: &[ComplexNumber]| roots.iter().map(|z| -z).collect::<Vec<ComplexNumber>>();
+    let gain = (&product(&negated(&poles)) / &product(&negated(&zeros))).re();
+    (zeros, poles, gain)
+}
+
+fn elliptic(order: usize, ripple: f64, attenuation: f64) -> Zpk {
+    let epsilon2 = (0.1 * ripple * std::f64::consts::LN_10).exp_m1();
+    let epsilon = epsilon2.sqrt();
+    if order == 1 {
+        let pole = -(1.0 / epsilon2).sqrt();
+        return (vec![], vec![complex(pole, 0.0)], -pole);
+    }
+    let n = order as f64;
+    // selectivity factor k1 and degree equation K(m) / K(1 - m) = n K(k1^2) / K(1 - k1^2)
+    let k1 = epsilon / (0.1 * attenuation * std::f64::consts::LN_10).exp_m1().sqrt();
+    let (k1_2, k1p_2) = (k1 * k1, 1.0 - k1 * k1);
+    let k_k1 = ellipkm1(k1p_2);
+    let ratio = n * k_k1 / ellipkm1(k1_2);
+    let (m, m1) = parameter_from_ratio(ratio);
+    let capital_k = ellipkm1(m1);
+
+    let mut zeros = vec![];
+    let mut poles = vec![];
+    let r = inverse_sc(1.0 / epsilon, k1p_2);
+    let v0 = capital_k * r / (n * k_k1);
+    let (sv, cv, dv) = ellipj(v0, m1);
+    for j in (1 - order % 2..order).step_by(2) {
+        let (s, c, d) = ellipj(j as f64 * capital_k / n, m);
+        if s.abs() > 1e-12 {
+            let zero = complex(0.0, 1.0 / (m.sqrt() * s));
+            zeros.push(zero);
+            zeros.push(zero.conj());
+        }
+        let denominator = 1.0 - (d * sv) * (d * sv);
+        let pole = complex(-c * d * sv * cv / denominator, -s * dv / denominator);
+        poles.push(pole);
+        if pole.im().abs() > 1e-12 * pole.module() {
+            poles.push(pole.conj());
+        }
+    }
+    let negated = |roots: &[ComplexNumber]| roots.iter().map(|z| -z).collect::<Vec<ComplexNumber>>();
+    let mut gain = (&product(&negated(&poles)) / &product(&negated(&zeros))).re();
+    if order.is_multiple_of(2) {
+        gain /= (1.0 + epsilon2).sqrt();
+    }
+    (zeros, poles, gain)
+}
+
+/// Bessel filter normalized so that phase at cutoff is half of its final value.
+fn bessel(order: usize) -> Zpk {
+    let n = order as f64;
+    // coefficients of Bessel polynomial from the highest power, a_k = (2n-k)! / (2^(n-k) k! (n-k)!)
+    let mut coefficients = vec![1.0];
+    for k in (1..=order).rev() {
+        let previous = coefficients[coefficients.len() - 1];
+        coefficients.push(previous * (2.0 * n - k as f64 + 1.0) * k as f64 / (2.0 * (n - k as f64 + 1.0)));
+    }
+    let scale = coefficients[order].powf(-1.0 / n);
+    let poles = crate::polynomial::roots(&coefficients).iter().map(|p| p * scale).collect::<Vec<ComplexNumber>>();
+    let gain = (&product(&poles) * if order.is_multiple_of(2) { 1.0 } else { -1.0 }).re();
+    (vec![], poles, gain)
+}
+
+fn low_pass_to_low_pass((zeros, poles, gain): Zpk, w0: f64) -> Zpk {
+    let degree = poles.len() as i32 - zeros.len() as i32;
+    (zeros.iter().map(|z| z * w0).collect(), poles.iter().map(|p| p * w0).collect(), gain * w0.powi(degree))
+}
+
+fn low_pass_to_high_pass((zeros, poles, gain): Zpk, w0: f64) -> Zpk {
+    let negated = |roots: &[ComplexNumber]| roots.iter().map(|z| -z).collect::<Vec<ComplexNumber>>();
+    let gain = gain * (&product(&negated(&zeros)) / &product(&negated(&poles))).re();
+    let invert = |roots: &[ComplexNumber]| roots.iter().map(|z| &complex(w0, 0.0) / z).collect::<Vec<ComplexNumber>>();
+    let mut new_zeros = invert(&zeros);
+    new_zeros.resize(poles.len().max(zeros.len()), ComplexNumber::default());
+    (new_zeros, invert(&poles), gain)
+}
+
+fn low_pass_to_band_pass((zeros, poles, gain): Zpk, w0: f64, bandwidth: f64) -> Zpk {
+    let degree = poles.len() - zeros.len();
+    let transform = |roots: &[ComplexNumber]| {
+        let mut result = vec![];
+        for root in roots {
+            let scaled = root * (bandwidth / 2.0);
+            let d = sqrt(&(&(&scaled * &scaled) + &(-w0 * w0)));
+            result.push(&scaled + &d);
+            result.push(&scaled - &d);
+        }
+        result
+    };
+    let mut new_zeros = transform(&zeros);
+    new_zeros.resize(new_zeros.len() + degree, ComplexNumber::default());
+    (new_zeros, transform(&poles), gain * bandwidth.powi(degree as i32))
+}
+
+fn low_pass_to_band_stop((zeros, poles, gain): Zpk, w0: f64, bandwidth: f64) -> Zpk {
+    let degree = poles.len() - zeros.len();
+    let negated = |roots: &[ComplexNumber]| roots.iter().map(|z| -z).collect::<Vec<ComplexNumber>>();
+    let gain = gain * (&product(&negated(&zeros)) / &product(&negated(&poles))).re();
+    let transform = |roots: &[ComplexNumber]| {
+        let mut result = vec![];
+        for root in roots {
+            let inverted = &complex(bandwidth / 2.0, 0.0) / root;
+            let d = sqrt(&(&(&inverted * &inverted) + &(-w0 * w0)));
+            result.push(&inverted + &d);
+            result.push(&inverted - &d);
+        }
+        result
+    };
+    let mut new_zeros = transform(&zeros);
+    for _ in 0..degree {
+        new_zeros.push(complex(0.0, w0));
+        new_zeros.push(complex(0.0, -w0));
+    }
+    (new_zeros, transform(&poles), gain)
+}
+
+/// Bilinear transform `s = 2 fs (z - 1) / (z + 1)`, zeros at infinity are moved to Nyquist frequency.
+fn bilinear((zeros, poles, gain): Zpk, sample_rate: f64) -> Zpk {
+    let fs2 = complex(2.0 * sample_rate, 0.0);
+    let map = |roots: &[ComplexNumber]| roots.iter().map(|s| &(&fs2 + s) / &(&fs2 - s)).collect::<Vec<ComplexNumber>>();
+    let differences = |roots: &[ComplexNumber]| roots.iter().map(|s| &fs2 - s).collect::<Vec<ComplexNumber>>();
+    let gain = gain * (&product(&differences(&zeros)) / &product(&differences(&poles))).re();
+    let mut new_zeros = map(&zeros);
+    new_zeros.resize(poles.len().max(zeros.len()), complex(-1.0, 0.0));
+    (new_zeros, map(&poles), gain)
+}
+
+/// Carlson's symmetric elliptic integral of the first kind `R_F(x, y, z)`.
+fn carlson_rf(mut x: f64, mut y: f64, mut z: f64) -> f64 {
+    loop {
+        let (sx, sy, sz) = (x.sqrt(), y.sqrt(), z.sqrt());
+        let lambda = sx * (sy + sz) + sy * sz;
+        x = 0.25 * (x + lambda);
+        y = 0.25 * (y + lambda);
+        z = 0.25 * (z + lambda);
+        let average = (x + y + z) / 3.0;
+        let (dx, dy, dz) = ((average - x) / average, (average - y) / average, (average - z) / average);
+        if dx.abs().max(dy.abs()).max(dz.abs()) < 1e-3 {
+            let e2 = dx * dy - dz * dz;
+            let e3 = dx * dy * dz;
+            return (1.0 + (e2 / 24.0 - 0.1 - 3.0 * e3 / 44.0) * e2 + e3 / 14.0) / average.sqrt();
+        }
+    }
+}
+
+/// Complete elliptic integral of the first kind `K(1 - m1)`, complementary parameter is used
+/// to keep precision for parameter close to 1.
+fn ellipkm1(m1: f64) -> f64 {
+    carlson_rf(0.0, m1, 1.0)
+}
+
+/// Solves `sc(u, m) = w` (`sc = sn / cn`), it is incomplete integral `F(atan(w), m)`.
+fn inverse_sc(w: f64, m: f64) -> f64 {
+    let phi = w.atan();
+    let (s, c) = phi.sin_cos();
+    s * carlson_rf(c * c, 1.0 - m * s * s, 1.0)
+}
+
+/// Jacobi elliptic functions `sn`, `cn` and `dn` with arithmetic-geometric mean method.
+fn ellipj(u: f64, m: f64) -> (f64, f64, f64) {
+    if m < 1e-12 {
+        return (u.sin(), u.cos(), 1.0);
+    }
+    let mut a = vec![1.0];
+    let mut c = vec![m.sqrt()];
+    let mut b = (1.0 - m).sqrt();
+    while c[c.len() - 1].abs() > 1e-16 && a.len() < 32 {
+        let last = a[a.len() - 1];
+        c.push((last - b) / 2.0);
+        a.push((last + b) / 2.0);
+        b = (last * b).sqrt();
+    }
+    let n = a.len() - 1;
+    let mut phi = 2f64.powi(n as i32) * a[n] * u;
+    let mut previous = phi;
+    for i in (1..=n).rev() {
+        previous = phi;
+        phi = (phi + (c[i] * phi.sin() / a[i]).asin()) / 2.0;
+    }
+    (phi.sin(), phi.cos(), phi.cos() / (previous - phi).cos())
+}
+
+/// Parameter `m` (and `1 - m`) for which `K(m) / K(1 - m) = ratio`, calculated from nome
+/// `q = exp(-pi / ratio)` with theta functions.
+fn parameter_from_ratio(ratio: f64) -> (f64, f64) {
+    let q = (-PI / ratio).exp();
+    let (mut theta2, mut theta3, mut theta4) = (0.0, 1.0, 1.0);
+    for n in 0..100 {
+        let n = n as f64;
+        let term2 = q.powf(n * (n + 1.0));
+        let term34 = q.powf((n + 1.0) * (n + 1.0));
+        theta2 += term2;
+        theta3 += 2.0 * term34;
+        theta4 += 2.0 * term34 * if (n as usize).is_multiple_of(2) { -1.0 } else { 1.0 };
+        if term2 < 1e-17 && term34 < 1e-17 {
+            break;
+        }
+    }
+    theta2 *= 2.0 * q.powf(0.25);
+    let k = (theta2 / theta3).powi(2);
+    let k_complement = (theta4 / theta3).powi(2);
+    (k * k, k_complement * k_complement)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{assert_close, gain_db};
+
+    fn max_gain_db(sos: &SosFilter, band: (f64, f64), sample_rate: f64) -> f64 {
+        (0..=2000).map(|i| gain_db(sos, band.0 + (band.1 - band.0) * i as f64 / 2000.0, sample_rate)).fold(f64::MIN, f64::max)
+    }
+
+    fn min_gain_db(sos: &SosFilter, band: (f64, f64), sample_rate: f64) -> f64 {
+        (0..=2000).map(|i| gain_db(sos, band.0 + (band.1 - band.0) * i as f64 / 2000.0, sample_rate)).fold(f64::MAX, f64::min)
+    }
+
+    #[test]
+    fn butterworth_shall_match_reference_coefficients() {
+        let second = IirDesign::low_pass(250.0, 1000.0).set_order(2).design().unwrap().to_transfer_function();
+        assert_close(second.b(), &[0.29289322, 0.58578644, 0.29289322], 1e-8);
+        assert_close(second.a(), &[1.0, 0.0, 0.17157288], 1e-8);
+
+        let fourth = IirDesign::low_pass(100.0, 1000.0).set_order(4).design().unwrap().to_transfer_function();
+        assert_close(fourth.b(), &[0.00482434, 0.01929737, 0.02894606, 0.01929737, 0.00482434], 1e-8);
+        assert_close(fourth.a(), &[1.0, -2.36951301, 2.31398841, -1.05466541, 0.18737949], 1e-8);
+    }
+
+    #[test]
+    fn chebyshev_and_elliptic_shall_match_reference_coefficients() {
+        let chebyshev1 = IirDesign::low_pass(100.0, 1000.0).set_order(4)
+            .set_prototype(IirPrototype::ChebyshevI{ripple: 0.5})
+            .design()
+            .unwrap()
+            .to_transfer_function();
+        assert_close(chebyshev1.b(), &[0.00247773002, 0.00991092008, 0.01486638012, 0.00991092008, 0.00247773002], 1e-8);
+        assert_close(chebyshev1.a(), &[1.0, -2.914102515, 3.517927191, -2.0347429, 0.47291094], 1e-8);
+
+        let chebyshev2 = IirDesign::high_pass(100.0, 1000.0).set_order(4)
+            .set_prototype(IirPrototype::ChebyshevII{attenuation: 40.0})
+            .design()
+            .unwrap()
+            .to_transfer_function();
+        assert_close(chebyshev2.b(), &[0.2511015425, -0.906086383, 1.315026172, -0.906086383, 0.2511015425], 1e-8);
+        assert_close(chebyshev2.a(), &[1.0, -1.191118195, 1.003474926, -0.3707582628, 0.06405063877], 1e-8);
+
+        let elliptic = IirDesign::low_pass(4000.0, 48000.0).set_order(4)
+            .set_prototype(IirPrototype::Elliptic{ripple: 1.0, attenuation: 60.0})
+            .design()
+            .unwrap()
+            .to_transfer_function();
+        assert_close(elliptic.b(), &[0.004097742752, 0.0011156212, 0.005663908472, 0.0011156212, 0.004097742752], 1e-8);
+        assert_close(elliptic.a(), &[1.0, -3.250425722, 4.213707743, -2.554623071, 0.6093950403], 1e-8);
+
+        let odd_elliptic = IirDesign::low_pass(4000.0, 48000.0).set_order(3)
+            .set_prototype(IirPrototype::Elliptic{ripple: 1.0, attenuation: 60.0})
+            .design()
+            .unwrap()
+            .to_transfer_function();
+        assert_close(odd_elliptic.b(), &[0.00997330862, 0.01819168721, 0.01819168721, 0.00997330862], 1e-8);
+        assert_close(odd_elliptic.a(), &[1.0, -2.308796455, 1.963337212, -0.5982107657], 1e-8);
+    }
+
+    #[test]
+    fn butterworth_shall_have_3_db_loss_at_cutoff_for_every_response() {
+        let fs = 8000.0;
+        let low_pass = IirDesign::low_pass(1000.0, fs).set_order(5).design().unwrap();
+        let high_pass = IirDesign::high_pass(1000.0, fs).set_order(5).design().unwrap();
+        let band_pass = IirDesign::band_pass(500.0, 2000.0, fs).set_order(3).design().unwrap();
+        let band_stop = IirDesign::band_stop(500.0, 2000.0, fs).set_order(3).design().unwrap();
+        let half_power = -10.0 * 2f64.log10();
+
+        assert_eq!(low_pass.sections().len(), 3);
+        assert_eq!(band_pass.sections().len(), 3);
+        assert!(gain_db(&low_pass, 0.0, fs).abs() < 1e-9);
+        assert!((gain_db(&low_pass, 1000.0, fs) - half_power).abs() < 1e-9);
+        assert!(gain_db(&high_pass, 4000.0, fs).abs() < 1e-9);
+        assert!((gain_db(&high_pass, 1000.0, fs) - half_power).abs() < 1e-9);
+        for edge in [500.0, 2000.0] {
+            assert!((gain_db(&band_pass, edge, fs) - half_power).abs() < 1e-9);
+            assert!((gain_db(&band_stop, edge, fs) - half_power).abs() < 1e-9);
+        }
+        assert!(max_gain_db(&band_pass, (0.0, 4000.0), fs) < 1e-9);
+        assert!(gain_db(&band_stop, 0.0, fs).abs() < 1e-9 && gain_db(&band_stop, 4000.0, fs).abs() < 1e-9);
+        // center of stop band is geometric mean of prewarped edges
+        let center = ((PI * 500.0 / fs).tan() * (PI * 2000.0 / fs).tan()).sqrt().atan() * fs / PI;
+        assert!(gain_db(&band_stop, center, fs) < -100.0);
+    }
+
+    #[test]
+    fn chebyshev_type_one_shall_have_equal_ripple_in_pass_band() {
+        let fs = 1000.0;
+        for order in [4, 5] {
+            let sos = IirDesign::low_pass(100.0, fs).set_order(order)
+                .set_prototype(IirPrototype::ChebyshevI{ripple: 0.5})
+                .design()
+                .unwrap();
+
+            assert!((gain_db(&sos, 100.0, fs) + 0.5).abs() < 1e-9);
+            // ripple extremes are found on grid
+            assert!((min_gain_db(&sos, (0.0, 100.0), fs) + 0.5).abs() < 1e-3);
+            assert!(max_gain_db(&sos, (0.0, 100.0), fs).abs() < 1e-3);
+            assert!(max_gain_db(&sos, (0.0, 100.0), fs) < 1e-12);
+            assert!(max_gain_db(&sos, (200.0, 500.0), fs) < -30.0);
+        }
+    }
+
+    #[test]
+    fn chebyshev_type_two_shall_have_equal_ripple_in_stop_band() {
+        let fs = 1000.0;
+        for order in [4, 5] {
+            let sos = IirDesign::high_pass(100.0, fs).set_order(order)
+                .set_prototype(IirPrototype::ChebyshevII{attenuation: 40.0})
+                .design()
+                .unwrap();
+
+            assert!((gain_db(&sos, 100.0, fs) + 40.0).abs() < 1e-9);
+            assert!((max_gain_db(&sos, (0.0, 100.0), fs) + 40.0).abs() < 1e-6);
+            assert!(gain_db(&sos, 500.0, fs).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn elliptic_filter_shall_have_equal_ripple_in_both_bands() {
+        let fs = 48000.0;
+        for order in [1, 2, 3, 4, 7] {
+            let sos = IirDesign::low_pass(4000.0, fs).set_order(order)
+                .set_prototype(IirPrototype::Elliptic{ripple: 1.0, attenuation: 60.0})
+                .design()
+                .unwrap();
+            let stop_band_start = (0..=2000).map(|i| 4000.0 + 20000.0 * i as f64 / 2000.0)
+                .find(|f| gain_db(&sos, *f, fs) < -60.0)
+                .unwrap_or(24000.0);
+
+            assert!((gain_db(&sos, 4000.0, fs) + 1.0).abs() < 1e-6, "{} {}", order, gain_db(&sos, 4000.0, fs));
+            assert!((min_gain_db(&sos, (0.0, 4000.0), fs) + 1.0).abs() < 1e-6);
+            assert!(max_gain_db(&sos, (0.0, 4000.0), fs) < 1e-9);
+            if order > 1 {
+                assert!((max_gain_db(&sos, (stop_band_start, 24000.0), fs) + 60.0).abs() < 0.01,
+                        "{} {}", order, max_gain_db(&sos, (stop_band_start, 24000.0), fs));
+            }
+        }
+    }
+
+    #[test]
+    fn bessel_prototype_shall_match_reference_poles() {
+        let expected = [complex(-0.9416000265332067, 0.0), complex(-0.7456403858480766, 0.7113666249728353),
+                        complex(-0.7456403858480766, -0.7113666249728353)];
+        let (_, poles, gain) = snap_to_real_axis(bessel(3));
+        assert!((gain - 1.0).abs() < 1e-12);
+        for (p, e) in poles.iter().zip(expected) {
+            assert!((p - &e).module() < 1e-12, "{:?}", p);
+        }
+
+        let fs = 1000.0;
+        let sos = IirDesign::low_pass(50.0, fs).set_order(3).set_prototype(IirPrototype::Bessel).design().unwrap();
+        assert!(gain_db(&sos, 0.0, fs).abs() < 1e-9);
+        // phase normalized prototype is not 3 dB down at cutoff
+        assert!((gain_db(&sos, 50.0, fs) + 6.235516638602888).abs() < 1e-9);
+    }
+
+    #[test]
+    fn estimated_order_shall_meet_specification() {
+        let fs = 10000.0;
+        let (order, cutoffs) = butterworth_order(&[1000.0], &[1500.0], 1.0, 40.0, fs).unwrap();
+        let sos = IirDesign::low_pass(cutoffs[0], fs).set_order(order).design().unwrap();
+        assert_eq!(order, 12);
+        assert!(gain_db(&sos, 1000.0, fs) > -1.0 - 1e-9);
+        assert!(gain_db(&sos, 1500.0, fs) < -40.0);
+
+        let (order, cutoffs) = chebyshev1_order(&[1000.0, 2000.0], &[700.0, 2500.0], 0.5, 50.0, fs).unwrap();
+        let prototype = IirPrototype::ChebyshevI{ripple: 0.5};
+        let sos = IirDesign::band_pass(cutoffs[0], cutoffs[1], fs).set_order(order).set_prototype(prototype).design().unwrap();
+        assert!(max_gain_db(&sos, (0.0, 700.0), fs) < -50.0 && max_gain_db(&sos, (2500.0, 5000.0), fs) < -50.0);
+        let weaker = IirDesign::band_pass(cutoffs[0], cutoffs[1], fs).set_order(order - 1).set_prototype(prototype).design().unwrap();
+        assert!(max_gain_db(&weaker, (0.0, 700.0), fs) > -50.0 || max_gain_db(&weaker, (2500.0, 5000.0), fs) > -50.0);
+
+        let (order, cutoffs) = elliptic_order(&[3000.0], &[2500.0], 1.0, 60.0, fs).unwrap();
+        let prototype = IirPrototype::Elliptic{ripple: 1.0, attenuation: 60.0};
+        let sos = IirDesign::high_pass(cutoffs[0], fs).set_order(order).set_prototype(prototype).design().unwrap();
+        assert!(max_gain_db(&sos, (0.0, 2500.0), fs) < -60.0 + 1e-6);
+        assert!(min_gain_db(&sos, (3000.0, 5000.0), fs) > -1.0 - 1e-6);
+        let weaker = IirDesign::high_pass(cutoffs[0], fs).set_order(order - 1).set_prototype(prototype).design().unwrap();
+        assert!(max_gain_db(&weaker, (0.0, 2500.0), fs) > -60.0);
+    }
+
+    #[test]
+    fn invalid_specification_shall_be_rejected() {
+        assert_eq!(IirDesign::low_pass(100.0, 1000.0).design(), Err(IirDesignError::MissingOrder));
+        assert_eq!(IirDesign::low_pass(600.0, 1000.0).set_order(2).design(),
+                   Err(IirDesignError::InvalidCutoff{cutoff: 600.0, nyquist: 500.0}));
+        assert_eq!(IirDesign::band_pass(200.0, 100.0, 1000.0).set_order(2).design(),
+                   Err(IirDesignError::InvalidBand{start: 200.0, end: 100.0}));
+        assert_eq!(IirDesign::low_pass(100.0, 1000.0).set_order(2)
+                       .set_prototype(IirPrototype::Elliptic{ripple: 1.0, attenuation: 0.5}).design(),
+                   Err(IirDesignError::InvalidAttenuation(0.5)));
+        assert_eq!(butterworth_order(&[100.0, 200.0], &[150.0], 1.0, 40.0, 1000.0),
+                   Err(IirDesignError::InvalidBand{start: 100.0, end: 150.0}));
+    }
+}
@@ -0,0 +1,233 @@
+const LENGTH_BASE: [usize; 29] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31,
+                                  35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA: [u32; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2,
+                                 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DISTANCE_BASE: [usize; 30] = [1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193,
+                                    257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+                                    8193, 12289, 16385, 24577];
+const DISTANCE_EXTRA: [u32; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6,
+                                   7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+const MAX_BITS: usize = 15;
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bit: u32,
+}
+
+impl BitReader<'_> {
+    fn bits(&mut self, count: u32) -> Result<usize, String> {
+        let mut value = 0;
+        for i in 0..count {
+            let byte = *self.data.get(self.pos).ok_or("unexpected end of compressed data")?;
+            value |= (((byte >> self.bit) & 1) as usize) << i;
+            self.bit += 1;
+            if self.bit == 8 {
+                self.bit = 0;
+                self.pos += 1;
+            }
+        }
+        Ok(value)
+    }
+
+    fn align_to_byte(&mut self) {
+        if self.bit != 0 {
+            self.bit = 0;
+            self.pos += 1;
+        }
+    }
+}
+
+/// Canonical Huffman code stored as number of codes of every length and symbols ordered by code.
+struct Huffman {
+    counts: [usize; MAX_BITS + 1],
+    symbols: Vec<usize>,
+}
+
+impl Huffman {
+    fn new(lengths: &[usize]) -> Self {
+        let mut counts = [0; MAX_BITS + 1];
+        for &len in lengths {
+            counts[len] += 1;
+        }
+        counts[0] = 0;
+        let mut offsets = [0; MAX_BITS + 2];
+        for len in 1..=MAX_BITS {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+        let mut symbols = vec![0; offsets[MAX_BITS + 1]];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols[offsets[len]] = symbol;
+                offsets[len] += 1;
+            }
+        }
+        Self{counts, symbols}
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<usize, String> {
+        let mut code = 0;
+        let mut first = 0;
+        let mut index = 0;
+        for len in 1..=MAX_BITS {
+            code |= reader.bits(1)?;
+            let count = self.counts[len];
+            if code < first + count {
+                return Ok(self.symbols[index + code - first]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err("invalid Huffman code".to_string())
+    }
+}
+
+/// Decodes raw DEFLATE stream (RFC 1951), it is used to read compressed NumPy archives.
+pub(crate) fn inflate(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut reader = BitReader{data, pos: 0, bit: 0};
+    let mut out = vec![];
+
+    loop {
+        let last = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => stored_block(&mut reader, &mut out)?,
+            1 => {
+                let (literals, distances) = fixed_codes();
+                compressed_block(&mut reader, &mut out, &literals, &distances)?;
+            }
+            2 => {
+                let (literals, distances) = dynamic_codes(&mut reader)?;
+                compressed_block(&mut reader, &mut out, &literals, &distances)?;
+            }
+            _ => return Err("invalid block type".to_string()),
+        }
+        if last {
+            return Ok(out);
+        }
+    }
+}
+
+fn stored_block(reader: &mut BitReader, out: &mut Vec<u8>) -> Result<(), String> {
+    reader.align_to_byte();
+    let header = reader.data.get(reader.pos..reader.pos + 4).ok_or("truncated stored block")?;
+    let len = u16::from_le_bytes([header[0], header[1]]) as usize;
+    let nlen = u16::from_le_bytes([header[2], header[3]]) as usize;
+    if len != !nlen & 0xFFFF {
+        return Err("corrupted stored block length".to_string());
+    }
+    reader.pos += 4;
+    out.extend_from_slice(reader.data.get(reader.pos..reader.pos + len).ok_or("truncated stored block")?);
+    reader.pos += len;
+    Ok(())
+}
+
+fn fixed_codes() -> (Huffman, Huffman) {
+    let mut lengths = [0; 288];
+    for (symbol, len) in lengths.iter_mut().enumerate() {
+        *len = match symbol {
+            0..=143 => 8,
+            144..=255 => 9,
+            256..=279 => 7,
+            _ => 8,
+        };
+    }
+    (Huffman::new(&lengths), Huffman::new(&[5; 30]))
+}
+
+fn dynamic_codes(reader: &mut BitReader) -> Result<(Huffman, Huffman), String> {
+    let literal_count = reader.bits(5)? + 257;
+    let distance_count = reader.bits(5)? + 1;
+    let code_length_count = reader.bits(4)? + 4;
+
+    let mut code_lengths = [0; 19];
+    for &i in CODE_LENGTH_ORDER.iter().take(code_length_count) {
+        code_lengths[i] = reader.bits(3)?;
+    }
+    let code_length_code = Huffman::new(&code_lengths);
+
+    let mut lengths = vec![];
+    while lengths.len() < literal_count + distance_count {
+        let symbol = code_length_code.decode(reader)?;
+        let (value, repeat) = match symbol {
+            0..=15 => (symbol, 1),
+            16 => (*lengths.last().ok_or("repeat without previous length")?, 3 + reader.bits(2)?),
+            17 => (0, 3 + reader.bits(3)?),
+            _ => (0, 11 + reader.bits(7)?),
+        };
+        lengths.extend(std::iter::repeat_n(value, repeat));
+    }
+    if lengths.len() != literal_count + distance_count {
+        return Err("code lengths exceed declared count".to_string());
+    }
+
+    Ok((Huffman::new(&lengths[..literal_count]), Huffman::new(&lengths[literal_count..])))
+}
+
+fn compressed_block(reader: &mut BitReader, out: &mut Vec<u8>, literals: &Huffman, distances: &Huffman) -> Result<(), String> {
+    loop {
+        let symbol = literals.decode(reader)?;
+        match symbol {
+            0..=255 => out.push(symbol as u8),
+            256 => return Ok(()),
+            257..=285 => {
+                let i = symbol - 257;
+                let len = LENGTH_BASE[i] + reader.bits(LENGTH_EXTRA[i])?;
+                let d = distances.decode(reader)?;
+                if d >= DISTANCE_BASE.len() {
+                    return Err("invalid distance symbol".to_string());
+                }
+                let distance = DISTANCE_BASE[d] + reader.bits(DISTANCE_EXTRA[d])?;
+                if distance > out.len() {
+                    return Err("distance exceeds decoded data".to_string());
+                }
+                let start = out.len() - distance;
+                for k in 0..len {
+                    out.push(out[start + k]);
+                }
+            }
+            _ => return Err("invalid literal/length symbol".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored_block_shall_be_copied() {
+        let data = [0x01, 0x03, 0x00, 0xFC, 0xFF, b'a', b'b', b'c'];
+        assert_eq!(inflate(&data).unwrap(), b"abc");
+    }
+
+    #[test]
+    fn fixed_huffman_block_shall_be_decoded() {
+        // zlib.compress(b"hello hello hello hello", wbits=-15)
+        let data = [0xCB, 0x48, 0xCD, 0xC9, 0xC9, 0x57, 0xC8, 0x40, 0x27, 0x01];
+        assert_eq!(inflate(&data).unwrap(), b"hello hello hello hello");
+    }
+
+    #[test]
+    fn dynamic_huffman_block_shall_be_decoded() {
+        // 100 letters with skewed distribution compressed with zlib, level=9, wbits=-15
+        let alphabet = b"aaaaaaaabbbbccd";
+        let mut x: u64 = 1;
+        let expected = (0..100).map(|_| {
+            x = (x * 1103515245 + 12345) % (1 << 31);
+            alphabet[((x >> 16) % 15) as usize]
+        }).collect::<Vec<u8>>();
+        let data = [0x2D, 0x8C, 0x89, 0x09, 0x00, 0x40, 0x08, 0xC3, 0x66, 0xED, 0xB3, 0xFF, 0x0C, 0xD7, 0xD6, 0x03,
+                    0x11, 0x4C, 0x82, 0x14, 0x08, 0x80, 0xEA, 0xCA, 0x10, 0xDA, 0xA1, 0x51, 0xC3, 0xA5, 0x9A, 0x68,
+                    0xC0, 0x5F, 0xAB, 0xA8, 0x69, 0xB9, 0x4F, 0xF7, 0x13, 0xA3, 0x96, 0xDC, 0x33, 0xCA, 0x31, 0x0F];
+        assert_eq!(data[0] >> 1 & 3, 2);
+        assert_eq!(inflate(&data).unwrap(), expected);
+    }
+
+    #[test]
+    fn truncated_data_shall_be_rejected() {
+        assert!(inflate(&[0xCB, 0x48]).is_err());
+        assert!(inflate(&[0x07]).is_err());
+    }
+}
@@ -0,0 +1,223 @@
+use std::f64::consts::PI;
+
+use crate::linear_algebra::solve;
+use crate::FirDesignError;
+
+/// Linear phase FIR design that minimizes weighted integral of squared error between
+/// amplitude response and desired one (like `firls`). Bands are given in Hz as `(start, end)`
+/// pairs, desired response changes linearly inside band from gain at its start to gain at
+/// its end. Frequencies between bands are not constrained.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LeastSquares {
+    number_of_taps: usize,
+    bands: Vec<(f64, f64)>,
+    desired: Vec<(f64, f64)>,
+    weights: Vec<f64>,
+    sample_rate: f64,
+}
+
+impl LeastSquares {
+    /// Desired response of every band is given as `(gain at start, gain at end)`.
+    pub fn new(number_of_taps: usize, bands: &[(f64, f64)], desired: &[(f64, f64)], sample_rate: f64) -> Self {
+        Self{
+            number_of_taps,
+            bands: bands.to_vec(),
+            desired: desired.to_vec(),
+            weights: vec![1.0; bands.len()],
+            sample_rate,
+        }
+    }
+
+    /// Weight of squared error in every band, by default all weights are 1.
+    pub fn set_weights(mut self, weights: &[f64]) -> Self {
+        self.weights = weights.to_vec();
+        self
+    }
+
+    /// Symmetric coefficients of filter, odd or even number of them.
+    pub fn design(&self) -> Result<Vec<f64>, FirDesignError> {
+        self.validate()?;
+        let taps = self.number_of_taps;
+        // amplitude is sum of cos(2 pi (k + shift) f), shift is 1/2 for even number of taps
+        let (functions, shift) = if taps % 2 == 1 { (taps / 2 + 1, 0.0) } else { (taps / 2, 0.5) };
+        let omega = |k: f64| 2.0 * PI * k;
+
+        let mut matrix = vec![vec![0.0; functions]; functions];
+        let mut rhs = vec![0.0; functions];
+        for ((&(start, end), &(start_gain, end_gain)), weight) in self.bands.iter().zip(&self.desired).zip(&self.weights) {
+            let (f1, f2) = (start / self.sample_rate, end / self.sample_rate);
+            let slope = (end_gain - start_gain) / (f2 - f1);
+            let offset = start_gain - slope * f1;
+            for k in 0..functions {
+                let kf = k as f64 + shift;
+                for (l, q) in matrix[k].iter_mut().enumerate() {
+                    let lf = l as f64 + shift;
+                    *q += weight * 0.5 * (cos_integral(omega(kf - lf), f1, f2) + cos_integral(omega(kf + lf), f1, f2));
+                }
+                rhs[k] += weight * (offset * cos_integral(omega(kf), f1, f2) + slope * f_cos_integral(omega(kf), f1, f2));
+            }
+        }
+        let a = solve(&matrix, &rhs).ok_or(FirDesignError::IllConditioned)?;
+
+        let mut fir = vec![0.0; taps];
+        let center = taps / 2;
+        if taps % 2 == 1 {
+            fir[center] = a[0];
+            for (k, a) in a.iter().enumerate().skip(1) {
+                fir[center - k] = a / 2.0;
+                fir[center + k] = a / 2.0;
+            }
+        } else {
+            for (k, a) in a.iter().enumerate() {
+                fir[center - 1 - k] = a / 2.0;
+                fir[center + k] = a / 2.0;
+            }
+        }
+        Ok(fir)
+    }
+
+    fn validate(&self) -> Result<(), FirDesignError> {
+        if !(self.sample_rate > 0.0 && self.sample_rate.is_finite()) {
+            return Err(FirDesignError::InvalidSampleRate(self.sample_rate));
+        }
+        if self.number_of_taps == 0 {
+            return Err(FirDesignError::InvalidNumberOfTaps(0));
+        }
+        if self.bands.is_empty() {
+            return Err(FirDesignError::MissingBands);
+        }
+        for values in [self.desired.len(), self.weights.len()] {
+            if values != self.bands.len() {
+                return Err(FirDesignError::BandCountMismatch{bands: self.bands.len(), values});
+            }
+        }
+        if let Some(w) = self.weights.iter().find(|w| !(**w > 0.0 && w.is_finite())) {
+            return Err(FirDesignError::InvalidWeight(*w));
+        }
+        let nyquist = self.sample_rate / 2.0;
+        let mut previous_end = 0.0;
+        for &(start, end) in &self.bands {
+            if !(start >= previous_end && start < end && end <= nyquist) {
+                return Err(FirDesignError::InvalidBand{start, end});
+            }
+            previous_end = end;
+        }
+        let (last_end, last_desired) = (self.bands[self.bands.len() - 1].1, self.desired[self.desired.len() - 1].1);
+        if self.number_of_taps.is_multiple_of(2) && last_end == nyquist && last_desired != 0.0 {
+            return Err(FirDesignError::EvenNumberOfTaps(self.number_of_taps));
+        }
+        Ok(())
+    }
+}
+
+/// Integral of `cos(omega * f)` from `f1` to `f2`.
+fn cos_integral(omega: f64, f1: f64, f2: f64) -> f64 {
+    if omega == 0.0 {
+        f2 - f1
+    } else {
+        ((omega * f2).sin() - (omega * f1).sin()) / omega
+    }
+}
+
+/// Integral of `f * cos(omega * f)` from `f1` to `f2`.
+fn f_cos_integral(omega: f64, f1: f64, f2: f64) -> f64 {
+    if omega == 0.0 {
+        (f2 * f2 - f1 * f1) / 2.0
+    } else {
+        let primitive = |f: f64| f * (omega * f).sin() / omega + (omega * f).cos() / (omega * omega);
+        primitive(f2) - primitive(f1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::fir_amplitude;
+
+    /// Weighted squared error integrated numerically with trapezoidal rule.
+    fn squared_error(fir: &[f64], design: &LeastSquares) -> f64 {
+        let mut total = 0.0;
+        for ((&(start, end), &(g1, g2)), w) in design.bands.iter().zip(&design.desired).zip(&design.weights) {
+            let points = 2000;
+            for i in 0..=points {
+                let x = i as f64 / points as f64;
+                let f = start + (end - start) * x;
+                let e = fir_amplitude(fir, f / design.sample_rate).re() - (g1 + (g2 - g1) * x);
+                let factor = if i == 0 || i == points { 0.5 } else { 1.0 };
+                total += factor * w * e * e * (end - start) / points as f64;
+            }
+        }
+        total
+    }
+
+    #[test]
+    fn all_pass_response_shall_give_single_impulse() {
+        let fir = LeastSquares::new(7, &[(0.0, 500.0)], &[(1.0, 1.0)], 1000.0).design().unwrap();
+
+        for (n, h) in fir.iter().enumerate() {
+            assert!((h - if n == 3 { 1.0 } else { 0.0 }).abs() < 1e-12, "{:?}", fir);
+        }
+    }
+
+    #[test]
+    fn low_pass_shall_be_symmetric_and_attenuate_stop_band() {
+        let fir = LeastSquares::new(41, &[(0.0, 100.0), (150.0, 500.0)], &[(1.0, 1.0), (0.0, 0.0)], 1000.0)
+            .design()
+            .unwrap();
+
+        for (a, b) in fir.iter().zip(fir.iter().rev()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        assert!((fir_amplitude(&fir, 0.05).re() - 1.0).abs() < 0.01);
+        // squared error is minimized, so the largest error is at the edge of band
+        assert!(fir_amplitude(&fir, 0.15).re().abs() < 0.03);
+        for i in 0..=50 {
+            let f = 0.17 + 0.33 * i as f64 / 50.0;
+            assert!(fir_amplitude(&fir, f).re().abs() < 0.01, "{} {}", f, fir_amplitude(&fir, f).re());
+        }
+    }
+
+    #[test]
+    fn design_shall_minimize_weighted_squared_error() {
+        for taps in [20, 21] {
+            let design = LeastSquares::new(taps, &[(0.0, 0.1), (0.2, 0.3), (0.35, 0.5)], &[(0.0, 0.0), (0.5, 1.0), (0.0, 0.0)], 1.0)
+                .set_weights(&[2.0, 1.0, 5.0]);
+            let fir = design.design().unwrap();
+            let optimum = squared_error(&fir, &design);
+
+            for n in 0..taps / 2 {
+                for delta in [-1e-3, 1e-3] {
+                    let mut perturbed = fir.clone();
+                    perturbed[n] += delta;
+                    perturbed[taps - 1 - n] += delta;
+                    assert!(squared_error(&perturbed, &design) > optimum, "{} {}", taps, n);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn weights_shall_reduce_error_of_selected_band() {
+        let bands = [(0.0, 0.2), (0.25, 0.5)];
+        let desired = [(1.0, 1.0), (0.0, 0.0)];
+        let stop_band_error = |weights: &[f64]| {
+            let fir = LeastSquares::new(31, &bands, &desired, 1.0).set_weights(weights).design().unwrap();
+            (0..=100).map(|i| fir_amplitude(&fir, 0.25 + 0.25 * i as f64 / 100.0).re().abs()).fold(0.0, f64::max)
+        };
+
+        assert!(stop_band_error(&[1.0, 100.0]) < stop_band_error(&[1.0, 1.0]) / 2.0);
+    }
+
+    #[test]
+    fn invalid_specification_shall_be_rejected() {
+        assert_eq!(LeastSquares::new(20, &[(0.0, 0.2), (0.3, 0.5)], &[(1.0, 1.0), (1.0, 1.0)], 1.0).design(),
+                   Err(FirDesignError::EvenNumberOfTaps(20)));
+        assert_eq!(LeastSquares::new(21, &[(0.0, 0.3), (0.2, 0.5)], &[(1.0, 1.0), (0.0, 0.0)], 1.0).design(),
+                   Err(FirDesignError::InvalidBand{start: 0.2, end: 0.5}));
+        assert_eq!(LeastSquares::new(21, &[(0.0, 0.3)], &[(1.0, 1.0), (0.0, 0.0)], 1.0).design(),
+                   Err(FirDesignError::BandCountMismatch{bands: 1, values: 2}));
+        assert_eq!(LeastSquares::new(0, &[(0.0, 0.3)], &[(1.0, 1.0)], 1.0).design(),
+                   Err(FirDesignError::InvalidNumberOfTaps(0)));
+    }
+}
@@ -3,11 +3,70 @@ mod fft;
 mod complex_numbers;
 mod descrete_signal;
 mod filtering;
+mod stream_filter;
+mod statistics;
+mod multichannel_signal;
+mod complex_signal;
+mod metadata;
+mod wav;
+mod csv;
+mod binary;
+mod inflate;
+mod numpy;
+mod convolution;
+mod fast_convolution;
+mod fir_design;
+mod remez;
+mod least_squares;
+mod arbitrary_response;
+mod linear_algebra;
+mod polynomial;
+mod iir;
+mod transfer_function;
+mod iir_design;
+mod equalizer;
+mod filtfilt;
+mod frequency_response;
+mod smoothing;
+mod rank_filters;
+mod savitzky_golay;
+#[cfg(feature = "serde")]
+mod serialization;
+#[cfg(test)]
+mod test_utils;
 pub use gen::Generator;
 pub use descrete_signal::DescreteSignal;
-pub use fft::fft;
+pub use stream_filter::StreamFilter;
+pub use fft::{fft, complex_fft};
 pub use complex_numbers::ComplexNumber;
-pub use filtering::{get_averaging_fir_coefficients, fir_filter, get_low_pass_fir_coefficients};
+pub use statistics::Histogram;
+pub use multichannel_signal::MultichannelSignal;
+pub use complex_signal::ComplexSignal;
+pub use metadata::SignalMetadata;
+pub use wav::{read_wav, read_wav_from, Dither, WavError, WavRecording, WavSampleFormat, WavWriteReport, WavWriter};
+pub use csv::{CsvColumn, CsvError, CsvReader, CsvWriter};
+pub use binary::{BinaryError, Endianness, RawFormat, RawSampleType};
+pub use numpy::{read_npy, read_npy_from, read_npz, read_npz_from, write_npz, write_npz_to, NpyArray, NpyValues};
+pub use convolution::{Convolution, ConvolutionMode, GroupDelay};
+pub use fast_convolution::{choose_method, convolve, convolve_with, cross_correlation, direct_convolve, fft_convolve, fft_correlate,
+                           overlap_add, overlap_save, ConvolutionMethod, PartitionedConvolver};
+pub use fir_design::{kaiser_beta, kaiser_order, DesignMethod, FirDesign, FirDesignError, Window};
+pub use remez::{remez_order, ripple_deviations, Remez, RemezDesign, RemezFilterType};
+pub use least_squares::LeastSquares;
+pub use arbitrary_response::ArbitraryResponse;
+pub use iir::{Biquad, BiquadForm, SosFilter};
+pub use polynomial::Polynomial;
+pub use transfer_function::{TransferFunction, ZeroPoleGain};
+pub use iir_design::{butterworth_order, chebyshev1_order, elliptic_order, IirDesign, IirDesignError, IirPrototype};
+pub use equalizer::{EqBand, EqError, EqFilterType, EqWidth, ParametricEqualizer};
+pub use filtfilt::{filtfilt, EdgePadding, FiltFilt, FiltFiltMethod};
+pub use frequency_response::{frequency_grid, FilterAnalysis, FrequencyResponse};
+pub use smoothing::{CascadedBoxcar, ExponentialMovingAverage, MovingAverage, MovingMaximum, MovingMinimum, MovingRms};
+pub use rank_filters::{HampelFilter, RankFilter, WeightedMedianFilter, WindowBoundary};
+pub use savitzky_golay::{SavitzkyGolay, SavitzkyGolayEdge, SavitzkyGolayError};
+pub use filtering::{get_averaging_fir_coefficients, fir_filter, FirFilter, complex_fir_filter};
+#[allow(deprecated)]
+pub use filtering::get_low_pass_fir_coefficients;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,112 @@
+/// Solves `matrix * x = rhs` with Gaussian elimination and partial pivoting.
+/// Returns `None` when matrix is singular (or numerically close to singular).
+pub(crate) fn solve(matrix: &[Vec<f64>], rhs: &[f64]) -> Option<Vec<f64>> {
+    let n = rhs.len();
+    assert!(matrix.len() == n && matrix.iter().all(|row| row.len() == n), "matrix shall be square and match right hand side");
+    let mut a = matrix.iter().zip(rhs).map(|(row, b)| {
+        let mut row = row.clone();
+        row.push(*b);
+        row
+    }).collect::<Vec<Vec<f64>>>();
+    let scale = matrix.iter().flatten().fold(0.0, |m: f64, x| m.max(x.abs()));
+    if scale == 0.0 {
+        return None;
+    }
+
+    for column in 0..n {
+        let pivot = (column..n).max_by(|&i, &j| a[i][column].abs().total_cmp(&a[j][column].abs()))?;
+        if a[pivot][column].abs() <= scale * 1e-14 {
+            return None;
+        }
+        a.swap(column, pivot);
+        let (upper, lower) = a.split_at_mut(column + 1);
+        let pivot_row = &upper[column];
+        for row in lower.iter_mut() {
+            let factor = row[column] / pivot_row[column];
+            if factor != 0.0 {
+                for (x, p) in row[column..].iter_mut().zip(&pivot_row[column..]) {
+                    *x -= factor * p;
+                }
+            }
+        }
+    }
+
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum = (row + 1..n).map(|k| a[row][k] * x[k]).sum::<f64>();
+        x[row] = (a[row][n] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+/// Least squares solution of overdetermined system `matrix * x = rhs` with Householder QR.
+/// Returns `None` when there are fewer rows than columns or columns are linearly dependent.
+pub(crate) fn least_squares(matrix: &[Vec<f64>], rhs: &[f64]) -> Option<Vec<f64>> {
+    let rows = rhs.len();
+    let columns = matrix.first().map_or(0, |row| row.len());
+    assert!(matrix.len() == rows && matrix.iter().all(|row| row.len() == columns), "matrix shall match right hand side");
+    if rows < columns || columns == 0 {
+        return None;
+    }
+    // column major copy, reflections are applied to whole columns
+    let mut a = (0..columns).map(|j| matrix.iter().map(|row| row[j]).collect()).collect::<Vec<Vec<f64>>>();
+    let mut b = rhs.to_vec();
+
+    for k in 0..columns {
+        let original = a[k].iter().map(|x| x * x).sum::<f64>().sqrt();
+        let norm = a[k][k..].iter().map(|x| x * x).sum::<f64>().sqrt();
+        if norm <= original * 1e-12 || original == 0.0 {
+            return None;
+        }
+        let alpha = if a[k][k] > 0.0 { -norm } else { norm };
+        let mut v = a[k][k..].to_vec();
+        v[0] -= alpha;
+        let length = v.iter().map(|x| x * x).sum::<f64>();
+        let reflect = |column: &mut [f64]| {
+            let factor = 2.0 * v.iter().zip(column.iter()).map(|(v, x)| v * x).sum::<f64>() / length;
+            for (x, v) in column.iter_mut().zip(&v) {
+                *x -= factor * v;
+            }
+        };
+        for column in a[k..].iter_mut() {
+            reflect(&mut column[k..]);
+        }
+        reflect(&mut b[k..]);
+    }
+
+    let mut x = vec![0.0; columns];
+    for row in (0..columns).rev() {
+        let sum = (row + 1..columns).map(|k| a[k][row] * x[k]).sum::<f64>();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_shall_find_solution_of_system_that_needs_pivoting() {
+        let matrix = vec![vec![0.0, 2.0, 1.0], vec![1.0, 1.0, 0.0], vec![3.0, 0.0, 1.0]];
+        let x = solve(&matrix, &[5.0, 3.0, 6.0]).unwrap();
+
+        for (x, expected) in x.iter().zip([1.4, 1.6, 1.8]) {
+            assert!((x - expected).abs() < 1e-12, "{} {}", x, expected);
+        }
+        assert_eq!(solve(&[vec![1.0, 2.0], vec![2.0, 4.0]], &[1.0, 2.0]), None);
+    }
+
+    #[test]
+    fn least_squares_shall_fit_overdetermined_system() {
+        // line 2 + 3x with residuals that are orthogonal to columns
+        let x = [0.0, 1.0, 2.0, 3.0];
+        let matrix = x.iter().map(|x| vec![1.0, *x]).collect::<Vec<Vec<f64>>>();
+        let rhs = x.iter().zip([1.0, -1.0, -1.0, 1.0]).map(|(x, r)| 2.0 + 3.0 * x + 0.5 * r).collect::<Vec<f64>>();
+        let solution = least_squares(&matrix, &rhs).unwrap();
+
+        assert!((solution[0] - 2.0).abs() < 1e-12 && (solution[1] - 3.0).abs() < 1e-12, "{:?}", solution);
+        assert_eq!(least_squares(&[vec![1.0, 2.0], vec![2.0, 4.0], vec![3.0, 6.0]], &[1.0, 2.0, 3.0]), None);
+        assert_eq!(least_squares(&[vec![1.0, 2.0]], &[1.0]), None);
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+
+/// Optional description of signal: its name, physical units of both axes, nominal sampling
+/// rate and free-form key/value properties (e.g. provenance of the data).
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SignalMetadata {
+    name: Option<String>,
+    time_unit: Option<String>,
+    value_unit: Option<String>,
+    sample_rate: Option<f64>,
+    properties: BTreeMap<String, String>,
+}
+
+impl SignalMetadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn set_time_unit(mut self, unit: &str) -> Self {
+        self.time_unit = Some(unit.to_string());
+        self
+    }
+
+    pub fn set_value_unit(mut self, unit: &str) -> Self {
+        self.value_unit = Some(unit.to_string());
+        self
+    }
+
+    pub fn set_sample_rate(mut self, rate: f64) -> Self {
+        self.sample_rate = Some(rate);
+        self
+    }
+
+    pub fn set_property(mut self, key: &str, value: &str) -> Self {
+        self.properties.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn time_unit(&self) -> Option<&str> {
+        self.time_unit.as_deref()
+    }
+
+    pub fn value_unit(&self) -> Option<&str> {
+        self.value_unit.as_deref()
+    }
+
+    pub fn sample_rate(&self) -> Option<f64> {
+        self.sample_rate
+    }
+
+    pub fn property(&self, key: &str) -> Option<&str> {
+        self.properties.get(key).map(String::as_str)
+    }
+
+    pub fn properties(&self) -> &BTreeMap<String, String> {
+        &self.properties
+    }
+
+    /// Combines metadata of two signals. Values set in `self` take precedence, missing ones are
+    /// taken from `other`. Sample rate is kept only if it does not differ between signals.
+    pub fn merge(&self, other: &SignalMetadata) -> Self {
+        let mut properties = other.properties.clone();
+        properties.extend(self.properties.clone());
+        let sample_rate = match (self.sample_rate, other.sample_rate) {
+            (Some(a), Some(b)) if a != b => None,
+            (a, b) => a.or(b),
+        };
+
+        Self{
+            name: self.name.clone().or_else(|| other.name.clone()),
+            time_unit: self.time_unit.clone().or_else(|| other.time_unit.clone()),
+            value_unit: self.value_unit.clone().or_else(|| other.value_unit.clone()),
+            sample_rate,
+            properties,
+        }
+    }
+
+    /// Metadata for spectrum calculated from signal described by `self`.
+    /// Time unit is replaced by matching frequency unit and sample rate is dropped.
+    pub fn to_spectrum(&self) -> Self {
+        Self{
+            time_unit: self.time_unit.as_deref().map(frequency_unit),
+            sample_rate: None,
+            ..self.clone()
+        }
+    }
+}
+
+fn frequency_unit(time_unit: &str) -> String {
+    match time_unit {
+        "s" => "Hz".to_string(),
+        "ms" => "kHz".to_string(),
+        "us" | "µs" => "MHz".to_string(),
+        "ns" => "GHz".to_string(),
+        unit => format!("1/{}", unit),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn setters_shall_fill_metadata_fields() {
+        let meta = SignalMetadata::new()
+            .set_name("Microphone")
+            .set_time_unit("s")
+            .set_value_unit("Pa")
+            .set_sample_rate(48000.0)
+            .set_property("source", "recording.wav");
+
+        assert_eq!(meta.name(), Some("Microphone"));
+        assert_eq!(meta.time_unit(), Some("s"));
+        assert_eq!(meta.value_unit(), Some("Pa"));
+        assert_eq!(meta.sample_rate(), Some(48000.0));
+        assert_eq!(meta.property("source"), Some("recording.wav"));
+        assert_eq!(meta.property("missing"), None);
+    }
+
+    #[test]
+    fn merge_shall_prefer_own_values_and_fill_missing_ones() {
+        let first = SignalMetadata::new().set_name("first").set_sample_rate(100.0).set_property("a", "1");
+        let second = SignalMetadata::new().set_name("second").set_value_unit("V").set_sample_rate(100.0)
+            .set_property("a", "2").set_property("b", "3");
+        let merged = first.merge(&second);
+
+        assert_eq!(merged.name(), Some("first"));
+        assert_eq!(merged.value_unit(), Some("V"));
+        assert_eq!(merged.sample_rate(), Some(100.0));
+        assert_eq!(merged.property("a"), Some("1"));
+        assert_eq!(merged.property("b"), Some("3"));
+    }
+
+    #[test]
+    fn merge_shall_drop_conflicting_sample_rates() {
+        let first = SignalMetadata::new().set_sample_rate(100.0);
+        let second = SignalMetadata::new().set_sample_rate(200.0);
+
+        assert_eq!(first.merge(&second).sample_rate(), None);
+        assert_eq!(first.merge(&SignalMetadata::new()).sample_rate(), Some(100.0));
+    }
+
+    #[test]
+    fn spectrum_metadata_shall_use_frequency_units() {
+        let meta = SignalMetadata::new().set_name("x").set_time_unit("ms").set_value_unit("V").set_sample_rate(10.0);
+        let spectrum = meta.to_spectrum();
+
+        assert_eq!(spectrum.time_unit(), Some("kHz"));
+        assert_eq!(spectrum.value_unit(), Some("V"));
+        assert_eq!(spectrum.name(), Some("x"));
+        assert_eq!(spectrum.sample_rate(), None);
+        assert_eq!(SignalMetadata::new().set_time_unit("s").to_spectrum().time_unit(), Some("Hz"));
+        assert_eq!(SignalMetadata::new().set_time_unit("m").to_spectrum().time_unit(), Some("1/m"));
+    }
+}
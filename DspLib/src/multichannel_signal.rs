@@ -0,0 +1,333 @@
+use crate::{fft, fir_filter, DescreteSignal, SignalMetadata};
+
+/// Set of synchronized channels that share one time axis and metadata.
+/// Values are stored in planar layout, every channel in separate vector.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MultichannelSignal {
+    time: Vec<f64>,
+    channels: Vec<Vec<f64>>,
+    metadata: SignalMetadata,
+}
+
+impl MultichannelSignal {
+    pub fn new(number_of_channels: usize) -> Self {
+        Self{time: Vec::new(), channels: vec![Vec::new(); number_of_channels], metadata: SignalMetadata::default()}
+    }
+
+    /// Creates signal from time axis and planar channel data.
+    /// Panics when any channel has different length than time axis.
+    pub fn new_from_planar(time: Vec<f64>, channels: Vec<Vec<f64>>) -> Self {
+        for ch in &channels {
+            assert_eq!(ch.len(), time.len(), "every channel shall have as many samples as time axis");
+        }
+        Self{time, channels, metadata: SignalMetadata::default()}
+    }
+
+    /// Creates signal from single channel signals. Timestamps and metadata are taken from
+    /// the first signal. Panics when signals differ in timestamps.
+    pub fn from_signals(signals: &[DescreteSignal]) -> Self {
+        let time = signals.first().map(|s| s.times().collect::<Vec<f64>>()).unwrap_or_default();
+        let mut channels = Vec::with_capacity(signals.len());
+
+        for s in signals {
+            assert!(s.times().eq(time.iter().copied()), "all signals shall share the same timestamps");
+            channels.push(s.values().collect());
+        }
+        let metadata = signals.first().map(|s| s.metadata().clone()).unwrap_or_default();
+
+        Self{time, channels, metadata}
+    }
+
+    /// Creates signal from interleaved samples (`ch0, ch1, ..., ch0, ch1, ...`).
+    /// Timestamps start at `start_time` and are spaced by `1/sampling_rate`.
+    /// Incomplete frame at the end of the data is dropped.
+    pub fn from_interleaved(samples: &[f64], number_of_channels: usize, sampling_rate: f64, start_time: f64) -> Self {
+        let frames = samples.len().checked_div(number_of_channels).unwrap_or(0);
+        let mut channels = vec![Vec::with_capacity(frames); number_of_channels];
+
+        for frame in samples.chunks_exact(number_of_channels.max(1)).take(frames) {
+            for (ch, y) in channels.iter_mut().zip(frame) {
+                ch.push(*y);
+            }
+        }
+
+        Self{
+            time: Self::uniform_time(frames, sampling_rate, start_time),
+            channels,
+            metadata: SignalMetadata::new().set_sample_rate(sampling_rate),
+        }
+    }
+
+    /// Creates signal from planar channel data with timestamps calculated from sampling rate.
+    pub fn from_planar(channels: Vec<Vec<f64>>, sampling_rate: f64, start_time: f64) -> Self {
+        let len = channels.first().map_or(0, Vec::len);
+        let mut signal = Self::new_from_planar(Self::uniform_time(len, sampling_rate, start_time), channels);
+        signal.metadata = SignalMetadata::new().set_sample_rate(sampling_rate);
+        signal
+    }
+
+    /// Appends one frame, `values` shall hold one value per channel.
+    pub fn push(&mut self, time: f64, values: &[f64]) {
+        assert_eq!(values.len(), self.channels.len(), "frame shall have value for every channel");
+        self.time.push(time);
+        for (ch, y) in self.channels.iter_mut().zip(values) {
+            ch.push(*y);
+        }
+    }
+
+    /// Number of samples in every channel.
+    pub fn len(&self) -> usize {
+        self.time.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.time.is_empty()
+    }
+
+    pub fn number_of_channels(&self) -> usize {
+        self.channels.len()
+    }
+
+    pub fn metadata(&self) -> &SignalMetadata {
+        &self.metadata
+    }
+
+    pub fn set_metadata(&mut self, metadata: SignalMetadata) {
+        self.metadata = metadata;
+    }
+
+    pub fn times(&self) -> &[f64] {
+        &self.time
+    }
+
+    pub fn channel_values(&self, channel: usize) -> &[f64] {
+        &self.channels[channel]
+    }
+
+    pub fn channel_values_mut(&mut self, channel: usize) -> &mut [f64] {
+        &mut self.channels[channel]
+    }
+
+    pub fn channel(&self, channel: usize) -> DescreteSignal {
+        let mut signal = self.time.iter()
+            .copied()
+            .zip(self.channels[channel].iter().copied())
+            .collect::<DescreteSignal>();
+        signal.set_metadata(self.metadata.clone());
+        signal
+    }
+
+    pub fn to_signals(&self) -> Vec<DescreteSignal> {
+        (0..self.channels.len()).map(|ch| self.channel(ch)).collect()
+    }
+
+    /// New signal holding only given channels, in given order. Channels can be repeated.
+    pub fn select_channels(&self, channels: &[usize]) -> Self {
+        Self{
+            time: self.time.clone(),
+            channels: channels.iter().map(|&ch| self.channels[ch].clone()).collect(),
+            metadata: self.metadata.clone(),
+        }
+    }
+
+    pub fn add_channel(&mut self, values: Vec<f64>) {
+        assert_eq!(values.len(), self.time.len(), "channel shall have as many samples as time axis");
+        self.channels.push(values);
+    }
+
+    pub fn remove_channel(&mut self, channel: usize) -> Vec<f64> {
+        self.channels.remove(channel)
+    }
+
+    pub fn to_interleaved(&self) -> Vec<f64> {
+        let mut samples = Vec::with_capacity(self.len() * self.channels.len());
+        for i in 0..self.len() {
+            samples.extend(self.channels.iter().map(|ch| ch[i]));
+        }
+        samples
+    }
+
+    pub fn to_planar(&self) -> Vec<Vec<f64>> {
+        self.channels.clone()
+    }
+
+    /// Mixes channels with matrix that has one row per output channel and one column
+    /// per input channel, so `out[o] = sum(matrix[o][i] * in[i])`.
+    pub fn mix(&self, matrix: &[Vec<f64>]) -> Self {
+        let mut channels = vec![vec![0.0; self.len()]; matrix.len()];
+
+        for (out, row) in channels.iter_mut().zip(matrix) {
+            assert_eq!(row.len(), self.channels.len(), "mixing matrix shall have one column per input channel");
+            for (gain, input) in row.iter().zip(&self.channels) {
+                for (y, x) in out.iter_mut().zip(input) {
+                    *y += gain * x;
+                }
+            }
+        }
+
+        Self{time: self.time.clone(), channels, metadata: self.metadata.clone()}
+    }
+
+    /// Runs `process` for every channel and gathers the results in new multichannel signal.
+    /// Processing shall produce the same timestamps for all channels.
+    pub fn process_channels<F>(&self, mut process: F) -> Self
+        where F: FnMut(usize, &DescreteSignal) -> DescreteSignal
+    {
+        let outputs = (0..self.channels.len())
+            .map(|ch| process(ch, &self.channel(ch)))
+            .collect::<Vec<DescreteSignal>>();
+
+        Self::from_signals(&outputs)
+    }
+
+    /// Filters all channels with the same FIR, see [`fir_filter`].
+    pub fn fir_filter(&self, fir: &[f64], output: &mut MultichannelSignal) {
+        *output = self.process_channels(|_, signal| {
+            let mut filtered = DescreteSignal::new();
+            fir_filter(signal, fir, &mut filtered);
+            filtered
+        });
+    }
+
+    /// Filters every channel with its own FIR, `firs` shall hold one FIR per channel.
+    pub fn fir_filter_per_channel(&self, firs: &[&[f64]], output: &mut MultichannelSignal) {
+        assert_eq!(firs.len(), self.channels.len(), "there shall be one FIR per channel");
+        *output = self.process_channels(|ch, signal| {
+            let mut filtered = DescreteSignal::new();
+            fir_filter(signal, firs[ch], &mut filtered);
+            filtered
+        });
+    }
+
+    /// Calculates spectrum of every channel, see [`fft`].
+    pub fn fft(&self, spectrum: &mut MultichannelSignal) {
+        *spectrum = self.process_channels(|_, signal| {
+            let mut channel_spectrum = DescreteSignal::new();
+            fft(signal, &mut channel_spectrum);
+            channel_spectrum
+        });
+    }
+
+    fn uniform_time(len: usize, sampling_rate: f64, start_time: f64) -> Vec<f64> {
+        (0..len).map(|i| start_time + i as f64 / sampling_rate).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Generator;
+
+    fn two_channel_signal() -> MultichannelSignal {
+        MultichannelSignal::new_from_planar(
+            vec![0.0, 0.5, 1.0],
+            vec![vec![1.0, 2.0, 3.0], vec![-1.0, -2.0, -3.0]])
+    }
+
+    #[test]
+    fn channels_shall_be_converted_to_descrete_signals() {
+        let signal = two_channel_signal();
+
+        assert_eq!(signal.len(), 3);
+        assert_eq!(signal.number_of_channels(), 2);
+        assert_eq!(signal.channel(1).get_data(), &[(0.0, -1.0), (0.5, -2.0), (1.0, -3.0)]);
+        assert_eq!(MultichannelSignal::from_signals(&signal.to_signals()), signal);
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_signals_shall_reject_signals_with_different_timestamps() {
+        let first = DescreteSignal::new_from_vec(vec![(0.0, 1.0), (1.0, 1.0)]);
+        let second = DescreteSignal::new_from_vec(vec![(0.0, 1.0), (2.0, 1.0)]);
+        MultichannelSignal::from_signals(&[first, second]);
+    }
+
+    #[test]
+    fn interleaved_data_shall_be_split_into_channels() {
+        let samples = [1.0, -1.0, 2.0, -2.0, 3.0, -3.0, 4.0];
+        let signal = MultichannelSignal::from_interleaved(&samples, 2, 2.0, 0.0);
+
+        assert_eq!(signal.times(), two_channel_signal().times());
+        assert_eq!(signal.to_planar(), two_channel_signal().to_planar());
+        assert_eq!(signal.metadata().sample_rate(), Some(2.0));
+        assert_eq!(signal.to_interleaved(), samples[..6].to_vec());
+    }
+
+    #[test]
+    fn planar_data_shall_get_timestamps_from_sampling_rate() {
+        let signal = MultichannelSignal::from_planar(two_channel_signal().to_planar(), 2.0, 0.0);
+
+        assert_eq!(signal.times(), two_channel_signal().times());
+        assert_eq!(signal.to_planar(), two_channel_signal().to_planar());
+        assert_eq!(signal.metadata().sample_rate(), Some(2.0));
+    }
+
+    #[test]
+    fn push_shall_append_frame_to_all_channels() {
+        let mut signal = MultichannelSignal::new(2);
+        signal.push(0.0, &[1.0, -1.0]);
+        signal.push(0.5, &[2.0, -2.0]);
+        signal.push(1.0, &[3.0, -3.0]);
+
+        assert_eq!(signal, two_channel_signal());
+    }
+
+    #[test]
+    fn select_channels_shall_reorder_and_repeat_channels() {
+        let signal = two_channel_signal().select_channels(&[1, 1, 0]);
+
+        assert_eq!(signal.number_of_channels(), 3);
+        assert_eq!(signal.channel_values(0), &[-1.0, -2.0, -3.0]);
+        assert_eq!(signal.channel_values(1), &[-1.0, -2.0, -3.0]);
+        assert_eq!(signal.channel_values(2), &[1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn mix_shall_combine_channels_with_matrix() {
+        let mixed = two_channel_signal().mix(&[vec![0.5, 0.5], vec![1.0, -1.0], vec![2.0, 0.0]]);
+
+        assert_eq!(mixed.channel_values(0), &[0.0, 0.0, 0.0]);
+        assert_eq!(mixed.channel_values(1), &[2.0, 4.0, 6.0]);
+        assert_eq!(mixed.channel_values(2), &[2.0, 4.0, 6.0]);
+        assert_eq!(mixed.times(), two_channel_signal().times());
+    }
+
+    #[test]
+    fn fir_filter_shall_process_every_channel() {
+        let signal = two_channel_signal();
+        let mut output = MultichannelSignal::default();
+
+        signal.fir_filter(&[1.0, 1.0], &mut output);
+        assert_eq!(output.times(), &[0.0, 0.5]);
+        assert_eq!(output.channel_values(0), &[3.0, 5.0]);
+        assert_eq!(output.channel_values(1), &[-3.0, -5.0]);
+
+        signal.fir_filter_per_channel(&[&[1.0, 0.0], &[0.0, 2.0]], &mut output);
+        assert_eq!(output.channel_values(0), &[2.0, 3.0]);
+        assert_eq!(output.channel_values(1), &[-2.0, -4.0]);
+    }
+
+    #[test]
+    fn fft_shall_calculate_spectrum_of_every_channel() {
+        let sine = Generator::sine_wave(10.0).set_sampling_rate(80.0).generate();
+        let signal = MultichannelSignal::from_signals(&[sine.clone(), sine]);
+        let mut spectrum = MultichannelSignal::default();
+        let mut expected = DescreteSignal::new();
+
+        signal.fft(&mut spectrum);
+        fft(&signal.channel(0), &mut expected);
+        assert_eq!(spectrum.channel(0), expected);
+        assert_eq!(spectrum.channel(1), expected);
+    }
+
+    #[test]
+    fn generator_shall_fill_all_channels_with_the_same_signal() {
+        let mut gen = Generator::triangle_wave(2.0).set_amplitude(3.0);
+        let signal = gen.generate_multichannel(3);
+
+        assert_eq!(signal.number_of_channels(), 3);
+        for ch in signal.to_signals() {
+            assert_eq!(ch, gen.generate());
+        }
+    }
+}
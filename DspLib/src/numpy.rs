@@ -0,0 +1,557 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::binary::{BinaryError, Endianness, RawSampleType};
+use crate::inflate::inflate;
+use crate::{ComplexNumber, ComplexSignal, DescreteSignal, MultichannelSignal};
+
+const NPY_MAGIC: &[u8] = b"\x93NUMPY";
+const ZIP_LOCAL_HEADER: u32 = 0x04034b50;
+const ZIP_CENTRAL_HEADER: u32 = 0x02014b50;
+const ZIP_END_OF_CENTRAL_DIRECTORY: u32 = 0x06054b50;
+
+/// Values of NumPy array in C (row-major) order.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NpyValues {
+    Real(Vec<f64>),
+    Complex(Vec<ComplexNumber>),
+}
+
+/// NumPy array with its shape and element type. Element type is kept, so array read from
+/// `.npy` file is written back in the same type. For complex arrays type describes one component.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NpyArray {
+    shape: Vec<usize>,
+    sample_type: RawSampleType,
+    values: NpyValues,
+}
+
+impl NpyArray {
+    /// One dimensional `float64` array.
+    pub fn from_values(values: Vec<f64>) -> Self {
+        Self{shape: vec![values.len()], sample_type: RawSampleType::F64, values: NpyValues::Real(values)}
+    }
+
+    /// One dimensional `complex128` array.
+    pub fn from_complex(values: Vec<ComplexNumber>) -> Self {
+        Self{shape: vec![values.len()], sample_type: RawSampleType::F64, values: NpyValues::Complex(values)}
+    }
+
+    /// Array of shape `(N, 2)` holding timestamp and value of every sample.
+    pub fn from_signal(signal: &DescreteSignal) -> Self {
+        let values = signal.iter().flat_map(|(x, y)| [*x, *y]).collect();
+        Self{shape: vec![signal.len(), 2], sample_type: RawSampleType::F64, values: NpyValues::Real(values)}
+    }
+
+    /// Array of shape `(N, channels)` holding values of all channels.
+    pub fn from_multichannel(signal: &MultichannelSignal) -> Self {
+        Self{
+            shape: vec![signal.len(), signal.number_of_channels()],
+            sample_type: RawSampleType::F64,
+            values: NpyValues::Real(signal.to_interleaved()),
+        }
+    }
+
+    pub fn from_complex_signal(signal: &ComplexSignal) -> Self {
+        Self::from_complex(signal.samples().collect())
+    }
+
+    /// Changes type that the array is written with. Complex arrays support only `F32` and `F64`.
+    pub fn set_sample_type(mut self, sample_type: RawSampleType) -> Self {
+        self.sample_type = sample_type;
+        self
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    pub fn sample_type(&self) -> RawSampleType {
+        self.sample_type
+    }
+
+    pub fn values(&self) -> &NpyValues {
+        &self.values
+    }
+
+    /// Restores signal written with [`NpyArray::from_signal`].
+    pub fn to_signal(&self) -> Result<DescreteSignal, BinaryError> {
+        match (&self.values, self.shape.as_slice()) {
+            (NpyValues::Real(values), [_, 2]) => Ok(values.chunks_exact(2).map(|p| (p[0], p[1])).collect()),
+            _ => Err(BinaryError::InvalidFormat(format!("expected real array of shape (N, 2), got {:?}", self.shape))),
+        }
+    }
+
+    /// Treats one dimensional array as one channel and two dimensional array as set of
+    /// channels in columns. Timestamps are calculated from sampling rate.
+    pub fn to_multichannel(&self, sampling_rate: f64) -> Result<MultichannelSignal, BinaryError> {
+        let channels = match self.shape.as_slice() {
+            [_] => 1,
+            [_, channels] => *channels,
+            _ => return Err(BinaryError::InvalidFormat(format!("expected 1 or 2 dimensional array, got {:?}", self.shape))),
+        };
+        match &self.values {
+            NpyValues::Real(values) => Ok(MultichannelSignal::from_interleaved(values, channels, sampling_rate, 0.0)),
+            NpyValues::Complex(_) => Err(BinaryError::UnsupportedType("complex array as real signal".to_string())),
+        }
+    }
+
+    pub fn to_complex_signal(&self, sampling_rate: f64) -> Result<ComplexSignal, BinaryError> {
+        match (&self.values, self.shape.len()) {
+            (NpyValues::Complex(values), 1) => Ok(ComplexSignal::from_samples(values, sampling_rate, 0.0)),
+            (NpyValues::Real(values), 1) => {
+                let values = values.iter().map(|y| ComplexNumber::new(*y, 0.0)).collect::<Vec<ComplexNumber>>();
+                Ok(ComplexSignal::from_samples(&values, sampling_rate, 0.0))
+            }
+            _ => Err(BinaryError::InvalidFormat(format!("expected 1 dimensional array, got {:?}", self.shape))),
+        }
+    }
+
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), BinaryError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes array in `.npy` format version 1.0, always in little endian byte order.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<(), BinaryError> {
+        writer.write_all(&self.to_npy_bytes()?)?;
+        Ok(())
+    }
+
+    fn to_npy_bytes(&self) -> Result<Vec<u8>, BinaryError> {
+        let descr = descr(self.sample_type, matches!(self.values, NpyValues::Complex(_)))?;
+        let shape = match self.shape.as_slice() {
+            [n] => format!("({},)", n),
+            dims => format!("({})", dims.iter().map(usize::to_string).collect::<Vec<String>>().join(", ")),
+        };
+        let mut header = format!("{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}", descr, shape);
+        let unpadded = NPY_MAGIC.len() + 4 + header.len() + 1;
+        header.push_str(&" ".repeat(unpadded.next_multiple_of(64) - unpadded));
+        header.push('\n');
+
+        let mut bytes = NPY_MAGIC.to_vec();
+        bytes.extend_from_slice(&[1, 0]);
+        bytes.extend_from_slice(&field::<u16>(header.len(), "npy header length")?.to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        match &self.values {
+            NpyValues::Real(values) => {
+                for y in values {
+                    self.sample_type.encode(*y, Endianness::Little, &mut bytes);
+                }
+            }
+            NpyValues::Complex(values) => {
+                for y in values {
+                    self.sample_type.encode(y.re(), Endianness::Little, &mut bytes);
+                    self.sample_type.encode(y.im(), Endianness::Little, &mut bytes);
+                }
+            }
+        }
+        Ok(bytes)
+    }
+
+    fn from_npy_bytes(bytes: &[u8]) -> Result<Self, BinaryError> {
+        if bytes.len() < 10 || &bytes[..6] != NPY_MAGIC {
+            return Err(BinaryError::InvalidFormat("missing NUMPY magic string".to_string()));
+        }
+        let (header_len, header_start) = match bytes[6] {
+            1 => (u16::from_le_bytes([bytes[8], bytes[9]]) as usize, 10),
+            2 | 3 if bytes.len() >= 12 => (u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize, 12),
+            version => return Err(BinaryError::InvalidFormat(format!("unsupported npy version {}", version))),
+        };
+        let header = bytes.get(header_start..header_start + header_len)
+            .ok_or(BinaryError::InvalidFormat("truncated header".to_string()))?;
+        let header = String::from_utf8_lossy(header);
+        let descr = header_value(&header, "descr")?.trim_matches(|c| c == '\'' || c == '"').to_string();
+        let fortran_order = header_value(&header, "fortran_order")? == "True";
+        let shape = parse_shape(header_value(&header, "shape")?)?;
+        let (sample_type, endianness, complex) = parse_descr(&descr)?;
+
+        let too_large = || BinaryError::InvalidFormat(format!("shape {:?} is too large", shape));
+        let count = shape.iter().try_fold(if complex { 2usize } else { 1 }, |count, dim| count.checked_mul(*dim))
+            .ok_or_else(too_large)?;
+        let data_len = count.checked_mul(sample_type.size()).ok_or_else(too_large)?;
+        let data = &bytes[header_start + header_len..];
+        if data.len() < data_len {
+            return Err(BinaryError::InvalidFormat("array data is shorter than its shape".to_string()));
+        }
+        let mut raw = data.chunks_exact(sample_type.size())
+            .take(count)
+            .map(|b| sample_type.decode(b, endianness))
+            .collect::<Vec<f64>>();
+        if fortran_order && shape.len() > 1 {
+            raw = fortran_to_c_order(&raw, &shape, if complex { 2 } else { 1 });
+        }
+        let values = if complex {
+            NpyValues::Complex(raw.chunks_exact(2).map(|c| ComplexNumber::new(c[0], c[1])).collect())
+        } else {
+            NpyValues::Real(raw)
+        };
+
+        Ok(Self{shape, sample_type, values})
+    }
+}
+
+pub fn read_npy<P: AsRef<Path>>(path: P) -> Result<NpyArray, BinaryError> {
+    read_npy_from(BufReader::new(File::open(path)?))
+}
+
+pub fn read_npy_from<R: Read>(mut reader: R) -> Result<NpyArray, BinaryError> {
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+    NpyArray::from_npy_bytes(&bytes)
+}
+
+/// Reads all arrays from `.npz` archive, both plain (`numpy.savez`) and compressed
+/// (`numpy.savez_compressed`) ones. Names are returned without `.npy` extension.
+pub fn read_npz<P: AsRef<Path>>(path: P) -> Result<Vec<(String, NpyArray)>, BinaryError> {
+    read_npz_from(BufReader::new(File::open(path)?))
+}
+
+pub fn read_npz_from<R: Read>(mut reader: R) -> Result<Vec<(String, NpyArray)>, BinaryError> {
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+    let invalid = |reason: &str| BinaryError::InvalidFormat(reason.to_string());
+    let u16_at = |i: usize| bytes.get(i..i + 2).map(|b| u16::from_le_bytes([b[0], b[1]]) as usize);
+    let u32_at = |i: usize| bytes.get(i..i + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+
+    let end = (0..bytes.len().saturating_sub(21)).rev()
+        .find(|&i| u32_at(i) == Some(ZIP_END_OF_CENTRAL_DIRECTORY))
+        .ok_or_else(|| invalid("missing zip end of central directory"))?;
+    let entries = u16_at(end + 10).ok_or_else(|| invalid("truncated zip directory"))?;
+    let mut pos = u32_at(end + 16).ok_or_else(|| invalid("truncated zip directory"))? as usize;
+    let mut arrays = vec![];
+
+    for _ in 0..entries {
+        if u32_at(pos) != Some(ZIP_CENTRAL_HEADER) {
+            return Err(invalid("corrupted zip central directory"));
+        }
+        let field = |offset: usize| u16_at(pos + offset).ok_or_else(|| invalid("truncated zip directory"));
+        let method = field(10)?;
+        let crc = u32_at(pos + 16).ok_or_else(|| invalid("truncated zip directory"))?;
+        let compressed_size = u32_at(pos + 20).ok_or_else(|| invalid("truncated zip directory"))? as usize;
+        let (name_len, extra_len, comment_len) = (field(28)?, field(30)?, field(32)?);
+        let local = u32_at(pos + 42).ok_or_else(|| invalid("truncated zip directory"))? as usize;
+        let name = bytes.get(pos + 46..pos + 46 + name_len).ok_or_else(|| invalid("truncated zip directory"))?;
+        let name = String::from_utf8_lossy(name).to_string();
+        pos += 46 + name_len + extra_len + comment_len;
+
+        if u32_at(local) != Some(ZIP_LOCAL_HEADER) {
+            return Err(invalid("corrupted zip local header"));
+        }
+        let data_start = local + 30 + u16_at(local + 26).unwrap_or(0) + u16_at(local + 28).unwrap_or(0);
+        let data = bytes.get(data_start..data_start + compressed_size).ok_or_else(|| invalid("truncated zip entry"))?;
+        let content = match method {
+            0 => data.to_vec(),
+            8 => inflate(data).map_err(BinaryError::InvalidFormat)?,
+            m => return Err(BinaryError::UnsupportedType(format!("zip compression method {}", m))),
+        };
+        if crc32(&content) != crc {
+            return Err(invalid(&format!("checksum mismatch in {}", name)));
+        }
+        let name = name.strip_suffix(".npy").unwrap_or(&name).to_string();
+        arrays.push((name, NpyArray::from_npy_bytes(&content)?));
+    }
+
+    Ok(arrays)
+}
+
+pub fn write_npz<P: AsRef<Path>>(path: P, arrays: &[(&str, &NpyArray)]) -> Result<(), BinaryError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_npz_to(&mut writer, arrays)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes arrays as uncompressed `.npz` archive, the same way as `numpy.savez` does.
+pub fn write_npz_to<W: Write>(mut writer: W, arrays: &[(&str, &NpyArray)]) -> Result<(), BinaryError> {
+    let mut archive = vec![];
+    let mut directory = vec![];
+
+    for (name, array) in arrays {
+        let name = format!("{}.npy", name);
+        let content = array.to_npy_bytes()?;
+        let crc = crc32(&content);
+        let offset = field::<u32>(archive.len(), "zip archive size")?;
+        let size = field::<u32>(content.len(), "npy file size")?;
+        let mut common = vec![];
+        common.extend_from_slice(&20u16.to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes());
+        common.extend_from_slice(&0u32.to_le_bytes());
+        common.extend_from_slice(&crc.to_le_bytes());
+        common.extend_from_slice(&size.to_le_bytes());
+        common.extend_from_slice(&size.to_le_bytes());
+        common.extend_from_slice(&field::<u16>(name.len(), "array name length")?.to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes());
+
+        archive.extend_from_slice(&ZIP_LOCAL_HEADER.to_le_bytes());
+        archive.extend_from_slice(&common);
+        archive.extend_from_slice(name.as_bytes());
+        archive.extend_from_slice(&content);
+
+        directory.extend_from_slice(&ZIP_CENTRAL_HEADER.to_le_bytes());
+        directory.extend_from_slice(&20u16.to_le_bytes());
+        directory.extend_from_slice(&common);
+        directory.extend_from_slice(&[0; 10]);
+        directory.extend_from_slice(&offset.to_le_bytes());
+        directory.extend_from_slice(name.as_bytes());
+    }
+
+    let directory_offset = field::<u32>(archive.len(), "zip archive size")?;
+    let count = field::<u16>(arrays.len(), "number of arrays")?;
+    archive.extend_from_slice(&directory);
+    archive.extend_from_slice(&ZIP_END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+    archive.extend_from_slice(&[0; 4]);
+    archive.extend_from_slice(&count.to_le_bytes());
+    archive.extend_from_slice(&count.to_le_bytes());
+    archive.extend_from_slice(&field::<u32>(directory.len(), "zip directory size")?.to_le_bytes());
+    archive.extend_from_slice(&directory_offset.to_le_bytes());
+    archive.extend_from_slice(&0u16.to_le_bytes());
+
+    writer.write_all(&archive)?;
+    Ok(())
+}
+
+/// Size or count stored in fixed width field of npy or zip header.
+fn field<T: TryFrom<usize>>(value: usize, what: &str) -> Result<T, BinaryError> {
+    T::try_from(value).map_err(|_| BinaryError::InvalidFormat(format!("{} {} is too large", what, value)))
+}
+
+/// Value of given key in header dictionary, e.g. `'<f8'` for `descr`.
+fn header_value<'a>(header: &'a str, key: &str) -> Result<&'a str, BinaryError> {
+    let missing = || BinaryError::InvalidFormat(format!("npy header misses '{}'", key));
+    let start = header.find(&format!("'{}'", key)).ok_or_else(missing)? + key.len() + 2;
+    let rest = header[start..].trim_start().strip_prefix(':').ok_or_else(missing)?.trim_start();
+    let end = if rest.starts_with('(') {
+        rest.find(')').map(|i| i + 1)
+    } else {
+        rest.find([',', '}'])
+    };
+    Ok(rest[..end.ok_or_else(missing)?].trim())
+}
+
+fn parse_shape(text: &str) -> Result<Vec<usize>, BinaryError> {
+    text.trim_matches(|c| c == '(' || c == ')')
+        .split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(|d| d.parse::<usize>().map_err(|_| BinaryError::InvalidFormat(format!("invalid shape {}", text))))
+        .collect()
+}
+
+fn parse_descr(descr: &str) -> Result<(RawSampleType, Endianness, bool), BinaryError> {
+    let unsupported = || BinaryError::UnsupportedType(descr.to_string());
+    let mut chars = descr.chars();
+    let endianness = match chars.next() {
+        Some('>') => Endianness::Big,
+        Some('<') | Some('|') | Some('=') => Endianness::Little,
+        _ => return Err(unsupported()),
+    };
+    let (sample_type, complex) = match chars.as_str() {
+        "i1" => (RawSampleType::I8, false),
+        "u1" => (RawSampleType::U8, false),
+        "i2" => (RawSampleType::I16, false),
+        "u2" => (RawSampleType::U16, false),
+        "i4" => (RawSampleType::I32, false),
+        "u4" => (RawSampleType::U32, false),
+        "i8" => (RawSampleType::I64, false),
+        "u8" => (RawSampleType::U64, false),
+        "f4" => (RawSampleType::F32, false),
+        "f8" => (RawSampleType::F64, false),
+        "c8" => (RawSampleType::F32, true),
+        "c16" => (RawSampleType::F64, true),
+        _ => return Err(unsupported()),
+    };
+    Ok((sample_type, endianness, complex))
+}
+
+fn descr(sample_type: RawSampleType, complex: bool) -> Result<&'static str, BinaryError> {
+    Ok(match (sample_type, complex) {
+        (RawSampleType::I8, false) => "|i1",
+        (RawSampleType::U8, false) => "|u1",
+        (RawSampleType::I16, false) => "<i2",
+        (RawSampleType::U16, false) => "<u2",
+        (RawSampleType::I32, false) => "<i4",
+        (RawSampleType::U32, false) => "<u4",
+        (RawSampleType::I64, false) => "<i8",
+        (RawSampleType::U64, false) => "<u8",
+        (RawSampleType::F32, false) => "<f4",
+        (RawSampleType::F64, false) => "<f8",
+        (RawSampleType::F32, true) => "<c8",
+        (RawSampleType::F64, true) => "<c16",
+        (t, true) => return Err(BinaryError::UnsupportedType(format!("complex {:?}", t))),
+    })
+}
+
+/// Reorders elements (each `width` values long) from column-major to row-major order.
+fn fortran_to_c_order(values: &[f64], shape: &[usize], width: usize) -> Vec<f64> {
+    let count = shape.iter().product::<usize>();
+    let mut result = vec![0.0; values.len()];
+    for (f_index, element) in values.chunks_exact(width).enumerate().take(count) {
+        let mut rest = f_index;
+        let mut c_index = 0;
+        let mut stride = 1;
+        let mut c_strides = vec![0; shape.len()];
+        for (i, dim) in shape.iter().enumerate().rev() {
+            c_strides[i] = stride;
+            stride *= dim;
+        }
+        for (i, dim) in shape.iter().enumerate() {
+            c_index += (rest % dim) * c_strides[i];
+            rest /= dim;
+        }
+        result[c_index * width..(c_index + 1) * width].copy_from_slice(element);
+    }
+    result
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFFFFFFu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB88320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(array: &NpyArray) -> NpyArray {
+        let mut bytes = vec![];
+        array.write_to(&mut bytes).unwrap();
+        assert_eq!((bytes.len() - array_data_len(array)) % 64, 0);
+        read_npy_from(bytes.as_slice()).unwrap()
+    }
+
+    fn array_data_len(array: &NpyArray) -> usize {
+        let width = match array.values() {
+            NpyValues::Real(_) => 1,
+            NpyValues::Complex(_) => 2,
+        };
+        array.shape().iter().try_fold(width * array.sample_type().size(), |len, dim| len.checked_mul(*dim)).unwrap()
+    }
+
+    #[test]
+    fn signal_shall_survive_npy_round_trip_with_timestamps() {
+        let signal = DescreteSignal::new_from_vec(vec![(0.1, 1.0 / 3.0), (0.25, -2.5), (0.4, 1e-300)]);
+        let array = round_trip(&NpyArray::from_signal(&signal));
+
+        assert_eq!(array.shape(), &[3, 2]);
+        assert_eq!(array.to_signal().unwrap(), signal);
+    }
+
+    #[test]
+    fn multichannel_signal_shall_be_stored_as_columns() {
+        let signal = MultichannelSignal::from_planar(vec![vec![1.0, 2.0, 3.0], vec![-1.0, -2.0, -3.0]], 10.0, 0.0);
+        let array = round_trip(&NpyArray::from_multichannel(&signal).set_sample_type(RawSampleType::I16));
+
+        assert_eq!(array.shape(), &[3, 2]);
+        assert_eq!(array.sample_type(), RawSampleType::I16);
+        assert_eq!(array.to_multichannel(10.0).unwrap(), signal);
+    }
+
+    #[test]
+    fn complex_values_shall_survive_round_trip() {
+        let values = vec![ComplexNumber::new(1.0, -1.0), ComplexNumber::new(0.5, 0.25)];
+        for t in [RawSampleType::F32, RawSampleType::F64] {
+            let array = round_trip(&NpyArray::from_complex(values.clone()).set_sample_type(t));
+            assert_eq!(array.values(), &NpyValues::Complex(values.clone()));
+            assert_eq!(array.to_complex_signal(2.0).unwrap().samples().collect::<Vec<_>>(), values);
+        }
+    }
+
+    #[test]
+    fn header_written_by_numpy_shall_be_parsed() {
+        let mut bytes = NPY_MAGIC.to_vec();
+        let header = "{'descr': '>i2', 'fortran_order': True, 'shape': (2, 3), }          \n";
+        bytes.extend_from_slice(&[1, 0]);
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        for v in [1i16, 4, 2, 5, 3, 6] {
+            bytes.extend_from_slice(&v.to_be_bytes());
+        }
+        let array = read_npy_from(bytes.as_slice()).unwrap();
+
+        assert_eq!(array.shape(), &[2, 3]);
+        assert_eq!(array.values(), &NpyValues::Real(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]));
+    }
+
+    #[test]
+    fn invalid_npy_data_shall_be_rejected() {
+        assert!(matches!(read_npy_from(&b"NUMPY"[..]), Err(BinaryError::InvalidFormat(_))));
+
+        let mut bytes = vec![];
+        NpyArray::from_values(vec![1.0]).write_to(&mut bytes).unwrap();
+        let descr = bytes.windows(3).position(|w| w == b"<f8").unwrap();
+        bytes[descr + 1] = b'U';
+        assert!(matches!(read_npy_from(bytes.as_slice()), Err(BinaryError::UnsupportedType(_))));
+
+        // element count overflows, so it shall not pass the length check
+        let mut header = "{'descr': '<f8', 'fortran_order': False, 'shape': (4294967296, 4294967296, 8), }".to_string();
+        header.push_str(&" ".repeat(117 - header.len()));
+        header.push('\n');
+        let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend_from_slice(&[0; 64]);
+        assert!(matches!(read_npy_from(bytes.as_slice()), Err(BinaryError::InvalidFormat(reason)) if reason.contains("too large")));
+    }
+
+    #[test]
+    fn arrays_shall_survive_npz_round_trip() {
+        let first = NpyArray::from_values(vec![1.0, 2.0]);
+        let second = NpyArray::from_complex(vec![ComplexNumber::new(0.0, 1.0)]);
+        let mut bytes = vec![];
+
+        write_npz_to(&mut bytes, &[("first", &first), ("second", &second)]).unwrap();
+        let arrays = read_npz_from(bytes.as_slice()).unwrap();
+        assert_eq!(arrays, vec![("first".to_string(), first), ("second".to_string(), second)]);
+    }
+
+    #[test]
+    fn values_not_fitting_into_header_fields_shall_be_rejected() {
+        let array = NpyArray{shape: vec![1; 25000], sample_type: RawSampleType::F64, values: NpyValues::Real(vec![0.0])};
+        assert!(matches!(array.write_to(vec![]), Err(BinaryError::InvalidFormat(reason)) if reason.contains("too large")));
+
+        let name = "x".repeat(70000);
+        let array = NpyArray::from_values(vec![1.0]);
+        assert!(matches!(write_npz_to(vec![], &[(&name, &array)]), Err(BinaryError::InvalidFormat(reason)) if reason.contains("too large")));
+    }
+
+    #[test]
+    fn compressed_npz_shall_be_read() {
+        // zipfile with ZIP_DEFLATED holding x.npy = np.arange(4, dtype='<i2')
+        let npz = [
+            0x50, 0x4B, 0x03, 0x04, 0x14, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x21, 0x00, 0xD2, 0x10,
+            0x36, 0x66, 0x4C, 0x00, 0x00, 0x00, 0x88, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x78, 0x2E,
+            0x6E, 0x70, 0x79, 0x9B, 0xEC, 0x17, 0xEA, 0x1B, 0x10, 0xC9, 0xC8, 0x50, 0xC6, 0x50, 0xAD, 0x9E,
+            0x92, 0x5A, 0x9C, 0x5C, 0xA4, 0x6E, 0xA5, 0xA0, 0x6E, 0x93, 0x69, 0xA4, 0xAE, 0xA3, 0xA0, 0x9E,
+            0x96, 0x5F, 0x54, 0x52, 0x94, 0x98, 0x17, 0x9F, 0x5F, 0x94, 0x92, 0x0A, 0x12, 0x77, 0x4B, 0xCC,
+            0x29, 0x4E, 0x05, 0x8A, 0x17, 0x67, 0x24, 0x16, 0xA4, 0x02, 0xF9, 0x1A, 0x26, 0x3A, 0x9A, 0x3A,
+            0x0A, 0xB5, 0x0A, 0x14, 0x00, 0x2E, 0x06, 0x06, 0x46, 0x06, 0x26, 0x06, 0x66, 0x06, 0x00, 0x50,
+            0x4B, 0x01, 0x02, 0x14, 0x03, 0x14, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x21, 0x00, 0xD2,
+            0x10, 0x36, 0x66, 0x4C, 0x00, 0x00, 0x00, 0x88, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x01, 0x00, 0x00, 0x00, 0x00, 0x78, 0x2E, 0x6E,
+            0x70, 0x79, 0x50, 0x4B, 0x05, 0x06, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x33, 0x00,
+            0x00, 0x00, 0x6F, 0x00, 0x00, 0x00, 0x00, 0x00];
+        let arrays = read_npz_from(&npz[..]).unwrap();
+
+        assert_eq!(arrays[0].0, "x");
+        assert_eq!(arrays[0].1.sample_type(), RawSampleType::I16);
+        assert_eq!(arrays[0].1.values(), &NpyValues::Real(vec![0.0, 1.0, 2.0, 3.0]));
+    }
+
+    #[test]
+    fn crc32_shall_match_reference_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF43926);
+    }
+}
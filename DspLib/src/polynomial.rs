@@ -0,0 +1,308 @@
+use std::ops::Mul;
+
+use crate::ComplexNumber;
+
+/// Polynomial with complex coefficients given from the highest power, `c[0] x^n + ... + c[n]`.
+/// It is the same order as in numerator and denominator of transfer function written in
+/// positive powers of `z`, so roots of `b` and `a` are zeros and poles of filter.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Polynomial {
+    coefficients: Vec<ComplexNumber>,
+}
+
+impl Polynomial {
+    /// Leading zeros are removed, polynomial without coefficients is zero.
+    pub fn new(coefficients: &[ComplexNumber]) -> Self {
+        let start = coefficients.iter().position(|c| !is_zero(c)).unwrap_or(coefficients.len());
+        let mut coefficients = coefficients[start..].to_vec();
+        if coefficients.is_empty() {
+            coefficients.push(ComplexNumber::default());
+        }
+        Self{coefficients}
+    }
+
+    pub fn from_real(coefficients: &[f64]) -> Self {
+        Self::new(&coefficients.iter().map(|c| ComplexNumber::new(*c, 0.0)).collect::<Vec<ComplexNumber>>())
+    }
+
+    /// Monic polynomial with given roots.
+    pub fn from_roots(roots: &[ComplexNumber]) -> Self {
+        Self{coefficients: expand(roots)}
+    }
+
+    pub fn coefficients(&self) -> &[ComplexNumber] {
+        &self.coefficients
+    }
+
+    /// Degree of polynomial, zero polynomial has degree 0.
+    pub fn degree(&self) -> usize {
+        self.coefficients.len() - 1
+    }
+
+    /// True when all coefficients are real.
+    pub fn is_real(&self) -> bool {
+        self.coefficients.iter().all(|c| c.im() == 0.0)
+    }
+
+    /// Value at `x` (Horner's scheme).
+    pub fn evaluate(&self, x: &ComplexNumber) -> ComplexNumber {
+        evaluate(&self.coefficients, x).0
+    }
+
+    pub fn derivative(&self) -> Polynomial {
+        let degree = self.degree();
+        Self::new(&self.coefficients[..degree].iter().enumerate().map(|(i, c)| c * (degree - i) as f64).collect::<Vec<ComplexNumber>>())
+    }
+
+    /// All roots with multiplicity (Aberth-Ehrlich iteration). Complex roots of polynomial with
+    /// real coefficients are exact conjugate pairs. Multiple roots are found less accurately.
+    pub fn roots(&self) -> Vec<ComplexNumber> {
+        if self.is_real() {
+            return roots(&self.coefficients.iter().map(|c| c.re()).collect::<Vec<f64>>());
+        }
+        let end = self.coefficients.iter().rposition(|c| !is_zero(c)).map_or(0, |i| i + 1);
+        let c = &self.coefficients[..end];
+        let mut result = vec![ComplexNumber::default(); self.coefficients.len() - end];
+        match c.len() {
+            0 | 1 => {}
+            2 => result.push(-&(&c[1] / &c[0])),
+            _ => result.extend(aberth(c)),
+        }
+        result
+    }
+}
+
+impl Mul<&Polynomial> for &Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, rhs: &Polynomial) -> Polynomial {
+        let mut coefficients = vec![ComplexNumber::default(); self.coefficients.len() + rhs.coefficients.len() - 1];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in rhs.coefficients.iter().enumerate() {
+                coefficients[i + j] = &coefficients[i + j] + &(a * b);
+            }
+        }
+        Polynomial::new(&coefficients)
+    }
+}
+
+/// Roots of polynomial with real coefficients given from the highest power. Leading zeros are
+/// ignored, complex roots are returned as exact conjugate pairs.
+pub(crate) fn roots(coefficients: &[f64]) -> Vec<ComplexNumber> {
+    let start = coefficients.iter().position(|c| *c != 0.0).unwrap_or(coefficients.len());
+    let end = coefficients.iter().rposition(|c| *c != 0.0).map_or(start, |i| i + 1);
+    let c = &coefficients[start..end];
+    let mut result = vec![ComplexNumber::default(); coefficients.len() - end];
+    match c.len() {
+        0 | 1 => {}
+        2 => result.push(ComplexNumber::new(-c[1] / c[0], 0.0)),
+        3 => result.extend(quadratic_roots(c[0], c[1], c[2])),
+        _ => result.extend(conjugate_pairs(aberth(&c.iter().map(|c| ComplexNumber::new(*c, 0.0)).collect::<Vec<ComplexNumber>>()))),
+    }
+    result
+}
+
+/// Coefficients (from the highest power) of monic polynomial with given roots. Roots shall
+/// come in conjugate pairs, imaginary parts of coefficients are dropped.
+pub(crate) fn from_roots(roots: &[ComplexNumber]) -> Vec<f64> {
+    expand(roots).iter().map(|c| c.re()).collect()
+}
+
+fn expand(roots: &[ComplexNumber]) -> Vec<ComplexNumber> {
+    let mut coefficients = vec![ComplexNumber::new(1.0, 0.0)];
+    for root in roots {
+        coefficients.push(ComplexNumber::default());
+        for i in (1..coefficients.len()).rev() {
+            coefficients[i] = &coefficients[i] - &(root * &coefficients[i - 1]);
+        }
+    }
+    coefficients
+}
+
+fn is_zero(c: &ComplexNumber) -> bool {
+    c.re() == 0.0 && c.im() == 0.0
+}
+
+fn quadratic_roots(a: f64, b: f64, c: f64) -> [ComplexNumber; 2] {
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant >= 0.0 {
+        // avoids cancellation of the smaller root
+        let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+        let other = if q == 0.0 { 0.0 } else { c / q };
+        [ComplexNumber::new(q / a, 0.0), ComplexNumber::new(other, 0.0)]
+    } else {
+        let (re, im) = (-b / (2.0 * a), (-discriminant).sqrt() / (2.0 * a).abs());
+        [ComplexNumber::new(re, im), ComplexNumber::new(re, -im)]
+    }
+}
+
+/// Value of polynomial and its derivative at `z` (Horner's scheme).
+fn evaluate(coefficients: &[ComplexNumber], z: &ComplexNumber) -> (ComplexNumber, ComplexNumber) {
+    let mut value = ComplexNumber::default();
+    let mut derivative = ComplexNumber::default();
+    for c in coefficients {
+        derivative = &(&derivative * z) + &value;
+        value = &(&value * z) + c;
+    }
+    (value, derivative)
+}
+
+/// Aberth-Ehrlich simultaneous iteration, all roots converge cubically (simple roots).
+fn aberth(coefficients: &[ComplexNumber]) -> Vec<ComplexNumber> {
+    let degree = coefficients.len() - 1;
+    let radius = (coefficients[degree].module() / coefficients[0].module()).powf(1.0 / degree as f64).max(1e-3);
+    let mut z = (0..degree)
+        .map(|k| ComplexNumber::from_polar(radius, 2.0 * std::f64::consts::PI * k as f64 / degree as f64 + 0.4))
+        .collect::<Vec<ComplexNumber>>();
+
+    for _ in 0..500 {
+        let mut largest_step: f64 = 0.0;
+        for i in 0..degree {
+            let (value, derivative) = evaluate(coefficients, &z[i]);
+            if value.module() == 0.0 {
+                continue;
+            }
+            let ratio = &value / &derivative;
+            let mut sum = ComplexNumber::default();
+            for (j, zj) in z.iter().enumerate() {
+                if j != i {
+                    sum = &sum + &(&ComplexNumber::new(1.0, 0.0) / &(&z[i] - zj));
+                }
+            }
+            let step = &ratio / &(&ComplexNumber::new(1.0, 0.0) - &(&ratio * &sum));
+            if step.re().is_finite() && step.im().is_finite() {
+                z[i] = &z[i] - &step;
+                largest_step = largest_step.max(step.module() / z[i].module().max(1.0));
+            }
+        }
+        if largest_step < 1e-15 {
+            break;
+        }
+    }
+    z
+}
+
+/// Snaps nearly real roots to real axis and makes complex roots exact conjugate pairs.
+fn conjugate_pairs(roots: Vec<ComplexNumber>) -> Vec<ComplexNumber> {
+    let tolerance = |z: &ComplexNumber| 1e-10 * z.module().max(1.0);
+    let mut result = vec![];
+    let mut lower = vec![];
+    for z in roots {
+        if z.im().abs() <= tolerance(&z) {
+            result.push(ComplexNumber::new(z.re(), 0.0));
+        } else if z.im() > 0.0 {
+            result.push(z);
+        } else {
+            lower.push(z);
+        }
+    }
+    let upper = result.iter().filter(|z| z.im() > 0.0).copied().collect::<Vec<ComplexNumber>>();
+    result.retain(|z| z.im() == 0.0);
+    for z in upper {
+        let nearest = (0..lower.len()).min_by(|&i, &j| {
+            (&lower[i].conj() - &z).module().total_cmp(&(&lower[j].conj() - &z).module())
+        });
+        let z = match nearest {
+            Some(i) => {
+                let conjugate = lower.swap_remove(i).conj();
+                ComplexNumber::new((z.re() + conjugate.re()) / 2.0, (z.im() + conjugate.im()) / 2.0)
+            }
+            None => z,
+        };
+        result.push(z);
+        result.push(z.conj());
+    }
+    // unmatched roots below real axis (only with inaccurate iteration) are kept as they are
+    result.extend(lower);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(mut roots: Vec<ComplexNumber>) -> Vec<ComplexNumber> {
+        roots.sort_by(|a, b| a.re().total_cmp(&b.re()).then(a.im().total_cmp(&b.im())));
+        roots
+    }
+
+    fn assert_roots(actual: Vec<ComplexNumber>, expected: &[ComplexNumber], tolerance: f64) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (a, e) in sorted(actual).iter().zip(sorted(expected.to_vec())) {
+            assert!((a - &e).module() < tolerance, "{:?} {:?}", a, e);
+        }
+    }
+
+    #[test]
+    fn roots_shall_be_found_for_polynomials_of_any_degree() {
+        let expected = [ComplexNumber::new(0.5, 0.0), ComplexNumber::new(-2.0, 0.0),
+                        ComplexNumber::new(0.3, 0.9), ComplexNumber::new(0.3, -0.9),
+                        ComplexNumber::new(-0.7, 0.2), ComplexNumber::new(-0.7, -0.2),
+                        ComplexNumber::new(1.5, 0.0)];
+        for degree in 1..=expected.len() {
+            // complex roots shall not be split from their conjugates
+            if degree < expected.len() && expected[degree - 1].im() > 0.0 {
+                continue;
+            }
+            let coefficients = from_roots(&expected[..degree]).iter().map(|c| 3.0 * c).collect::<Vec<f64>>();
+            assert_roots(roots(&coefficients), &expected[..degree], 1e-10);
+        }
+    }
+
+    #[test]
+    fn zero_coefficients_shall_give_roots_at_origin_and_lower_degree() {
+        let roots = roots(&[0.0, 2.0, -2.0, 0.0]);
+
+        assert_roots(roots.clone(), &[ComplexNumber::new(1.0, 0.0), ComplexNumber::default()], 1e-15);
+        assert_eq!(roots.iter().filter(|z| z.im() != 0.0).count(), 0);
+    }
+
+    #[test]
+    fn complex_roots_shall_be_exact_conjugates() {
+        let roots = roots(&[1.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+        let upper = roots.iter().filter(|z| z.im() > 0.0).collect::<Vec<_>>();
+
+        assert_eq!(upper.len(), 2);
+        for z in upper {
+            assert!(roots.contains(&z.conj()));
+            assert!((z.module() - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn polynomial_shall_be_expanded_from_roots() {
+        let coefficients = from_roots(&[ComplexNumber::new(1.0, 1.0), ComplexNumber::new(1.0, -1.0), ComplexNumber::new(-3.0, 0.0)]);
+
+        assert_eq!(coefficients, vec![1.0, 1.0, -4.0, 6.0]);
+    }
+
+    #[test]
+    fn product_of_polynomials_shall_have_roots_of_both() {
+        let first = Polynomial::from_roots(&[ComplexNumber::new(1.0, 2.0), ComplexNumber::new(-0.5, 0.1)]);
+        let second = Polynomial::new(&[ComplexNumber::default(), ComplexNumber::new(2.0, 0.0), ComplexNumber::new(0.0, -6.0)]);
+        let product = &first * &second;
+
+        assert_eq!(second.degree(), 1);
+        assert_eq!(product.degree(), 3);
+        assert!(!product.is_real());
+        assert_roots(product.roots(), &[ComplexNumber::new(1.0, 2.0), ComplexNumber::new(-0.5, 0.1), ComplexNumber::new(0.0, 3.0)], 1e-12);
+        for root in product.roots() {
+            assert!(product.evaluate(&root).module() < 1e-12);
+        }
+        let x = ComplexNumber::new(0.3, -0.7);
+        assert!((&product.evaluate(&x) - &(&first.evaluate(&x) * &second.evaluate(&x))).module() < 1e-12);
+    }
+
+    #[test]
+    fn real_polynomial_shall_give_conjugate_roots() {
+        let polynomial = Polynomial::from_real(&[0.0, 2.0, 0.0, 8.0]);
+
+        assert!(polynomial.is_real());
+        assert_eq!(polynomial.degree(), 2);
+        assert_eq!(polynomial.roots(), vec![ComplexNumber::new(0.0, 2.0), ComplexNumber::new(0.0, -2.0)]);
+        assert_eq!(polynomial.derivative(), Polynomial::from_real(&[4.0, 0.0]));
+        assert_eq!(Polynomial::from_real(&[5.0]).derivative(), Polynomial::new(&[]));
+        assert_eq!(Polynomial::new(&[]).roots(), vec![]);
+    }
+}
@@ -0,0 +1,597 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+use crate::{DescreteSignal, StreamFilter};
+
+/// Batch filtering (`filter`) of [`RankFilter`], [`WeightedMedianFilter`] and [`HampelFilter`]
+/// centers window on every sample, so output is not delayed, and does not change state of the
+/// filter. This is how the window is completed near signal edges.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WindowBoundary {
+    /// Window is truncated to existing samples, default.
+    #[default]
+    Shrink,
+    /// Edge sample is repeated.
+    Nearest,
+    /// Mirror reflection without repeating the edge sample.
+    Reflect,
+    /// Missing samples have given value.
+    Constant(f64),
+}
+
+const LOW: usize = 0;
+const HIGH: usize = 1;
+
+/// Order statistics of sliding window kept in two indexed binary heaps, max-heap with
+/// the smallest samples and min-heap with the rest. Samples leave in the order they came and
+/// every operation costs O(log N).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct OrderStatistics {
+    /// Value and id of sample.
+    heaps: [Vec<(f64, usize)>; 2],
+    /// Heap and position in it for every sample in window, the oldest first.
+    slots: VecDeque<(usize, usize)>,
+    /// Id of the oldest sample.
+    first: usize,
+}
+
+impl OrderStatistics {
+    fn new() -> Self {
+        Self{heaps: [vec![], vec![]], slots: VecDeque::new(), first: 0}
+    }
+
+    fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    fn insert(&mut self, x: f64) {
+        let item = (x, self.first + self.slots.len());
+        self.slots.push_back((LOW, 0));
+        let side = match self.heaps[LOW].first() {
+            Some(top) if compare(&item, top) == Ordering::Less => LOW,
+            _ => HIGH,
+        };
+        self.push(side, item);
+    }
+
+    /// Panics when window is empty.
+    fn remove_oldest(&mut self) {
+        let (side, position) = self.slots[0];
+        self.remove(side, position);
+        self.slots.pop_front();
+        self.first += 1;
+    }
+
+    /// Sample of given rank (0 is the smallest). Panics when rank is out of window.
+    fn select(&mut self, rank: usize) -> f64 {
+        assert!(rank < self.len(), "rank shall be smaller than window length");
+        while self.heaps[LOW].len() > rank + 1 {
+            let item = self.remove(LOW, 0);
+            self.push(HIGH, item);
+        }
+        while self.heaps[LOW].len() < rank + 1 {
+            let item = self.remove(HIGH, 0);
+            self.push(LOW, item);
+        }
+        self.heaps[LOW][0].0
+    }
+
+    fn above(side: usize, a: &(f64, usize), b: &(f64, usize)) -> bool {
+        compare(a, b) == if side == LOW { Ordering::Greater } else { Ordering::Less }
+    }
+
+    fn swap(&mut self, side: usize, i: usize, j: usize) {
+        self.heaps[side].swap(i, j);
+        for k in [i, j] {
+            let id = self.heaps[side][k].1;
+            self.slots[id - self.first] = (side, k);
+        }
+    }
+
+    fn push(&mut self, side: usize, item: (f64, usize)) {
+        self.heaps[side].push(item);
+        let position = self.heaps[side].len() - 1;
+        self.slots[item.1 - self.first] = (side, position);
+        self.sift_up(side, position);
+    }
+
+    fn remove(&mut self, side: usize, position: usize) -> (f64, usize) {
+        let last = self.heaps[side].len() - 1;
+        self.swap(side, position, last);
+        let item = self.heaps[side].pop().unwrap();
+        if position < last {
+            self.sift_up(side, position);
+            self.sift_down(side, position);
+        }
+        item
+    }
+
+    fn sift_up(&mut self, side: usize, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if !Self::above(side, &self.heaps[side][i], &self.heaps[side][parent]) {
+                break;
+            }
+            self.swap(side, i, parent);
+            i = parent;
+        }
+    }
+
+    fn sift_down(&mut self, side: usize, mut i: usize) {
+        loop {
+            let mut top = i;
+            for child in [2 * i + 1, 2 * i + 2] {
+                if child < self.heaps[side].len() && Self::above(side, &self.heaps[side][child], &self.heaps[side][top]) {
+                    top = child;
+                }
+            }
+            if top == i {
+                break;
+            }
+            self.swap(side, i, top);
+            i = top;
+        }
+    }
+}
+
+/// Samples ordered by value, equal values by id.
+fn compare(a: &(f64, usize), b: &(f64, usize)) -> Ordering {
+    a.0.total_cmp(&b.0).then(a.1.cmp(&b.1))
+}
+
+/// Signal completed at edges, so window of `length` centered on every sample fits into it,
+/// and index of the first sample.
+pub(crate) fn extend(values: &[f64], length: usize, boundary: WindowBoundary) -> (Vec<f64>, usize) {
+    let half = length / 2;
+    let n = values.len() as isize;
+    let sample = |i: isize| match boundary {
+        WindowBoundary::Nearest => values[i.clamp(0, n - 1) as usize],
+        WindowBoundary::Reflect => {
+            let period = 2 * (n - 1);
+            let k = if period == 0 { 0 } else { i.rem_euclid(period) };
+            values[if k >= n { period - k } else { k } as usize]
+        }
+        WindowBoundary::Constant(value) => if (0..n).contains(&i) { values[i as usize] } else { value },
+        WindowBoundary::Shrink => values[i as usize],
+    };
+    match boundary {
+        _ if values.is_empty() => (vec![], 0),
+        WindowBoundary::Shrink => (values.to_vec(), 0),
+        _ => ((-(half as isize)..n + half as isize).map(sample).collect(), half),
+    }
+}
+
+/// Bounds (inclusive) of window centered on sample `i` in extended signal.
+fn window(i: usize, offset: usize, length: usize, extended: usize) -> (usize, usize) {
+    let half = length / 2;
+    ((i + offset).saturating_sub(half), (i + offset + half).min(extended - 1))
+}
+
+/// Rank of given quantile among `n` samples, the nearest one without interpolation.
+fn rank(quantile: f64, n: usize) -> usize {
+    (quantile * (n - 1) as f64).round() as usize
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    let middle = (values.len() - 1) / 2;
+    *values.select_nth_unstable_by(middle, f64::total_cmp).1
+}
+
+/// Rank-order filter, output is sample of given quantile (the nearest rank, no interpolation)
+/// of sliding window. Quantile 0.5 gives median filter, which removes impulsive noise without
+/// smearing edges. Every sample costs O(log N).
+///
+/// Streaming uses the newest `length` samples (delay `(length - 1) / 2` samples) and samples
+/// received so far until the window is filled.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RankFilter {
+    length: usize,
+    quantile: f64,
+    boundary: WindowBoundary,
+    statistics: OrderStatistics,
+}
+
+impl RankFilter {
+    /// Panics when length is zero or quantile is not between 0 and 1.
+    pub fn new(length: usize, quantile: f64) -> Self {
+        assert!(length > 0, "window shall have at least one sample");
+        assert!((0.0..=1.0).contains(&quantile), "quantile shall be between 0 and 1");
+        Self{length, quantile, boundary: WindowBoundary::default(), statistics: OrderStatistics::new()}
+    }
+
+    pub fn median(length: usize) -> Self {
+        Self::new(length, 0.5)
+    }
+
+    pub fn set_boundary(mut self, boundary: WindowBoundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    pub fn quantile(&self) -> f64 {
+        self.quantile
+    }
+
+    /// Panics when length is even.
+    pub fn filter(&self, signal: &DescreteSignal, output: &mut DescreteSignal) {
+        assert!(self.length % 2 == 1, "centered window shall have odd length");
+        output.set_metadata(signal.metadata().clone());
+        let (extended, offset) = extend(&signal.values().collect::<Vec<f64>>(), self.length, self.boundary);
+        let mut statistics = OrderStatistics::new();
+        let (mut start, mut end) = (0, 0);
+        for (i, t) in signal.times().enumerate() {
+            let (low, high) = window(i, offset, self.length, extended.len());
+            while end <= high {
+                statistics.insert(extended[end]);
+                end += 1;
+            }
+            while start < low {
+                statistics.remove_oldest();
+                start += 1;
+            }
+            output.push(t, statistics.select(rank(self.quantile, statistics.len())));
+        }
+    }
+}
+
+impl StreamFilter for RankFilter {
+    fn process_sample(&mut self, x: f64) -> f64 {
+        self.statistics.insert(x);
+        if self.statistics.len() > self.length {
+            self.statistics.remove_oldest();
+        }
+        let rank = rank(self.quantile, self.statistics.len());
+        self.statistics.select(rank)
+    }
+
+    fn reset(&mut self) {
+        self.statistics.clear();
+    }
+}
+
+/// Median of samples of sliding window counted with weights, e.g. center weighted median
+/// that keeps more details than plain median. `weights[0]` belongs to the oldest sample of window.
+/// Every sample costs O(N).
+///
+/// Streaming uses the newest `weights.len()` samples and until the window is filled samples
+/// received so far with the last weights.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WeightedMedianFilter {
+    weights: Vec<f64>,
+    boundary: WindowBoundary,
+    /// Window in order of arrival and the same samples (value and id) sorted by value.
+    window: VecDeque<f64>,
+    sorted: Vec<(f64, usize)>,
+    count: usize,
+}
+
+impl WeightedMedianFilter {
+    /// Panics when there are no weights or some is negative or all are zero.
+    pub fn new(weights: &[f64]) -> Self {
+        assert!(weights.iter().all(|w| *w >= 0.0) && weights.iter().any(|w| *w > 0.0),
+                "weights shall not be negative and at least one shall be positive");
+        Self{weights: weights.to_vec(), boundary: WindowBoundary::default(), window: VecDeque::new(), sorted: vec![], count: 0}
+    }
+
+    pub fn set_boundary(mut self, boundary: WindowBoundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// Panics when number of weights is even.
+    pub fn filter(&self, signal: &DescreteSignal, output: &mut DescreteSignal) {
+        let length = self.weights.len();
+        assert!(length % 2 == 1, "centered window shall have odd length");
+        output.set_metadata(signal.metadata().clone());
+        let (extended, offset) = extend(&signal.values().collect::<Vec<f64>>(), length, self.boundary);
+        let mut sorted = vec![];
+        let (mut start, mut end) = (0, 0);
+        for (i, t) in signal.times().enumerate() {
+            let (low, high) = window(i, offset, length, extended.len());
+            while end <= high {
+                insert_sorted(&mut sorted, (extended[end], end));
+                end += 1;
+            }
+            while start < low {
+                remove_sorted(&mut sorted, (extended[start], start));
+                start += 1;
+            }
+            // weights are aligned with center of window, also for truncated one
+            let center = i + offset;
+            output.push(t, weighted_median(&sorted, |id| self.weights[id + length / 2 - center]));
+        }
+    }
+}
+
+impl StreamFilter for WeightedMedianFilter {
+    fn process_sample(&mut self, x: f64) -> f64 {
+        insert_sorted(&mut self.sorted, (x, self.count));
+        self.window.push_back(x);
+        self.count += 1;
+        if self.window.len() > self.weights.len() {
+            let old = self.window.pop_front().unwrap();
+            remove_sorted(&mut self.sorted, (old, self.count - self.weights.len() - 1));
+        }
+        let newest = self.count - 1;
+        let length = self.weights.len();
+        weighted_median(&self.sorted, |id| self.weights[length - 1 - (newest - id)])
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.sorted.clear();
+        self.count = 0;
+    }
+}
+
+fn insert_sorted(sorted: &mut Vec<(f64, usize)>, item: (f64, usize)) {
+    let position = sorted.partition_point(|x| compare(x, &item) == Ordering::Less);
+    sorted.insert(position, item);
+}
+
+fn remove_sorted(sorted: &mut Vec<(f64, usize)>, item: (f64, usize)) {
+    let position = sorted.partition_point(|x| compare(x, &item) == Ordering::Less);
+    sorted.remove(position);
+}
+
+/// The smallest sample at which cumulative weight reaches half of total weight.
+fn weighted_median(sorted: &[(f64, usize)], weight: impl Fn(usize) -> f64) -> f64 {
+    let total = sorted.iter().map(|(_, id)| weight(*id)).sum::<f64>();
+    let mut sum = 0.0;
+    for (x, id) in sorted {
+        sum += weight(*id);
+        if sum >= total / 2.0 && sum > 0.0 {
+            return *x;
+        }
+    }
+    // all weights of truncated window are zero, plain median is used then
+    sorted[(sorted.len() - 1) / 2].0
+}
+
+/// Hampel outlier filter. Sample in the middle of window is replaced by median of window when
+/// it differs from the median by more than `threshold` scaled median absolute deviations
+/// (MAD multiplied by 1.4826, estimate of standard deviation for Gaussian noise). Other samples
+/// pass unchanged. Every sample costs O(N).
+///
+/// Window has `2 * half_width + 1` samples. Streaming checks the middle of the newest samples,
+/// so output is delayed by `half_width` samples (middle of samples received so far until the
+/// window is filled).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HampelFilter {
+    half_width: usize,
+    threshold: f64,
+    boundary: WindowBoundary,
+    window: VecDeque<f64>,
+}
+
+impl HampelFilter {
+    /// Threshold is 3 by default.
+    pub fn new(half_width: usize) -> Self {
+        Self{half_width, threshold: 3.0, boundary: WindowBoundary::default(), window: VecDeque::new()}
+    }
+
+    /// Panics when threshold is negative.
+    pub fn set_threshold(mut self, threshold: f64) -> Self {
+        assert!(threshold >= 0.0, "threshold shall not be negative");
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn set_boundary(mut self, boundary: WindowBoundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    pub fn half_width(&self) -> usize {
+        self.half_width
+    }
+
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    pub fn filter(&self, signal: &DescreteSignal, output: &mut DescreteSignal) {
+        output.set_metadata(signal.metadata().clone());
+        let length = 2 * self.half_width + 1;
+        let (extended, offset) = extend(&signal.values().collect::<Vec<f64>>(), length, self.boundary);
+        for (i, (t, x)) in signal.iter().enumerate() {
+            let (low, high) = window(i, offset, length, extended.len());
+            output.push(*t, self.check(*x, extended[low..=high].to_vec()));
+        }
+    }
+
+    fn check(&self, x: f64, mut window: Vec<f64>) -> f64 {
+        let median = median(&mut window);
+        for w in window.iter_mut() {
+            *w = (*w - median).abs();
+        }
+        let deviation = 1.4826 * self::median(&mut window);
+        if (x - median).abs() > self.threshold * deviation { median } else { x }
+    }
+}
+
+impl StreamFilter for HampelFilter {
+    fn process_sample(&mut self, x: f64) -> f64 {
+        self.window.push_back(x);
+        if self.window.len() > 2 * self.half_width + 1 {
+            self.window.pop_front();
+        }
+        let center = self.window[(self.window.len() - 1) / 2];
+        self.check(center, self.window.iter().copied().collect())
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input() -> Vec<f64> {
+        // pseudo random with many repeated values
+        (0..400).map(|i| ((i * 7919 + 13) % 61) as f64 / 4.0 - ((i * 31) % 7) as f64).collect()
+    }
+
+    fn signal(values: &[f64]) -> DescreteSignal {
+        DescreteSignal::new_from_vec(values.iter().enumerate().map(|(i, x)| (0.1 * i as f64, *x)).collect())
+    }
+
+    fn quantile_of(window: &[f64], quantile: f64) -> f64 {
+        let mut sorted = window.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        sorted[rank(quantile, sorted.len())]
+    }
+
+    #[test]
+    fn streaming_rank_filter_shall_match_sorted_window() {
+        let input = input();
+        for (length, quantile) in [(1, 0.5), (4, 0.5), (7, 0.5), (10, 0.0), (15, 0.9), (33, 1.0), (50, 0.25)] {
+            let mut filter = RankFilter::new(length, quantile);
+            for (i, x) in input.iter().enumerate() {
+                let window = &input[(i + 1).saturating_sub(length)..=i];
+                assert_eq!(filter.process_sample(*x), quantile_of(window, quantile), "{} {} {}", length, quantile, i);
+            }
+        }
+    }
+
+    #[test]
+    fn median_shall_remove_spikes_and_keep_edges() {
+        let mut values = (0..40).map(|i| if i < 20 { 0.0 } else { 5.0 }).collect::<Vec<f64>>();
+        values[7] = 100.0;
+        values[30] = -50.0;
+        let mut output = DescreteSignal::new();
+        RankFilter::median(5).filter(&signal(&values), &mut output);
+
+        for (i, y) in output.values().enumerate() {
+            assert_eq!(y, if i < 20 { 0.0 } else { 5.0 }, "{}", i);
+        }
+        assert_eq!(output.times().collect::<Vec<f64>>(), signal(&values).times().collect::<Vec<f64>>());
+    }
+
+    #[test]
+    fn boundaries_shall_complete_centered_window() {
+        let values = [3.0, 1.0, 4.0, 1.5, 5.0, 9.0];
+        let median = |boundary| {
+            let mut output = DescreteSignal::new();
+            RankFilter::median(5).set_boundary(boundary).filter(&signal(&values), &mut output);
+            output.values().collect::<Vec<f64>>()
+        };
+
+        // windows of the first sample: [3 1 4], [3 3 3 1 4], [4 1 3 1 4], [0 0 3 1 4]
+        assert_eq!(median(WindowBoundary::Shrink), vec![3.0, 3.0, 3.0, 4.0, 5.0, 5.0]);
+        assert_eq!(median(WindowBoundary::Nearest), vec![3.0, 3.0, 3.0, 4.0, 5.0, 9.0]);
+        assert_eq!(median(WindowBoundary::Reflect), vec![3.0, 1.5, 3.0, 4.0, 5.0, 5.0]);
+        assert_eq!(median(WindowBoundary::Constant(0.0)), vec![1.0, 1.5, 3.0, 4.0, 4.0, 1.5]);
+        assert!(extend(&[], 5, WindowBoundary::Nearest).0.is_empty());
+        assert_eq!(extend(&[1.0, 2.0], 7, WindowBoundary::Reflect).0, vec![2.0, 1.0, 2.0, 1.0, 2.0, 1.0, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn batch_rank_filter_shall_match_sorted_window() {
+        let input = input();
+        let mut output = DescreteSignal::new();
+        RankFilter::new(9, 0.75).filter(&signal(&input), &mut output);
+
+        for (i, y) in output.values().enumerate() {
+            let window = &input[i.saturating_sub(4)..(i + 5).min(input.len())];
+            assert_eq!(y, quantile_of(window, 0.75));
+        }
+    }
+
+    #[test]
+    fn weighted_median_shall_count_samples_by_weight() {
+        let input = input();
+        let mut plain = RankFilter::median(5);
+        let mut weighted = WeightedMedianFilter::new(&[1.0; 5]);
+        for x in &input {
+            let (expected, actual) = (plain.process_sample(*x), weighted.process_sample(*x));
+            if plain.statistics.len() % 2 == 1 {
+                assert_eq!(actual, expected);
+            }
+        }
+
+        // center sample with more than half of total weight wins
+        let mut center = WeightedMedianFilter::new(&[1.0, 1.0, 5.0, 1.0, 1.0]);
+        let mut output = vec![0.0; 5];
+        center.process_block(&[9.0, 9.0, 0.0, 8.0, 8.0], &mut output);
+        assert_eq!(output[4], 0.0);
+        let mut output = DescreteSignal::new();
+        center.filter(&signal(&[9.0, 9.0, 0.0, 8.0, 8.0]), &mut output);
+        assert_eq!(output.values().nth(2), Some(0.0));
+
+        let mut brute_force = WeightedMedianFilter::new(&[1.0, 2.0, 0.5, 4.0]);
+        for (i, x) in input.iter().enumerate() {
+            let start = (i + 1).saturating_sub(4);
+            let weights = &brute_force.weights()[4 - (i + 1 - start)..].to_vec();
+            let mut pairs = input[start..=i].iter().copied().zip(weights.iter().copied()).collect::<Vec<(f64, f64)>>();
+            pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let total = weights.iter().sum::<f64>();
+            let mut sum = 0.0;
+            let expected = pairs.iter().find(|(_, w)| { sum += w; sum >= total / 2.0 }).unwrap().0;
+            assert_eq!(brute_force.process_sample(*x), expected);
+        }
+    }
+
+    #[test]
+    fn hampel_filter_shall_replace_only_outliers() {
+        let mut values = (0..60).map(|i| (i as f64 * 0.3).sin() + 0.05 * ((i * 17) % 5) as f64).collect::<Vec<f64>>();
+        values[25] = 8.0;
+        values[40] = -6.0;
+        let mut output = DescreteSignal::new();
+        HampelFilter::new(4).filter(&signal(&values), &mut output);
+
+        for (i, (y, x)) in output.values().zip(&values).enumerate() {
+            if i == 25 || i == 40 {
+                assert!(y.abs() < 1.5, "{} {}", i, y);
+            } else if (4..56).contains(&i) {
+                // truncated windows at edges see trend of sine, only full ones are checked
+                assert_eq!(y, *x, "{}", i);
+            }
+        }
+
+        // streaming result is the same, only delayed by half width
+        let mut hampel = HampelFilter::new(4);
+        let mut streamed = vec![0.0; values.len()];
+        hampel.process_block(&values, &mut streamed);
+        for (s, y) in streamed[8..].iter().zip(output.values().skip(4)) {
+            assert_eq!(*s, y);
+        }
+    }
+
+    #[test]
+    fn streaming_shall_not_depend_on_block_size() {
+        let input = input();
+        let mut expected = DescreteSignal::new();
+        RankFilter::median(11).process_signal(&signal(&input), &mut expected);
+
+        let mut filter = RankFilter::median(11);
+        let mut output = vec![];
+        for block in input.chunks(13) {
+            let mut y = vec![0.0; block.len()];
+            filter.process_block(block, &mut y);
+            output.extend(y);
+        }
+        assert_eq!(output, expected.values().collect::<Vec<f64>>());
+        filter.reset();
+        assert_eq!(filter.process_sample(2.0), 2.0);
+    }
+}
@@ -0,0 +1,507 @@
+use std::f64::consts::PI;
+
+use crate::FirDesignError;
+
+/// Kind of filter designed with [`Remez`], it selects symmetry of coefficients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RemezFilterType {
+    /// Symmetric filter with piecewise constant response (low/high/band-pass, band-stop).
+    Bandpass,
+    /// Antisymmetric filter with response growing linearly with frequency. Desired value of
+    /// band is slope of response, error is weighted relatively to desired response.
+    Differentiator,
+    /// Antisymmetric filter with 90 degrees phase shift in pass bands.
+    Hilbert,
+}
+
+/// Equiripple (minimax) linear phase FIR design with Parks-McClellan (Remez exchange)
+/// algorithm. Bands are given in Hz as `(start, end)` pairs with one desired gain and
+/// one weight for each band, frequencies between bands are not constrained.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Remez {
+    number_of_taps: usize,
+    bands: Vec<(f64, f64)>,
+    desired: Vec<f64>,
+    weights: Vec<f64>,
+    sample_rate: f64,
+    filter_type: RemezFilterType,
+    grid_density: usize,
+    max_iterations: usize,
+}
+
+/// Result of [`Remez::design`] with diagnostics of the exchange algorithm.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RemezDesign {
+    coefficients: Vec<f64>,
+    deviation: f64,
+    iterations: usize,
+    converged: bool,
+    extremal_frequencies: Vec<f64>,
+}
+
+impl RemezDesign {
+    pub fn coefficients(&self) -> &[f64] {
+        &self.coefficients
+    }
+
+    pub fn into_coefficients(self) -> Vec<f64> {
+        self.coefficients
+    }
+
+    /// Maximal weighted error, ripple of band with weight `w` is `deviation / w`.
+    pub fn deviation(&self) -> f64 {
+        self.deviation
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// False when algorithm did not reach equiripple solution in allowed number of iterations,
+    /// coefficients of the last iteration are returned anyway.
+    pub fn converged(&self) -> bool {
+        self.converged
+    }
+
+    /// Frequencies (in Hz) where weighted error reaches its maximum.
+    pub fn extremal_frequencies(&self) -> &[f64] {
+        &self.extremal_frequencies
+    }
+}
+
+impl Remez {
+    pub fn new(number_of_taps: usize, bands: &[(f64, f64)], desired: &[f64], sample_rate: f64) -> Self {
+        Self{
+            number_of_taps,
+            bands: bands.to_vec(),
+            desired: desired.to_vec(),
+            weights: vec![1.0; bands.len()],
+            sample_rate,
+            filter_type: RemezFilterType::Bandpass,
+            grid_density: 16,
+            max_iterations: 40,
+        }
+    }
+
+    /// Weight of error in every band, by default all weights are 1.
+    pub fn set_weights(mut self, weights: &[f64]) -> Self {
+        self.weights = weights.to_vec();
+        self
+    }
+
+    pub fn set_filter_type(mut self, filter_type: RemezFilterType) -> Self {
+        self.filter_type = filter_type;
+        self
+    }
+
+    /// Number of grid points per extremal frequency, default is 16.
+    pub fn set_grid_density(mut self, density: usize) -> Self {
+        self.grid_density = density.max(1);
+        self
+    }
+
+    pub fn set_max_iterations(mut self, iterations: usize) -> Self {
+        self.max_iterations = iterations;
+        self
+    }
+
+    pub fn design(&self) -> Result<RemezDesign, FirDesignError> {
+        self.validate()?;
+        let taps = self.number_of_taps;
+        let odd = taps % 2 == 1;
+        let antisymmetric = self.filter_type != RemezFilterType::Bandpass;
+        let functions = if odd && !antisymmetric { taps / 2 + 1 } else { taps / 2 };
+        let q = |f: f64| match (antisymmetric, odd) {
+            (false, true) => 1.0,
+            (false, false) => (PI * f).cos(),
+            (true, true) => (2.0 * PI * f).sin(),
+            (true, false) => (PI * f).sin(),
+        };
+
+        let grid = self.grid(functions, |f| q(f).abs() > 1e-9);
+        if grid.points.len() <= functions {
+            return Err(FirDesignError::InvalidNumberOfTaps(taps));
+        }
+        // approximation of D/Q by polynomial in cos(2 pi f) with weight W*Q
+        let x = grid.points.iter().map(|f| (2.0 * PI * f).cos()).collect::<Vec<f64>>();
+        let desired = grid.points.iter().zip(&grid.desired).map(|(f, d)| d / q(*f)).collect::<Vec<f64>>();
+        let weights = grid.points.iter().zip(&grid.weights).map(|(f, w)| w * q(*f)).collect::<Vec<f64>>();
+
+        let len = x.len();
+        let mut extremals = (0..=functions).map(|k| k * (len - 1) / functions).collect::<Vec<usize>>();
+        let mut iterations = 0;
+        let mut converged = false;
+        let (mut nodes, mut values, mut delta);
+
+        loop {
+            iterations += 1;
+            let ext_x = extremals.iter().map(|&i| x[i]).collect::<Vec<f64>>();
+            let b = barycentric_weights(&ext_x);
+            let mut numerator = 0.0;
+            let mut denominator = 0.0;
+            for (k, &i) in extremals.iter().enumerate() {
+                let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+                numerator += b[k] * desired[i];
+                denominator += sign * b[k] / weights[i];
+            }
+            delta = numerator / denominator;
+            nodes = ext_x[..functions].to_vec();
+            values = extremals[..functions].iter().enumerate()
+                .map(|(k, &i)| {
+                    let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+                    desired[i] - sign * delta / weights[i]
+                })
+                .collect::<Vec<f64>>();
+            let node_weights = barycentric_weights(&nodes);
+
+            let error = (0..len)
+                .map(|i| weights[i] * (desired[i] - interpolate(&nodes, &node_weights, &values, x[i])))
+                .collect::<Vec<f64>>();
+            let new_extremals = find_extremals(&error, &grid.band_starts, delta.abs(), functions + 1);
+            let Some(new_extremals) = new_extremals else {
+                break;
+            };
+            let magnitudes = new_extremals.iter().map(|&i| error[i].abs());
+            let max = magnitudes.clone().fold(0.0, f64::max);
+            let min = magnitudes.fold(f64::INFINITY, f64::min);
+            let unchanged = new_extremals == extremals;
+            extremals = new_extremals;
+            if unchanged || max - min <= 1e-6 * max {
+                converged = true;
+                break;
+            }
+            if iterations >= self.max_iterations {
+                break;
+            }
+        }
+
+        let node_weights = barycentric_weights(&nodes);
+        let amplitude = |f: f64| interpolate(&nodes, &node_weights, &values, (2.0 * PI * f).cos()) * q(f);
+        let coefficients = linear_phase_coefficients(taps, antisymmetric, amplitude);
+        Ok(RemezDesign{
+            coefficients,
+            deviation: delta.abs(),
+            iterations,
+            converged,
+            extremal_frequencies: extremals.iter().map(|&i| grid.points[i] * self.sample_rate).collect(),
+        })
+    }
+
+    fn validate(&self) -> Result<(), FirDesignError> {
+        if !(self.sample_rate > 0.0 && self.sample_rate.is_finite()) {
+            return Err(FirDesignError::InvalidSampleRate(self.sample_rate));
+        }
+        if self.number_of_taps < 3 {
+            return Err(FirDesignError::InvalidNumberOfTaps(self.number_of_taps));
+        }
+        if self.bands.is_empty() {
+            return Err(FirDesignError::MissingBands);
+        }
+        for values in [&self.desired, &self.weights] {
+            if values.len() != self.bands.len() {
+                return Err(FirDesignError::BandCountMismatch{bands: self.bands.len(), values: values.len()});
+            }
+        }
+        if let Some(w) = self.weights.iter().find(|w| !(**w > 0.0 && w.is_finite())) {
+            return Err(FirDesignError::InvalidWeight(*w));
+        }
+        if let Some(d) = self.desired.iter().find(|d| !d.is_finite()) {
+            return Err(FirDesignError::InvalidGain(*d));
+        }
+        let nyquist = self.sample_rate / 2.0;
+        let mut previous_end = 0.0;
+        for (k, &(start, end)) in self.bands.iter().enumerate() {
+            if !(start >= previous_end && start < end && end <= nyquist) || (k > 0 && start == previous_end) {
+                return Err(FirDesignError::InvalidBand{start, end});
+            }
+            previous_end = end;
+        }
+        let (last_end, last_desired) = (self.bands[self.bands.len() - 1].1, self.desired[self.desired.len() - 1]);
+        if self.filter_type == RemezFilterType::Bandpass && self.number_of_taps.is_multiple_of(2)
+            && last_end == nyquist && last_desired != 0.0 {
+            return Err(FirDesignError::EvenNumberOfTaps(self.number_of_taps));
+        }
+        Ok(())
+    }
+
+    /// Dense grid of normalized frequencies in bands, with points where `allowed` is false removed.
+    fn grid(&self, functions: usize, allowed: impl Fn(f64) -> bool) -> Grid {
+        let step = 0.5 / (self.grid_density * functions) as f64;
+        let mut grid = Grid{points: vec![], desired: vec![], weights: vec![], band_starts: vec![]};
+        for (k, &(start, end)) in self.bands.iter().enumerate() {
+            let (start, end) = (start / self.sample_rate, end / self.sample_rate);
+            let count = ((end - start) / step).ceil().max(1.0) as usize;
+            grid.band_starts.push(grid.points.len());
+            for i in 0..=count {
+                let f = if i == count { end } else { start + (end - start) * i as f64 / count as f64 };
+                if !allowed(f) {
+                    continue;
+                }
+                let (desired, weight) = match self.filter_type {
+                    RemezFilterType::Differentiator if self.desired[k] != 0.0 => {
+                        (self.desired[k] * f * self.sample_rate, self.weights[k] / f)
+                    }
+                    RemezFilterType::Differentiator => (0.0, self.weights[k]),
+                    _ => (self.desired[k], self.weights[k]),
+                };
+                grid.points.push(f);
+                grid.desired.push(desired);
+                grid.weights.push(weight);
+            }
+        }
+        grid
+    }
+}
+
+struct Grid {
+    points: Vec<f64>,
+    desired: Vec<f64>,
+    weights: Vec<f64>,
+    /// Index of the first point of every band.
+    band_starts: Vec<usize>,
+}
+
+/// Estimates number of taps of equiripple low-pass (or high-pass) filter with Herrmann's formula.
+/// Ripple in pass band and attenuation of stop band are in dB, transition width in Hz.
+/// Weights of bands shall be set in inverse proportion to their deviations, see [`ripple_deviations`].
+pub fn remez_order(passband_ripple: f64, stopband_attenuation: f64, transition_width: f64, sample_rate: f64) -> usize {
+    let (dp, ds) = ripple_deviations(passband_ripple, stopband_attenuation);
+    let (lp, ls) = (dp.log10(), ds.log10());
+    let d_inf = ls * (5.309e-3 * lp * lp + 7.114e-2 * lp - 4.761e-1) - (2.66e-3 * lp * lp + 5.941e-1 * lp + 4.278e-1);
+    let f = 11.01217 + 0.51244 * (lp - ls);
+    let df = transition_width / sample_rate;
+    (d_inf / df - f * df + 1.0).ceil().max(3.0) as usize
+}
+
+/// Linear deviations of pass band and stop band from ripple and attenuation given in dB.
+pub fn ripple_deviations(passband_ripple: f64, stopband_attenuation: f64) -> (f64, f64) {
+    let rp = 10f64.powf(passband_ripple / 20.0);
+    ((rp - 1.0) / (rp + 1.0), 10f64.powf(-stopband_attenuation / 20.0))
+}
+
+/// Weights for barycentric Lagrange interpolation. Differences are doubled to avoid
+/// underflow of the product, common scale does not change the result.
+fn barycentric_weights(x: &[f64]) -> Vec<f64> {
+    (0..x.len()).map(|k| {
+        let product = x.iter().enumerate()
+            .filter(|(i, _)| *i != k)
+            .fold(1.0, |p, (_, xi)| p * 2.0 * (x[k] - xi));
+        1.0 / product
+    }).collect()
+}
+
+fn interpolate(nodes: &[f64], weights: &[f64], values: &[f64], x: f64) -> f64 {
+    let mut numerator = 0.0;
+    let mut denominator = 0.0;
+    for ((xk, wk), yk) in nodes.iter().zip(weights).zip(values) {
+        let d = x - xk;
+        if d.abs() < 1e-15 {
+            return *yk;
+        }
+        numerator += wk / d * yk;
+        denominator += wk / d;
+    }
+    numerator / denominator
+}
+
+/// New set of `count` alternating extremal points of error, `None` when there are too few of them.
+fn find_extremals(error: &[f64], band_starts: &[usize], delta: f64, count: usize) -> Option<Vec<usize>> {
+    let len = error.len();
+    let band_end = |i: usize| band_starts.iter().any(|s| *s == i + 1) || i + 1 == len;
+    let band_start = |i: usize| band_starts.contains(&i);
+    let threshold = delta * (1.0 - 1e-9);
+    let mut candidates: Vec<usize> = vec![];
+
+    for i in 0..len {
+        let e = error[i];
+        let left = if band_start(i) { None } else { Some(error[i - 1]) };
+        let right = if band_end(i) { None } else { Some(error[i + 1]) };
+        let is_peak = if e > 0.0 {
+            left.is_none_or(|l| e >= l) && right.is_none_or(|r| e > r)
+        } else {
+            left.is_none_or(|l| e <= l) && right.is_none_or(|r| e < r)
+        };
+        if !is_peak || e.abs() < threshold {
+            continue;
+        }
+        match candidates.last() {
+            Some(&last) if error[last].signum() == e.signum() => {
+                if e.abs() > error[last].abs() {
+                    *candidates.last_mut().unwrap() = i;
+                }
+            }
+            _ => candidates.push(i),
+        }
+    }
+
+    if candidates.len() < count {
+        return None;
+    }
+    while candidates.len() > count {
+        if error[candidates[0]].abs() < error[candidates[candidates.len() - 1]].abs() {
+            candidates.remove(0);
+        } else {
+            candidates.pop();
+        }
+    }
+    Some(candidates)
+}
+
+/// Coefficients of linear phase filter with given real amplitude response (frequency relative
+/// to sampling rate), calculated with inverse DFT of `taps` samples of the response.
+/// Response of antisymmetric filter is `j * A(f)` with linear phase removed.
+fn linear_phase_coefficients(taps: usize, antisymmetric: bool, amplitude: impl Fn(f64) -> f64) -> Vec<f64> {
+    let n = taps as f64;
+    let center = (n - 1.0) / 2.0;
+    let samples = (0..=taps / 2).map(|k| amplitude(k as f64 / n)).collect::<Vec<f64>>();
+    (0..taps).map(|i| {
+        let m = i as f64 - center;
+        let mut h = if antisymmetric { 0.0 } else { samples[0] };
+        for (k, a) in samples.iter().enumerate().take(taps.div_ceil(2)).skip(1) {
+            let theta = 2.0 * PI * k as f64 * m / n;
+            h += if antisymmetric { -2.0 * a * theta.sin() } else { 2.0 * a * theta.cos() };
+        }
+        if taps.is_multiple_of(2) {
+            let a = samples[taps / 2];
+            h += if antisymmetric { -a * (PI * m).sin() } else { a * (PI * m).cos() };
+        }
+        h / n
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::fir_amplitude;
+
+    fn band_error(fir: &[f64], band: (f64, f64), desired: impl Fn(f64) -> f64, antisymmetric: bool) -> f64 {
+        (0..=200).map(|i| {
+            let f = band.0 + (band.1 - band.0) * i as f64 / 200.0;
+            let amplitude = fir_amplitude(fir, f);
+            (if antisymmetric { amplitude.im() } else { amplitude.re() } - desired(f)).abs()
+        }).fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn low_pass_shall_have_equal_ripple_in_both_bands() {
+        let design = Remez::new(31, &[(0.0, 0.2), (0.3, 0.5)], &[1.0, 0.0], 1.0).design().unwrap();
+        let fir = design.coefficients();
+
+        assert!(design.converged());
+        assert_eq!(fir.len(), 31);
+        for (a, b) in fir.iter().zip(fir.iter().rev()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        let passband = band_error(fir, (0.0, 0.2), |_| 1.0, false);
+        let stopband = band_error(fir, (0.3, 0.5), |_| 0.0, false);
+        // extremals are searched on grid, so true peaks may be slightly above deviation
+        assert!((passband - design.deviation()).abs() < 1e-2 * design.deviation(), "{} {}", passband, design.deviation());
+        assert!((stopband - design.deviation()).abs() < 1e-2 * design.deviation());
+        assert!(design.deviation() < 2e-3);
+        assert!(design.extremal_frequencies().iter().all(|f| (0.0..=0.5).contains(f)));
+    }
+
+    #[test]
+    fn weights_shall_scale_ripple_of_bands() {
+        let design = Remez::new(41, &[(0.0, 1000.0), (1500.0, 4000.0)], &[1.0, 0.0], 8000.0)
+            .set_weights(&[1.0, 10.0])
+            .design()
+            .unwrap();
+        let fir = design.coefficients();
+        let passband = band_error(fir, (0.0, 0.125), |_| 1.0, false);
+        let stopband = band_error(fir, (0.1875, 0.5), |_| 0.0, false);
+
+        assert!(design.converged());
+        assert!((passband / stopband - 10.0).abs() < 0.1, "{} {}", passband, stopband);
+    }
+
+    #[test]
+    fn band_pass_and_high_pass_shall_be_designed() {
+        let band_pass = Remez::new(55, &[(0.0, 0.1), (0.15, 0.3), (0.35, 0.5)], &[0.0, 1.0, 0.0], 1.0).design().unwrap();
+        let high_pass = Remez::new(33, &[(0.0, 0.2), (0.3, 0.5)], &[0.0, 1.0], 1.0).design().unwrap();
+
+        assert!(band_pass.converged() && high_pass.converged());
+        assert!(band_error(band_pass.coefficients(), (0.15, 0.3), |_| 1.0, false) < 1.02 * band_pass.deviation());
+        assert!(band_error(high_pass.coefficients(), (0.0, 0.2), |_| 0.0, false) < 1.02 * high_pass.deviation());
+        assert_eq!(Remez::new(32, &[(0.0, 0.2), (0.3, 0.5)], &[0.0, 1.0], 1.0).design(),
+                   Err(FirDesignError::EvenNumberOfTaps(32)));
+    }
+
+    #[test]
+    fn even_length_low_pass_shall_be_designed() {
+        let design = Remez::new(30, &[(0.0, 0.2), (0.3, 0.5)], &[1.0, 0.0], 1.0).design().unwrap();
+
+        assert!(design.converged());
+        assert!(band_error(design.coefficients(), (0.0, 0.2), |_| 1.0, false) < 1.02 * design.deviation());
+        assert!(band_error(design.coefficients(), (0.3, 0.5), |_| 0.0, false) < 1.02 * design.deviation());
+    }
+
+    #[test]
+    fn differentiator_shall_have_linear_response() {
+        let design = Remez::new(20, &[(0.0, 0.45)], &[2.0 * PI], 1.0)
+            .set_filter_type(RemezFilterType::Differentiator)
+            .design()
+            .unwrap();
+        let fir = design.coefficients();
+
+        assert!(design.converged());
+        for (a, b) in fir.iter().zip(fir.iter().rev()) {
+            assert!((a + b).abs() < 1e-12);
+        }
+        for i in 1..=100 {
+            let f = 0.45 * i as f64 / 100.0;
+            let relative_error = (fir_amplitude(fir, f).im() - 2.0 * PI * f).abs() / (2.0 * PI * f);
+            assert!(relative_error < 1.02 * design.deviation() / (2.0 * PI), "{} {}", f, relative_error);
+        }
+        assert!(design.deviation() < 0.01);
+    }
+
+    #[test]
+    fn hilbert_transformer_shall_have_unity_gain_in_band() {
+        let design = Remez::new(31, &[(0.05, 0.45)], &[1.0], 1.0)
+            .set_filter_type(RemezFilterType::Hilbert)
+            .design()
+            .unwrap();
+        let fir = design.coefficients();
+
+        assert!(design.converged());
+        assert!(band_error(fir, (0.05, 0.45), |_| 1.0, true) < 1.02 * design.deviation());
+        assert!(design.deviation() < 0.01);
+        for h in fir.iter().skip(1).step_by(2) {
+            assert!(h.abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn estimated_order_shall_meet_specification() {
+        let (dp, ds) = ripple_deviations(0.5, 60.0);
+        let taps = remez_order(0.5, 60.0, 500.0, 8000.0);
+        let design = Remez::new(taps | 1, &[(0.0, 1000.0), (1500.0, 4000.0)], &[1.0, 0.0], 8000.0)
+            .set_weights(&[1.0, dp / ds])
+            .design()
+            .unwrap();
+
+        assert!((30..=40).contains(&taps), "{}", taps);
+        assert!(band_error(design.coefficients(), (0.1875, 0.5), |_| 0.0, false) < 1.2 * ds);
+        assert!(band_error(design.coefficients(), (0.0, 0.125), |_| 1.0, false) < 1.2 * dp);
+    }
+
+    #[test]
+    fn invalid_specification_shall_be_rejected() {
+        assert_eq!(Remez::new(31, &[(0.0, 0.3), (0.2, 0.5)], &[1.0, 0.0], 1.0).design(),
+                   Err(FirDesignError::InvalidBand{start: 0.2, end: 0.5}));
+        assert_eq!(Remez::new(31, &[(0.0, 0.3)], &[1.0, 0.0], 1.0).design(),
+                   Err(FirDesignError::BandCountMismatch{bands: 1, values: 2}));
+        assert_eq!(Remez::new(31, &[(0.0, 0.3)], &[1.0], 1.0).set_weights(&[0.0]).design(),
+                   Err(FirDesignError::InvalidWeight(0.0)));
+        assert_eq!(Remez::new(31, &[(0.0, 0.3)], &[1.0], 1.0).set_weights(&[f64::INFINITY]).design(),
+                   Err(FirDesignError::InvalidWeight(f64::INFINITY)));
+        assert_eq!(Remez::new(31, &[(0.0, 0.3)], &[f64::INFINITY], 1.0).design(),
+                   Err(FirDesignError::InvalidGain(f64::INFINITY)));
+        assert!(matches!(Remez::new(31, &[(0.0, 0.3)], &[f64::NAN], 1.0).design(), Err(FirDesignError::InvalidGain(d)) if d.is_nan()));
+        assert_eq!(Remez::new(31, &[], &[], 1.0).design(), Err(FirDesignError::MissingBands));
+    }
+}
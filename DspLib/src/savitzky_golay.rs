@@ -0,0 +1,267 @@
+use std::fmt::Display;
+
+use crate::linear_algebra::least_squares;
+use crate::rank_filters::extend;
+use crate::{DescreteSignal, WindowBoundary};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SavitzkyGolayError {
+    /// Window shall have odd number of samples.
+    InvalidWindowLength(usize),
+    /// Polynomial order shall be smaller than window length.
+    InvalidOrder { order: usize, window_length: usize },
+    /// Derivative order shall not exceed polynomial order.
+    InvalidDerivative { derivative: usize, order: usize },
+    InvalidSampleSpacing(f64),
+    /// Polynomial edge treatment needs at least one whole window.
+    SignalTooShort { length: usize, window_length: usize },
+}
+
+impl Display for SavitzkyGolayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SavitzkyGolayError::InvalidWindowLength(length) => write!(f, "window length {} shall be odd", length),
+            SavitzkyGolayError::InvalidOrder{order, window_length} =>
+                write!(f, "polynomial order {} shall be smaller than window length {}", order, window_length),
+            SavitzkyGolayError::InvalidDerivative{derivative, order} =>
+                write!(f, "derivative order {} shall not exceed polynomial order {}", derivative, order),
+            SavitzkyGolayError::InvalidSampleSpacing(spacing) => write!(f, "sample spacing {} shall be positive", spacing),
+            SavitzkyGolayError::SignalTooShort{length, window_length} =>
+                write!(f, "signal with {} samples is shorter than window length {}", length, window_length),
+        }
+    }
+}
+
+impl std::error::Error for SavitzkyGolayError {}
+
+/// Treatment of samples, which do not have whole window centered on them.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SavitzkyGolayEdge {
+    /// Polynomial fitted to the first (last) window is evaluated at edge samples.
+    #[default]
+    Polynomial,
+    /// Edge sample is repeated.
+    Nearest,
+    /// Mirror reflection without repeating the edge sample.
+    Reflect,
+    /// Missing samples have given value.
+    Constant(f64),
+}
+
+/// Savitzky–Golay filter. Polynomial of given order is fitted by least squares to window
+/// centered on every sample and its value (or derivative) at the center is the output.
+/// It smooths noise while it keeps height and width of peaks better than moving average.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SavitzkyGolay {
+    window_length: usize,
+    order: usize,
+    derivative: usize,
+    edge: SavitzkyGolayEdge,
+}
+
+impl SavitzkyGolay {
+    /// Smoothing filter with odd window length, parameters are checked when it is used.
+    pub fn new(window_length: usize, order: usize) -> Self {
+        Self{window_length, order, derivative: 0, edge: SavitzkyGolayEdge::default()}
+    }
+
+    /// Order of derivative, 0 (default) gives smoothed signal.
+    pub fn set_derivative(mut self, derivative: usize) -> Self {
+        self.derivative = derivative;
+        self
+    }
+
+    pub fn set_edge(mut self, edge: SavitzkyGolayEdge) -> Self {
+        self.edge = edge;
+        self
+    }
+
+    pub fn window_length(&self) -> usize {
+        self.window_length
+    }
+
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    pub fn derivative(&self) -> usize {
+        self.derivative
+    }
+
+    pub fn edge(&self) -> SavitzkyGolayEdge {
+        self.edge
+    }
+
+    /// Coefficients for samples with given spacing (e.g. sampling period in seconds). They are in
+    /// order of window, the oldest sample first: `y[n] = sum(c[k] x[n - window_length / 2 + k])`,
+    /// so they have to be reversed for convolution.
+    pub fn coefficients(&self, sample_spacing: f64) -> Result<Vec<f64>, SavitzkyGolayError> {
+        Ok(self.weights(&self.fit(sample_spacing)?, 0.0, sample_spacing))
+    }
+
+    /// Filters whole signal, sample spacing is sampling period of the signal.
+    /// Output samples keep timestamps of the input ones.
+    pub fn filter(&self, signal: &DescreteSignal, output: &mut DescreteSignal) -> Result<(), SavitzkyGolayError> {
+        let spacing = if signal.len() > 1 { signal.get_sampling_period() } else { 1.0 };
+        let values = self.filter_block(&signal.values().collect::<Vec<f64>>(), spacing)?;
+        output.set_metadata(signal.metadata().clone());
+        for (t, y) in signal.times().zip(values) {
+            output.push(t, y);
+        }
+        Ok(())
+    }
+
+    /// Filters samples with given spacing.
+    pub fn filter_block(&self, input: &[f64], sample_spacing: f64) -> Result<Vec<f64>, SavitzkyGolayError> {
+        let fit = self.fit(sample_spacing)?;
+        let coefficients = self.weights(&fit, 0.0, sample_spacing);
+        if input.is_empty() {
+            return Ok(vec![]);
+        }
+        let length = self.window_length;
+        let half = length / 2;
+        let boundary = match self.edge {
+            SavitzkyGolayEdge::Polynomial => {
+                if input.len() < length {
+                    return Err(SavitzkyGolayError::SignalTooShort{length: input.len(), window_length: length});
+                }
+                WindowBoundary::Shrink
+            }
+            SavitzkyGolayEdge::Nearest => WindowBoundary::Nearest,
+            SavitzkyGolayEdge::Reflect => WindowBoundary::Reflect,
+            SavitzkyGolayEdge::Constant(value) => WindowBoundary::Constant(value),
+        };
+        let (extended, offset) = extend(input, length, boundary);
+        let apply = |c: &[f64], start: usize| c.iter().zip(&extended[start..start + length]).map(|(c, x)| c * x).sum::<f64>();
+
+        Ok((0..input.len()).map(|i| {
+            let last = input.len().saturating_sub(half + 1);
+            if offset == 0 && i < half {
+                apply(&self.weights(&fit, i as f64 - half as f64, sample_spacing), 0)
+            } else if offset == 0 && i > last {
+                apply(&self.weights(&fit, (i - last) as f64, sample_spacing), input.len() - length)
+            } else {
+                apply(&coefficients, i + offset - half)
+            }
+        }).collect())
+    }
+
+    /// Checks parameters and returns rows of least squares solution for polynomial coefficients
+    /// (from `x^0`), so that `p[j] = sum(fit[j][k] x[k])` where offset of the window center is 0.
+    fn fit(&self, sample_spacing: f64) -> Result<Vec<Vec<f64>>, SavitzkyGolayError> {
+        let length = self.window_length;
+        if !(sample_spacing > 0.0 && sample_spacing.is_finite()) {
+            return Err(SavitzkyGolayError::InvalidSampleSpacing(sample_spacing));
+        }
+        if length.is_multiple_of(2) {
+            return Err(SavitzkyGolayError::InvalidWindowLength(length));
+        }
+        if self.order >= length {
+            return Err(SavitzkyGolayError::InvalidOrder{order: self.order, window_length: length});
+        }
+        if self.derivative > self.order {
+            return Err(SavitzkyGolayError::InvalidDerivative{derivative: self.derivative, order: self.order});
+        }
+        let half = (length / 2) as f64;
+        let matrix = (0..length).map(|k| {
+            (0..=self.order).map(|p| (k as f64 - half).powi(p as i32)).collect()
+        }).collect::<Vec<Vec<f64>>>();
+        // columns of pseudo inverse are fits of unit impulses
+        let columns = (0..length).map(|k| {
+            let mut impulse = vec![0.0; length];
+            impulse[k] = 1.0;
+            least_squares(&matrix, &impulse).expect("distinct offsets give full rank")
+        }).collect::<Vec<Vec<f64>>>();
+        Ok((0..=self.order).map(|p| columns.iter().map(|c| c[p]).collect()).collect())
+    }
+
+    /// Coefficients giving derivative of fitted polynomial at offset `t` (in samples) from
+    /// the window center.
+    fn weights(&self, fit: &[Vec<f64>], t: f64, sample_spacing: f64) -> Vec<f64> {
+        let d = self.derivative;
+        let scale = sample_spacing.powi(d as i32);
+        (0..self.window_length).map(|k| {
+            (d..=self.order).map(|p| {
+                let falling = ((p - d + 1)..=p).product::<usize>() as f64;
+                fit[p][k] * falling * t.powi((p - d) as i32)
+            }).sum::<f64>() / scale
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_close;
+
+    #[test]
+    fn coefficients_shall_match_tabulated_values() {
+        let smoothing = SavitzkyGolay::new(5, 2).coefficients(1.0).unwrap();
+        assert_close(&smoothing, &[-3.0, 12.0, 17.0, 12.0, -3.0].map(|c| c / 35.0), 1e-12);
+
+        let derivative = SavitzkyGolay::new(5, 2).set_derivative(1).coefficients(0.5).unwrap();
+        assert_close(&derivative, &[-2.0, -1.0, 0.0, 1.0, 2.0].map(|c| c / 5.0), 1e-12);
+
+        let second = SavitzkyGolay::new(7, 3).set_derivative(2).coefficients(1.0).unwrap();
+        assert_close(&second, &[5.0, 0.0, -3.0, -4.0, -3.0, 0.0, 5.0].map(|c| c / 42.0), 1e-12);
+    }
+
+    #[test]
+    fn polynomial_shall_be_reproduced_including_edges() {
+        let dt = 0.01;
+        let signal = DescreteSignal::new_from_vec((0..40).map(|i| {
+            let t = i as f64 * dt;
+            (t, 1.0 + 2.0 * t - 30.0 * t * t + 40.0 * t * t * t)
+        }).collect());
+        let expected = [
+            signal.times().map(|t| 1.0 + 2.0 * t - 30.0 * t * t + 40.0 * t * t * t).collect::<Vec<f64>>(),
+            signal.times().map(|t| 2.0 - 60.0 * t + 120.0 * t * t).collect(),
+            signal.times().map(|t| -60.0 + 240.0 * t).collect(),
+        ];
+
+        for (derivative, expected) in expected.iter().enumerate() {
+            let mut output = DescreteSignal::new();
+            SavitzkyGolay::new(9, 3).set_derivative(derivative).filter(&signal, &mut output).unwrap();
+            assert_close(&output.values().collect::<Vec<f64>>(), expected, 1e-6);
+            assert_eq!(output.times().collect::<Vec<f64>>(), signal.times().collect::<Vec<f64>>());
+        }
+    }
+
+    #[test]
+    fn smoothing_shall_keep_peak_better_than_moving_average() {
+        let peak = (0..101).map(|i| (-((i as f64 - 50.0) / 4.0).powi(2)).exp()).collect::<Vec<f64>>();
+        let noisy = peak.iter().enumerate().map(|(i, x)| x + 0.02 * (((i * 37) % 11) as f64 / 5.0 - 1.0)).collect::<Vec<f64>>();
+        let smoothed = SavitzkyGolay::new(11, 4).filter_block(&noisy, 1.0).unwrap();
+        let average = SavitzkyGolay::new(11, 0).filter_block(&noisy, 1.0).unwrap();
+
+        assert!((smoothed[50] - 1.0).abs() < 0.03, "{}", smoothed[50]);
+        assert!(average[50] < 0.75, "{}", average[50]);
+        let error = |y: &[f64]| y.iter().zip(&peak).map(|(y, x)| (y - x).powi(2)).sum::<f64>();
+        assert!(error(&smoothed) < error(&noisy) / 2.0);
+    }
+
+    #[test]
+    fn padded_edges_shall_use_window_boundary() {
+        let filter = SavitzkyGolay::new(3, 1);
+        let input = [1.0, 4.0, 7.0, 4.0];
+        assert_close(&filter.clone().set_edge(SavitzkyGolayEdge::Nearest).filter_block(&input, 1.0).unwrap(), &[2.0, 4.0, 5.0, 5.0], 1e-12);
+        assert_close(&filter.clone().set_edge(SavitzkyGolayEdge::Reflect).filter_block(&input, 1.0).unwrap(), &[3.0, 4.0, 5.0, 6.0], 1e-12);
+        assert_close(&filter.clone().set_edge(SavitzkyGolayEdge::Constant(-2.0)).filter_block(&input, 1.0).unwrap(), &[1.0, 4.0, 5.0, 3.0], 1e-12);
+        // polynomial edge extrapolates lines fitted to the first and the last window
+        assert_close(&filter.filter_block(&input, 1.0).unwrap(), &[1.0, 4.0, 5.0, 5.0], 1e-12);
+        assert_close(&SavitzkyGolay::new(5, 2).set_edge(SavitzkyGolayEdge::Reflect).filter_block(&[2.0], 1.0).unwrap(), &[2.0], 1e-12);
+    }
+
+    #[test]
+    fn invalid_parameters_shall_be_rejected() {
+        assert_eq!(SavitzkyGolay::new(4, 2).coefficients(1.0), Err(SavitzkyGolayError::InvalidWindowLength(4)));
+        assert_eq!(SavitzkyGolay::new(5, 5).coefficients(1.0), Err(SavitzkyGolayError::InvalidOrder{order: 5, window_length: 5}));
+        assert_eq!(SavitzkyGolay::new(5, 2).set_derivative(3).coefficients(1.0),
+                   Err(SavitzkyGolayError::InvalidDerivative{derivative: 3, order: 2}));
+        assert_eq!(SavitzkyGolay::new(5, 2).coefficients(0.0), Err(SavitzkyGolayError::InvalidSampleSpacing(0.0)));
+        assert_eq!(SavitzkyGolay::new(5, 2).filter_block(&[1.0, 2.0], 1.0),
+                   Err(SavitzkyGolayError::SignalTooShort{length: 2, window_length: 5}));
+    }
+}
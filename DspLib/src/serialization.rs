@@ -0,0 +1,235 @@
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{ComplexNumber, ComplexSignal, DescreteSignal, MultichannelSignal, SignalMetadata};
+
+/// Time axis of serialized signal. Uniformly sampled signals are stored only with their first
+/// timestamp and sampling rate (or step), but only when timestamps are restored bit-exact,
+/// otherwise every timestamp is stored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum TimeAxis {
+    /// Timestamps equal to `start + i / sampling_rate`.
+    Sampled { start: f64, sampling_rate: f64 },
+    /// Timestamps created by repeated addition of `step` to `start`.
+    Stepped { start: f64, step: f64 },
+    Explicit(Vec<f64>),
+}
+
+impl TimeAxis {
+    fn new(times: &[f64], metadata: &SignalMetadata) -> Self {
+        if times.len() < 2 {
+            return TimeAxis::Explicit(times.to_vec());
+        }
+        let start = times[0];
+        let delta = times[1] - times[0];
+        let rates = [metadata.sample_rate(), Some(1.0 / delta)];
+        let steps = [Some(delta), metadata.sample_rate().map(|rate| 1.0 / rate)];
+        // zero step (repeated timestamps) gives infinite rate that cannot be stored
+        let usable = |value: &f64| value.is_finite() && *value != 0.0;
+
+        let candidates = rates.into_iter().flatten().filter(usable).map(|sampling_rate| TimeAxis::Sampled{start, sampling_rate})
+            .chain(steps.into_iter().flatten().filter(usable).map(|step| TimeAxis::Stepped{start, step}));
+        for axis in candidates {
+            if axis.times(times.len()).iter().zip(times).all(|(a, b)| a.to_bits() == b.to_bits()) {
+                return axis;
+            }
+        }
+        TimeAxis::Explicit(times.to_vec())
+    }
+
+    fn times(&self, len: usize) -> Vec<f64> {
+        match self {
+            TimeAxis::Sampled{start, sampling_rate} => (0..len).map(|i| start + i as f64 / sampling_rate).collect(),
+            TimeAxis::Stepped{start, step} => {
+                let mut t = *start;
+                (0..len).map(|_| {
+                    let current = t;
+                    t += step;
+                    current
+                }).collect()
+            }
+            TimeAxis::Explicit(times) => times.clone(),
+        }
+    }
+
+    fn restore<E: Error>(&self, len: usize) -> Result<Vec<f64>, E> {
+        match self {
+            TimeAxis::Explicit(times) if times.len() != len => Err(E::custom(format!(
+                "time axis has {} timestamps, but there are {} samples", times.len(), len))),
+            axis => Ok(axis.times(len)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SignalData<T> {
+    time: TimeAxis,
+    values: T,
+    #[serde(default)]
+    metadata: SignalMetadata,
+}
+
+impl Serialize for DescreteSignal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let times = self.times().collect::<Vec<f64>>();
+        SignalData{
+            time: TimeAxis::new(&times, self.metadata()),
+            values: self.values().collect::<Vec<f64>>(),
+            metadata: self.metadata().clone(),
+        }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DescreteSignal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = SignalData::<Vec<f64>>::deserialize(deserializer)?;
+        let times = data.time.restore(data.values.len())?;
+        let mut signal = times.into_iter().zip(data.values).collect::<DescreteSignal>();
+        signal.set_metadata(data.metadata);
+        Ok(signal)
+    }
+}
+
+impl Serialize for ComplexSignal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let times = self.times().collect::<Vec<f64>>();
+        SignalData{
+            time: TimeAxis::new(&times, self.metadata()),
+            values: self.samples().collect::<Vec<ComplexNumber>>(),
+            metadata: self.metadata().clone(),
+        }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ComplexSignal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = SignalData::<Vec<ComplexNumber>>::deserialize(deserializer)?;
+        let times = data.time.restore(data.values.len())?;
+        let mut signal = times.into_iter().zip(data.values).collect::<ComplexSignal>();
+        signal.set_metadata(data.metadata);
+        Ok(signal)
+    }
+}
+
+impl Serialize for MultichannelSignal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SignalData{
+            time: TimeAxis::new(self.times(), self.metadata()),
+            values: (0..self.number_of_channels()).map(|ch| self.channel_values(ch)).collect::<Vec<&[f64]>>(),
+            metadata: self.metadata().clone(),
+        }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for MultichannelSignal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = SignalData::<Vec<Vec<f64>>>::deserialize(deserializer)?;
+        let len = data.values.first().map_or(0, Vec::len);
+        if data.values.iter().any(|ch| ch.len() != len) {
+            return Err(D::Error::custom("every channel shall have the same number of samples"));
+        }
+        let mut signal = MultichannelSignal::new_from_planar(data.time.restore(len)?, data.values);
+        signal.set_metadata(data.metadata);
+        Ok(signal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fft, Generator, Histogram, NpyArray, RawFormat, RawSampleType};
+
+    fn round_trip<T: Serialize + for<'de> Deserialize<'de>>(value: &T) -> (String, T) {
+        let json = serde_json::to_string(value).unwrap();
+        let restored = serde_json::from_str(&json).unwrap();
+        (json, restored)
+    }
+
+    #[test]
+    fn uniformly_sampled_signal_shall_be_stored_without_timestamps() {
+        let mut signal = MultichannelSignal::from_planar(vec![vec![1.0, 2.0, 3.0], vec![0.5, 0.25, 0.125]], 48000.0, 0.5)
+            .channel(1);
+        signal.set_metadata(signal.metadata().clone().set_name("level").set_value_unit("V"));
+        let (json, restored) = round_trip(&signal);
+
+        assert!(json.contains(r#""time":{"start":0.5,"sampling_rate":48000.0}"#), "{}", json);
+        assert_eq!(restored, signal);
+    }
+
+    #[test]
+    fn generated_signal_and_its_spectrum_shall_be_stored_compactly() {
+        let signal = Generator::sine_wave(50.0).set_number_of_periods(3.0).generate();
+        let mut spectrum = DescreteSignal::new();
+        fft(&signal, &mut spectrum);
+
+        for s in [signal, spectrum] {
+            let (json, restored) = round_trip(&s);
+            assert!(json.starts_with(r#"{"time":{"start""#), "{}", json);
+            assert_eq!(restored, s);
+        }
+    }
+
+    #[test]
+    fn irregular_timestamps_shall_be_stored_explicitly() {
+        let signal = DescreteSignal::new_from_vec(vec![(0.0, 1.0), (0.1, 2.0), (0.35, 3.0)]);
+        let (json, restored) = round_trip(&signal);
+
+        assert!(json.starts_with(r#"{"time":[0.0,0.1,0.35],"values":[1.0,2.0,3.0]"#), "{}", json);
+        assert_eq!(restored, signal);
+    }
+
+    #[test]
+    fn repeated_and_unordered_timestamps_shall_survive_round_trip() {
+        let repeated = DescreteSignal::new_from_vec(vec![(0.0, 1.0), (0.0, 2.0), (0.0, 3.0)]);
+        let (json, restored) = round_trip(&repeated);
+        assert!(json.starts_with(r#"{"time":[0.0,0.0,0.0],"#), "{}", json);
+        assert_eq!(restored, repeated);
+
+        for times in [[0.0, 1.0, 0.5], [2.0, 1.0, 0.0], [1.0, 1.0, 2.0]] {
+            let signal = times.iter().map(|t| (*t, 1.0)).collect::<DescreteSignal>();
+            assert_eq!(round_trip(&signal).1, signal);
+        }
+    }
+
+    #[test]
+    fn complex_and_multichannel_signals_shall_survive_round_trip() {
+        let complex = ComplexSignal::from_iq(&[1.0, 0.0, -1.0], &[0.0, 1.0, 0.0], 8.0, 0.0);
+        assert_eq!(round_trip(&complex).1, complex);
+
+        let multichannel = MultichannelSignal::from_interleaved(&[1.0, -1.0, 2.0, -2.0], 2, 100.0, 1.0);
+        assert_eq!(round_trip(&multichannel).1, multichannel);
+    }
+
+    #[test]
+    fn invalid_signal_data_shall_be_rejected() {
+        assert!(serde_json::from_str::<DescreteSignal>(r#"{"time":[0.0],"values":[1.0,2.0]}"#).is_err());
+        assert!(serde_json::from_str::<MultichannelSignal>(
+            r#"{"time":{"start":0.0,"step":1.0},"values":[[1.0],[1.0,2.0]]}"#).is_err());
+    }
+
+    #[test]
+    fn generator_configuration_shall_survive_round_trip() {
+        let mut generator = Generator::rectangle_wave(10.0, 0.3).set_amplitude(2.0).set_number_of_periods(2.0);
+        let (_, mut restored) = round_trip(&generator);
+
+        assert_eq!(restored.generate(), generator.generate());
+    }
+
+    #[test]
+    fn other_types_shall_survive_round_trip() {
+        let number = ComplexNumber::new(1.5, -0.25);
+        let (json, restored) = round_trip(&number);
+        assert_eq!(json, "[1.5,-0.25]");
+        assert_eq!(restored, number);
+
+        let histogram = DescreteSignal::new_from_vec(vec![(0.0, 1.0), (1.0, 2.0), (2.0, 2.0)]).histogram(2);
+        assert_eq!(round_trip::<Histogram>(&histogram).1, histogram);
+
+        let format = RawFormat::new(RawSampleType::I16).set_channels(2);
+        assert_eq!(round_trip(&format).1, format);
+
+        let array = NpyArray::from_complex(vec![number]);
+        assert_eq!(round_trip(&array).1, array);
+    }
+}
@@ -0,0 +1,436 @@
+//! Streaming smoothing filters. Constructors of sliding window filters panic when window length
+//! (or number of passes) is zero.
+
+use std::collections::VecDeque;
+
+use crate::StreamFilter;
+
+/// The newest `length` samples in circular buffer.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Window {
+    buffer: Vec<f64>,
+    position: usize,
+    filled: usize,
+}
+
+impl Window {
+    fn new(length: usize) -> Self {
+        assert!(length > 0, "window shall have at least one sample");
+        Self{buffer: vec![0.0; length], position: 0, filled: 0}
+    }
+
+    /// Stores sample and returns the one that left the window.
+    fn push(&mut self, x: f64) -> Option<f64> {
+        let old = std::mem::replace(&mut self.buffer[self.position], x);
+        self.position = (self.position + 1) % self.buffer.len();
+        if self.filled < self.buffer.len() {
+            self.filled += 1;
+            None
+        } else {
+            Some(old)
+        }
+    }
+
+    /// True when the whole buffer was just overwritten, running sums can be refreshed then.
+    fn wrapped(&self) -> bool {
+        self.position == 0 && self.filled == self.buffer.len()
+    }
+
+    fn samples(&self) -> &[f64] {
+        &self.buffer[..self.filled]
+    }
+
+    fn reset(&mut self) {
+        self.buffer.fill(0.0);
+        self.position = 0;
+        self.filled = 0;
+    }
+}
+
+/// Mean of the newest `length` samples computed from running sum, so every sample costs O(1)
+/// regardless of window length. Until the window is filled, output is mean of samples received
+/// so far. Output is delayed by `(length - 1) / 2` samples, timestamps are not shifted.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MovingAverage {
+    window: Window,
+    sum: f64,
+}
+
+impl MovingAverage {
+    pub fn new(length: usize) -> Self {
+        Self{window: Window::new(length), sum: 0.0}
+    }
+
+    pub fn length(&self) -> usize {
+        self.window.buffer.len()
+    }
+
+    /// Delay in samples, `(length - 1) / 2`.
+    pub fn delay(&self) -> f64 {
+        (self.length() - 1) as f64 / 2.0
+    }
+}
+
+impl StreamFilter for MovingAverage {
+    fn process_sample(&mut self, x: f64) -> f64 {
+        let old = self.window.push(x).unwrap_or(0.0);
+        // running sum is recomputed once per window, so rounding errors do not accumulate
+        self.sum = if self.window.wrapped() { self.window.samples().iter().sum() } else { self.sum + x - old };
+        self.sum / self.window.filled as f64
+    }
+
+    fn reset(&mut self) {
+        self.window.reset();
+        self.sum = 0.0;
+    }
+}
+
+/// Root mean square of the newest `length` samples computed from running sum of squares,
+/// O(1) per sample. Until the window is filled, only samples received so far are used.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MovingRms {
+    window: Window,
+    sum: f64,
+}
+
+impl MovingRms {
+    pub fn new(length: usize) -> Self {
+        Self{window: Window::new(length), sum: 0.0}
+    }
+
+    pub fn length(&self) -> usize {
+        self.window.buffer.len()
+    }
+}
+
+impl StreamFilter for MovingRms {
+    fn process_sample(&mut self, x: f64) -> f64 {
+        let old = self.window.push(x).unwrap_or(0.0);
+        self.sum = if self.window.wrapped() {
+            self.window.samples().iter().map(|x| x * x).sum()
+        } else {
+            self.sum + x * x - old * old
+        };
+        // rounding can make sum of squares slightly negative
+        (self.sum.max(0.0) / self.window.filled as f64).sqrt()
+    }
+
+    fn reset(&mut self) {
+        self.window.reset();
+        self.sum = 0.0;
+    }
+}
+
+/// Candidates for extreme of window, the oldest first. Every sample enters and leaves
+/// the deque once, so cost is amortized O(1) per sample.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct MonotonicDeque {
+    length: usize,
+    count: usize,
+    candidates: VecDeque<(usize, f64)>,
+}
+
+impl MonotonicDeque {
+    fn new(length: usize) -> Self {
+        assert!(length > 0, "window shall have at least one sample");
+        Self{length, count: 0, candidates: VecDeque::new()}
+    }
+
+    /// Adds sample and returns extreme of window. Samples `dominated` by the new one can never
+    /// be extreme again.
+    fn push(&mut self, x: f64, dominated: impl Fn(f64, f64) -> bool) -> f64 {
+        while self.candidates.back().is_some_and(|(_, old)| dominated(*old, x)) {
+            self.candidates.pop_back();
+        }
+        self.candidates.push_back((self.count, x));
+        self.count += 1;
+        while self.candidates.front().is_some_and(|(i, _)| i + self.length < self.count) {
+            self.candidates.pop_front();
+        }
+        self.candidates[0].1
+    }
+
+    fn reset(&mut self) {
+        self.count = 0;
+        self.candidates.clear();
+    }
+}
+
+/// Minimum of the newest `length` samples (monotonic deque), amortized O(1) per sample.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MovingMinimum {
+    deque: MonotonicDeque,
+}
+
+impl MovingMinimum {
+    pub fn new(length: usize) -> Self {
+        Self{deque: MonotonicDeque::new(length)}
+    }
+
+    pub fn length(&self) -> usize {
+        self.deque.length
+    }
+}
+
+impl StreamFilter for MovingMinimum {
+    fn process_sample(&mut self, x: f64) -> f64 {
+        self.deque.push(x, |old, new| old >= new)
+    }
+
+    fn reset(&mut self) {
+        self.deque.reset();
+    }
+}
+
+/// Maximum of the newest `length` samples (monotonic deque), amortized O(1) per sample.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MovingMaximum {
+    deque: MonotonicDeque,
+}
+
+impl MovingMaximum {
+    pub fn new(length: usize) -> Self {
+        Self{deque: MonotonicDeque::new(length)}
+    }
+
+    pub fn length(&self) -> usize {
+        self.deque.length
+    }
+}
+
+impl StreamFilter for MovingMaximum {
+    fn process_sample(&mut self, x: f64) -> f64 {
+        self.deque.push(x, |old, new| old <= new)
+    }
+
+    fn reset(&mut self) {
+        self.deque.reset();
+    }
+}
+
+/// Exponential moving average `y[n] = y[n-1] + alpha (x[n] - y[n-1])` with time constant in
+/// seconds, `alpha = 1 - exp(-T / tau)`. Step response reaches `1 - 1/e` of the step after
+/// time constant. The first sample initializes output, so there is no start-up transient.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExponentialMovingAverage {
+    alpha: f64,
+    value: Option<f64>,
+}
+
+impl ExponentialMovingAverage {
+    /// Panics when time constant is negative or sampling rate is not positive.
+    pub fn new(time_constant: f64, sample_rate: f64) -> Self {
+        assert!(time_constant >= 0.0, "time constant shall not be negative");
+        assert!(sample_rate > 0.0, "sampling rate shall be positive");
+        Self::from_alpha(1.0 - (-1.0 / (time_constant * sample_rate)).exp())
+    }
+
+    /// Smoothing factor from 0 (output never changes) to 1 (no smoothing). Panics outside that range.
+    pub fn from_alpha(alpha: f64) -> Self {
+        assert!((0.0..=1.0).contains(&alpha), "smoothing factor shall be between 0 and 1");
+        Self{alpha, value: None}
+    }
+
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+}
+
+impl StreamFilter for ExponentialMovingAverage {
+    fn process_sample(&mut self, x: f64) -> f64 {
+        let y = self.value.map_or(x, |y| y + self.alpha * (x - y));
+        self.value = Some(y);
+        y
+    }
+
+    /// Clears state, the next sample initializes output again.
+    fn reset(&mut self) {
+        self.value = None;
+    }
+}
+
+/// Several moving averages of the same length in series. Impulse response is convolution of
+/// boxcars, which quickly approaches Gaussian (3 passes are usually enough), so it is cheap
+/// Gaussian smoothing with O(passes) cost per sample.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CascadedBoxcar {
+    stages: Vec<MovingAverage>,
+}
+
+impl CascadedBoxcar {
+    pub fn new(length: usize, passes: usize) -> Self {
+        assert!(passes > 0, "there shall be at least one pass");
+        Self{stages: vec![MovingAverage::new(length); passes]}
+    }
+
+    /// The shortest boxcar whose cascade has standard deviation at least `sigma` samples.
+    pub fn from_sigma(sigma: f64, passes: usize) -> Self {
+        // variance of boxcar of length N is (N^2 - 1) / 12
+        let length = (12.0 * sigma * sigma / passes as f64 + 1.0).sqrt().ceil().max(1.0);
+        Self::new(length as usize, passes)
+    }
+
+    pub fn length(&self) -> usize {
+        self.stages[0].length()
+    }
+
+    pub fn passes(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// Standard deviation of impulse response in samples.
+    pub fn sigma(&self) -> f64 {
+        let length = self.length() as f64;
+        (self.passes() as f64 * (length * length - 1.0) / 12.0).sqrt()
+    }
+
+    /// Delay in samples, sum of delays of all passes.
+    pub fn delay(&self) -> f64 {
+        self.stages.iter().map(|s| s.delay()).sum()
+    }
+}
+
+impl StreamFilter for CascadedBoxcar {
+    fn process_sample(&mut self, x: f64) -> f64 {
+        self.stages.iter_mut().fold(x, |x, stage| stage.process_sample(x))
+    }
+
+    fn reset(&mut self) {
+        for stage in self.stages.iter_mut() {
+            stage.reset();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DescreteSignal, FirFilter};
+
+    fn input() -> Vec<f64> {
+        (0..500).map(|i| ((i * 7919) % 103) as f64 / 10.0 - 5.0 + (i as f64 * 0.05).sin()).collect()
+    }
+
+    #[test]
+    fn moving_average_shall_match_boxcar_fir_after_warm_up() {
+        let length = 16;
+        let mut fir = FirFilter::new(&[1.0 / length as f64; 16]);
+        let mut average = MovingAverage::new(length);
+
+        for (i, x) in input().iter().enumerate() {
+            let (y, expected) = (average.process_sample(*x), fir.process_sample(*x));
+            if i + 1 >= length {
+                assert!((y - expected).abs() < 1e-12, "{} {} {}", i, y, expected);
+            } else {
+                let mean = input()[..=i].iter().sum::<f64>() / (i + 1) as f64;
+                assert!((y - mean).abs() < 1e-12);
+            }
+        }
+        assert_eq!(average.delay(), 7.5);
+    }
+
+    #[test]
+    fn running_sums_shall_not_drift() {
+        let mut average = MovingAverage::new(10);
+        let mut rms = MovingRms::new(10);
+        let x = |i: usize| 1e6 + (i % 17) as f64 * 0.1;
+        let (mut mean, mut root) = (0.0, 0.0);
+        for i in 0..200_000 {
+            mean = average.process_sample(x(i));
+            root = rms.process_sample(x(i));
+        }
+
+        let window = (200_000 - 10..200_000).map(x).collect::<Vec<f64>>();
+        assert!((mean - window.iter().sum::<f64>() / 10.0).abs() < 1e-9);
+        assert!((root - (window.iter().map(|x| x * x).sum::<f64>() / 10.0).sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn moving_rms_of_sine_shall_be_amplitude_over_square_root_of_two() {
+        let mut rms = MovingRms::new(40);
+        let sine = (0..400).map(|i| 3.0 * (2.0 * std::f64::consts::PI * i as f64 / 20.0).sin()).collect::<Vec<f64>>();
+        let mut output = vec![0.0; sine.len()];
+        rms.process_block(&sine, &mut output);
+
+        for y in &output[40..] {
+            assert!((y - 3.0 / 2f64.sqrt()).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn moving_extremes_shall_match_brute_force() {
+        let input = input();
+        for length in [1, 2, 5, 33] {
+            let mut minimum = MovingMinimum::new(length);
+            let mut maximum = MovingMaximum::new(length);
+            for (i, x) in input.iter().enumerate() {
+                let window = &input[(i + 1).saturating_sub(length)..=i];
+                assert_eq!(minimum.process_sample(*x), window.iter().copied().fold(f64::INFINITY, f64::min));
+                assert_eq!(maximum.process_sample(*x), window.iter().copied().fold(f64::NEG_INFINITY, f64::max));
+            }
+        }
+    }
+
+    #[test]
+    fn ema_step_response_shall_reach_63_percent_after_time_constant() {
+        let sample_rate = 1000.0;
+        let mut ema = ExponentialMovingAverage::new(0.05, sample_rate);
+
+        assert_eq!(ema.process_sample(0.0), 0.0);
+        let mut y = 0.0;
+        for _ in 0..50 {
+            y = ema.process_sample(1.0);
+        }
+        assert!((y - (1.0 - (-1.0f64).exp())).abs() < 1e-12);
+        ema.reset();
+        assert_eq!(ema.process_sample(4.0), 4.0);
+        assert_eq!(ExponentialMovingAverage::new(0.0, sample_rate).alpha(), 1.0);
+    }
+
+    #[test]
+    fn cascaded_boxcar_shall_approximate_gaussian() {
+        let mut boxcar = CascadedBoxcar::from_sigma(4.0, 3);
+        assert_eq!(boxcar.length(), 9);
+        assert!(boxcar.sigma() >= 4.0);
+
+        // windows filled with zeros first, so impulse response is not affected by warm-up
+        let warm_up = boxcar.length() * boxcar.passes();
+        let mut response = vec![];
+        for n in 0..warm_up + 40 {
+            let y = boxcar.process_sample(if n == warm_up { 1.0 } else { 0.0 });
+            if n >= warm_up {
+                response.push(y);
+            }
+        }
+        let mean = response.iter().enumerate().map(|(i, h)| i as f64 * h).sum::<f64>();
+        let variance = response.iter().enumerate().map(|(i, h)| (i as f64 - mean).powi(2) * h).sum::<f64>();
+        assert!((response.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!((mean - boxcar.delay()).abs() < 1e-12);
+        assert!((variance.sqrt() - boxcar.sigma()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn streaming_shall_not_depend_on_block_size() {
+        let signal = DescreteSignal::new_from_vec(input().iter().enumerate().map(|(i, x)| (i as f64 * 0.01, *x)).collect());
+        let mut expected = DescreteSignal::new();
+        CascadedBoxcar::new(5, 3).process_signal(&signal, &mut expected);
+
+        let mut boxcar = CascadedBoxcar::new(5, 3);
+        let mut output = vec![];
+        for block in input().chunks(7) {
+            let mut y = vec![0.0; block.len()];
+            boxcar.process_block(block, &mut y);
+            output.extend(y);
+        }
+        assert_eq!(output, expected.values().collect::<Vec<f64>>());
+        assert_eq!(expected.times().collect::<Vec<f64>>(), signal.times().collect::<Vec<f64>>());
+    }
+}
@@ -0,0 +1,301 @@
+use crate::DescreteSignal;
+
+/// Histogram of signal values with equally wide bins.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Histogram {
+    /// Bin edges, there is always one edge more than bins.
+    edges: Vec<f64>,
+    counts: Vec<usize>,
+}
+
+impl Histogram {
+    pub fn edges(&self) -> &[f64] {
+        &self.edges
+    }
+
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+
+    pub fn bin_centers(&self) -> Vec<f64> {
+        self.edges.windows(2).map(|e| 0.5 * (e[0] + e[1])).collect()
+    }
+}
+
+/// Statistics treat every sample with equal weight. Functions prefixed with `time_weighted_`
+/// integrate the signal over its timestamps with trapezoidal rule, so they give meaningful
+/// results for non-uniformly sampled signals as well.
+/// All functions return NaN for signals that have not enough samples to calculate the value.
+impl DescreteSignal {
+    pub fn min(&self) -> f64 {
+        self.values().fold(f64::NAN, f64::min)
+    }
+
+    pub fn max(&self) -> f64 {
+        self.values().fold(f64::NAN, f64::max)
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.values().sum::<f64>() / self.len() as f64
+    }
+
+    pub fn rms(&self) -> f64 {
+        self.power().sqrt()
+    }
+
+    /// Population variance of signal values.
+    pub fn variance(&self) -> f64 {
+        self.central_moment(2)
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    /// Largest absolute value of the signal.
+    pub fn peak(&self) -> f64 {
+        self.values().map(f64::abs).fold(f64::NAN, f64::max)
+    }
+
+    pub fn peak_to_peak(&self) -> f64 {
+        self.max() - self.min()
+    }
+
+    /// Ratio of peak value to RMS value.
+    pub fn crest_factor(&self) -> f64 {
+        self.peak() / self.rms()
+    }
+
+    /// Sum of squared sample values.
+    pub fn energy(&self) -> f64 {
+        if self.is_empty() {
+            return f64::NAN;
+        }
+        self.values().map(|y| y * y).sum()
+    }
+
+    /// Mean of squared sample values.
+    pub fn power(&self) -> f64 {
+        self.energy() / self.len() as f64
+    }
+
+    pub fn skewness(&self) -> f64 {
+        self.central_moment(3) / self.variance().powf(1.5)
+    }
+
+    /// Kurtosis as defined by Pearson, equal to 3.0 for normal distribution.
+    pub fn kurtosis(&self) -> f64 {
+        self.central_moment(4) / self.variance().powi(2)
+    }
+
+    /// Kurtosis reduced by 3.0, so normal distribution gives 0.0.
+    pub fn excess_kurtosis(&self) -> f64 {
+        self.kurtosis() - 3.0
+    }
+
+    /// Percentile of signal values, `p` is given in range 0.0..=100.0.
+    /// Values between samples are linearly interpolated.
+    pub fn percentile(&self, p: f64) -> f64 {
+        if self.is_empty() || !(0.0..=100.0).contains(&p) {
+            return f64::NAN;
+        }
+        let mut sorted = self.values().collect::<Vec<f64>>();
+        sorted.sort_by(f64::total_cmp);
+        let rank = p / 100.0 * (sorted.len() - 1) as f64;
+        let low = rank.floor() as usize;
+        let high = rank.ceil() as usize;
+
+        sorted[low] + (sorted[high] - sorted[low]) * (rank - low as f64)
+    }
+
+    pub fn median(&self) -> f64 {
+        self.percentile(50.0)
+    }
+
+    /// Histogram with `bins` equally wide bins spread between minimum and maximum value.
+    /// Last bin is closed, so maximum value is counted in it.
+    pub fn histogram(&self, bins: usize) -> Histogram {
+        self.histogram_in_range(bins, (self.min(), self.max()))
+    }
+
+    /// Histogram with `bins` equally wide bins spread over given range. Values outside
+    /// the range are not counted.
+    pub fn histogram_in_range(&self, bins: usize, (low, high): (f64, f64)) -> Histogram {
+        let width = (high - low) / bins as f64;
+        let edges = (0..=bins).map(|i| low + width * i as f64).collect();
+        let mut counts = vec![0; bins];
+
+        for y in self.values() {
+            if bins == 0 || !(low..=high).contains(&y) {
+                continue;
+            }
+            let bin = if width > 0.0 {
+                (((y - low) / width) as usize).min(bins - 1)
+            } else {
+                0
+            };
+            counts[bin] += 1;
+        }
+
+        Histogram{edges, counts}
+    }
+
+    pub fn time_weighted_mean(&self) -> f64 {
+        self.integrate(|y| y) / self.duration()
+    }
+
+    pub fn time_weighted_rms(&self) -> f64 {
+        self.time_weighted_power().sqrt()
+    }
+
+    pub fn time_weighted_variance(&self) -> f64 {
+        let mean = self.time_weighted_mean();
+        self.integrate(|y| (y - mean) * (y - mean)) / self.duration()
+    }
+
+    pub fn time_weighted_std_dev(&self) -> f64 {
+        self.time_weighted_variance().sqrt()
+    }
+
+    /// Integral of squared signal over time.
+    pub fn time_weighted_energy(&self) -> f64 {
+        self.integrate(|y| y * y)
+    }
+
+    /// Integral of squared signal divided by signal duration.
+    pub fn time_weighted_power(&self) -> f64 {
+        self.time_weighted_energy() / self.duration()
+    }
+
+    fn duration(&self) -> f64 {
+        if self.len() < 2 {
+            return f64::NAN;
+        }
+        self[self.len() - 1].0 - self[0].0
+    }
+
+    fn integrate(&self, f: impl Fn(f64) -> f64) -> f64 {
+        if self.len() < 2 {
+            return f64::NAN;
+        }
+        self.get_data()
+            .windows(2)
+            .map(|w| 0.5 * (f(w[0].1) + f(w[1].1)) * (w[1].0 - w[0].0))
+            .sum()
+    }
+
+    fn central_moment(&self, order: i32) -> f64 {
+        let mean = self.mean();
+        self.values().map(|y| (y - mean).powi(order)).sum::<f64>() / self.len() as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signal_from_values(values: &[f64]) -> DescreteSignal {
+        values.iter().enumerate().map(|(i, y)| (i as f64, *y)).collect()
+    }
+
+    #[test]
+    fn basic_statistics_shall_be_calculated_from_values() {
+        let signal = signal_from_values(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+
+        assert_eq!(signal.min(), 2.0);
+        assert_eq!(signal.max(), 9.0);
+        assert_eq!(signal.mean(), 5.0);
+        assert_eq!(signal.variance(), 4.0);
+        assert_eq!(signal.std_dev(), 2.0);
+        assert_eq!(signal.peak_to_peak(), 7.0);
+        assert_eq!(signal.energy(), 232.0);
+        assert_eq!(signal.power(), 29.0);
+        assert_eq!(signal.rms(), 29.0_f64.sqrt());
+    }
+
+    #[test]
+    fn peak_and_crest_factor_shall_use_absolute_values() {
+        let signal = signal_from_values(&[1.0, -1.0, 1.0, -1.0, -4.0]);
+
+        assert_eq!(signal.peak(), 4.0);
+        assert_eq!(signal.crest_factor(), 4.0 / 4.0_f64.sqrt());
+    }
+
+    #[test]
+    fn symmetric_distribution_shall_have_zero_skewness() {
+        let signal = signal_from_values(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+
+        assert_eq!(signal.skewness(), 0.0);
+        assert!((signal.kurtosis() - 1.7).abs() < 1e-12);
+        assert!((signal.excess_kurtosis() + 1.3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn skewness_shall_be_positive_for_right_tailed_data() {
+        let signal = signal_from_values(&[0.0, 0.0, 0.0, 0.0, 10.0]);
+
+        assert!(signal.skewness() > 0.0);
+    }
+
+    #[test]
+    fn percentile_shall_interpolate_between_sorted_values() {
+        let signal = signal_from_values(&[4.0, 1.0, 3.0, 2.0]);
+
+        assert_eq!(signal.percentile(0.0), 1.0);
+        assert_eq!(signal.percentile(100.0), 4.0);
+        assert_eq!(signal.median(), 2.5);
+        assert_eq!(signal.percentile(25.0), 1.75);
+        assert!(signal.percentile(101.0).is_nan());
+    }
+
+    #[test]
+    fn histogram_shall_count_values_in_bins() {
+        let signal = signal_from_values(&[0.0, 0.5, 1.0, 1.5, 2.0, 2.0]);
+        let hist = signal.histogram(2);
+
+        assert_eq!(hist.edges(), &[0.0, 1.0, 2.0]);
+        assert_eq!(hist.counts(), &[2, 4]);
+        assert_eq!(hist.bin_centers(), vec![0.5, 1.5]);
+    }
+
+    #[test]
+    fn histogram_in_range_shall_skip_values_outside_range() {
+        let signal = signal_from_values(&[-1.0, 0.1, 0.2, 0.9, 3.0]);
+        let hist = signal.histogram_in_range(4, (0.0, 1.0));
+
+        assert_eq!(hist.counts(), &[2, 0, 0, 1]);
+    }
+
+    #[test]
+    fn statistics_of_empty_signal_shall_be_nan() {
+        let signal = DescreteSignal::new();
+
+        assert!(signal.mean().is_nan());
+        assert!(signal.min().is_nan());
+        assert!(signal.peak().is_nan());
+        assert!(signal.energy().is_nan());
+        assert!(signal.median().is_nan());
+        assert!(signal.time_weighted_mean().is_nan());
+    }
+
+    #[test]
+    fn time_weighted_statistics_shall_account_for_sampling_intervals() {
+        let signal = DescreteSignal::new_from_vec(vec![(0.0, 0.0), (1.0, 0.0), (1.1, 10.0), (2.0, 10.0)]);
+
+        assert!((signal.time_weighted_mean() - 5.0 * 0.1 / 2.0 - 10.0 * 0.9 / 2.0).abs() < 1e-12);
+        assert_eq!(signal.mean(), 5.0);
+    }
+
+    #[test]
+    fn time_weighted_statistics_of_uniform_constant_signal_shall_match_values() {
+        let signal = signal_from_values(&[3.0, 3.0, 3.0, 3.0]);
+
+        assert_eq!(signal.time_weighted_mean(), 3.0);
+        assert_eq!(signal.time_weighted_rms(), 3.0);
+        assert_eq!(signal.time_weighted_variance(), 0.0);
+        assert_eq!(signal.time_weighted_std_dev(), 0.0);
+        assert_eq!(signal.time_weighted_energy(), 27.0);
+        assert_eq!(signal.time_weighted_power(), 9.0);
+    }
+}
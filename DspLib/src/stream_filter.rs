@@ -0,0 +1,28 @@
+use crate::DescreteSignal;
+
+/// Filter that keeps its state between calls, so stream can be processed sample by sample
+/// or block by block and output does not depend on how input is split.
+pub trait StreamFilter {
+    /// Processes next sample of the stream and returns one output sample.
+    fn process_sample(&mut self, x: f64) -> f64;
+
+    /// Clears state, as if filter was just created.
+    fn reset(&mut self);
+
+    /// Panics when `input` and `output` differ in length.
+    fn process_block(&mut self, input: &[f64], output: &mut [f64]) {
+        assert_eq!(input.len(), output.len(), "output block shall be as long as input block");
+        for (x, y) in input.iter().zip(output.iter_mut()) {
+            *y = self.process_sample(*x);
+        }
+    }
+
+    /// Filters next part of the stream, output samples keep timestamps of the input ones.
+    fn process_signal(&mut self, signal: &DescreteSignal, output: &mut DescreteSignal) {
+        output.set_metadata(signal.metadata().clone());
+        for (t, x) in signal {
+            let y = self.process_sample(*x);
+            output.push(*t, y);
+        }
+    }
+}
@@ -0,0 +1,29 @@
+//! Helpers shared by unit tests.
+
+use std::f64::consts::PI;
+
+use crate::{ComplexNumber, FilterAnalysis, SosFilter};
+
+pub(crate) fn assert_close(actual: &[f64], expected: &[f64], tolerance: f64) {
+    assert_eq!(actual.len(), expected.len(), "{:?}\n{:?}", actual, expected);
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < tolerance, "{:?}\n{:?}", actual, expected);
+    }
+}
+
+/// Magnitude response of SOS filter in dB at frequency in Hz.
+pub(crate) fn gain_db(filter: &SosFilter, frequency: f64, sample_rate: f64) -> f64 {
+    20.0 * FilterAnalysis::sos(filter, sample_rate).response(frequency).module().log10()
+}
+
+/// Magnitude response of FIR filter at frequency in Hz.
+pub(crate) fn fir_gain(taps: &[f64], frequency: f64, sample_rate: f64) -> f64 {
+    FilterAnalysis::fir(taps, sample_rate).response(frequency).module()
+}
+
+/// Response of linear phase FIR at normalized frequency without its delay of `(N - 1) / 2` samples.
+/// It is real for symmetric taps and imaginary for antisymmetric ones.
+pub(crate) fn fir_amplitude(taps: &[f64], frequency: f64) -> ComplexNumber {
+    let delay = (taps.len() - 1) as f64 / 2.0;
+    &FilterAnalysis::fir(taps, 1.0).response(frequency) * &ComplexNumber::from_polar(1.0, 2.0 * PI * frequency * delay)
+}
@@ -0,0 +1,362 @@
+use crate::polynomial::{from_roots, roots};
+use crate::{direct_convolve, Biquad, ComplexNumber, SosFilter};
+
+/// Rational transfer function `H(z) = B(z) / A(z)` with coefficients of `z^0, z^-1, z^-2, ...`
+/// (the same order as `b` and `a` of difference equation).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransferFunction {
+    b: Vec<f64>,
+    a: Vec<f64>,
+}
+
+/// Transfer function given by zeros, poles and gain, `H(z) = k * prod(z - z_i) / prod(z - p_i)`.
+/// Complex zeros and poles shall come in conjugate pairs. When there are fewer zeros than
+/// poles, the missing ones are at infinity (they delay response). Missing poles are at origin.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZeroPoleGain {
+    zeros: Vec<ComplexNumber>,
+    poles: Vec<ComplexNumber>,
+    gain: f64,
+}
+
+impl TransferFunction {
+    /// Coefficients are normalized, so `a[0]` becomes 1, and trailing zeros are removed.
+    /// Panics when `a` is empty or `a[0]` is zero.
+    pub fn new(b: &[f64], a: &[f64]) -> Self {
+        assert!(a.first().is_some_and(|a0| *a0 != 0.0), "the first denominator coefficient shall not be zero");
+        let trim = |c: &[f64]| {
+            let len = c.iter().rposition(|c| *c != 0.0).map_or(1, |i| i + 1);
+            c.iter().take(len).map(|c| c / a[0]).collect::<Vec<f64>>()
+        };
+        let b = if b.is_empty() { vec![0.0] } else { trim(b) };
+        Self{b, a: trim(a)}
+    }
+
+    /// Numerator coefficients.
+    pub fn b(&self) -> &[f64] {
+        &self.b
+    }
+
+    /// Normalized denominator coefficients, `a[0]` is 1.
+    pub fn a(&self) -> &[f64] {
+        &self.a
+    }
+
+    /// Order of filter, the highest power of `z^-1`.
+    pub fn order(&self) -> usize {
+        self.b.len().max(self.a.len()) - 1
+    }
+
+    /// Zeros are roots of numerator, poles roots of denominator, both padded with roots
+    /// at origin to the same degree. Multiple roots are found less accurately.
+    pub fn to_zpk(&self) -> ZeroPoleGain {
+        let len = self.b.len().max(self.a.len());
+        let padded = |c: &[f64]| {
+            let mut c = c.to_vec();
+            c.resize(len, 0.0);
+            c
+        };
+        let gain = self.b.iter().find(|b| **b != 0.0).copied().unwrap_or(0.0);
+        if gain == 0.0 {
+            return ZeroPoleGain::new(&[], &roots(&padded(&self.a)), 0.0);
+        }
+        ZeroPoleGain::new(&roots(&padded(&self.b)), &roots(&padded(&self.a)), gain)
+    }
+
+    /// Cascade of second order sections, see [`ZeroPoleGain::to_sos`].
+    pub fn to_sos(&self) -> SosFilter {
+        self.to_zpk().to_sos()
+    }
+
+    /// See [`ZeroPoleGain::is_stable`].
+    pub fn is_stable(&self) -> bool {
+        self.to_zpk().is_stable()
+    }
+
+    /// See [`ZeroPoleGain::is_minimum_phase`].
+    pub fn is_minimum_phase(&self) -> bool {
+        self.to_zpk().is_minimum_phase()
+    }
+}
+
+impl ZeroPoleGain {
+    pub fn new(zeros: &[ComplexNumber], poles: &[ComplexNumber], gain: f64) -> Self {
+        Self{zeros: zeros.to_vec(), poles: poles.to_vec(), gain}
+    }
+
+    pub fn zeros(&self) -> &[ComplexNumber] {
+        &self.zeros
+    }
+
+    pub fn poles(&self) -> &[ComplexNumber] {
+        &self.poles
+    }
+
+    pub fn gain(&self) -> f64 {
+        self.gain
+    }
+
+    /// All poles are strictly inside unit circle.
+    pub fn is_stable(&self) -> bool {
+        self.poles.iter().all(|p| p.module() < 1.0)
+    }
+
+    /// Stable filter with all zeros strictly inside unit circle and none at infinity (no pure
+    /// delay), so it has stable and causal inverse.
+    pub fn is_minimum_phase(&self) -> bool {
+        self.gain != 0.0 && self.is_stable() && self.zeros.len() >= self.poles.len()
+            && self.zeros.iter().all(|z| z.module() < 1.0)
+    }
+
+    pub fn to_transfer_function(&self) -> TransferFunction {
+        let mut b = from_roots(&self.zeros).iter().map(|b| b * self.gain).collect::<Vec<f64>>();
+        let a = from_roots(&self.poles);
+        if b.len() < a.len() {
+            let mut delayed = vec![0.0; a.len() - b.len()];
+            delayed.append(&mut b);
+            b = delayed;
+        }
+        TransferFunction::new(&b, &a)
+    }
+
+    /// Cascade of second order sections. Complex roots are kept with their conjugates, poles
+    /// closest to the unit circle go to the last section and every pair of poles is matched
+    /// with the nearest zeros. Gain is put into the first section.
+    pub fn to_sos(&self) -> SosFilter {
+        let order = self.zeros.len().max(self.poles.len());
+        let mut poles = self.poles.clone();
+        poles.resize(order, ComplexNumber::default());
+        let mut zeros = self.zeros.clone();
+        if order % 2 == 1 {
+            // pole at origin added to fill the last section is cancelled by zero at origin
+            poles.push(ComplexNumber::default());
+            zeros.push(ComplexNumber::default());
+        }
+        let mut pole_pairs = pair_roots(&poles);
+        pole_pairs.resize(order.div_ceil(2).max(1), vec![]);
+        let distance = |pair: &Vec<ComplexNumber>| pair.iter().map(|p| (1.0 - p.module()).abs()).fold(f64::INFINITY, f64::min);
+        pole_pairs.sort_by(|p, q| distance(q).total_cmp(&distance(p)));
+        let mut zero_pairs = pair_roots(&zeros);
+
+        let mut sections = vec![];
+        for poles in pole_pairs.iter().rev() {
+            let zeros = match poles.first() {
+                Some(pole) if !zero_pairs.is_empty() => {
+                    let nearest = (0..zero_pairs.len()).min_by(|&i, &j| {
+                        let d = |k: usize| zero_pairs[k].iter().map(|z| (z - pole).module()).fold(f64::INFINITY, f64::min);
+                        d(i).total_cmp(&d(j))
+                    }).unwrap();
+                    zero_pairs.remove(nearest)
+                }
+                _ => zero_pairs.pop().unwrap_or_default(),
+            };
+            // zeros at infinity are delays, section without poles has none
+            let mut b = vec![0.0; poles.len().saturating_sub(zeros.len())];
+            b.extend(from_roots(&zeros));
+            b.resize(3, 0.0);
+            let mut a = from_roots(poles);
+            a.resize(3, 0.0);
+            sections.push(Biquad::new([b[0], b[1], b[2]], [a[0], a[1], a[2]]));
+        }
+        sections.reverse();
+        let first = sections[0].coefficients();
+        sections[0] = Biquad::from_coefficients([first[0] * self.gain, first[1] * self.gain, first[2] * self.gain,
+                                                 first[3], first[4], first[5]]);
+        SosFilter::new(sections)
+    }
+}
+
+impl SosFilter {
+    /// Product of all sections as single transfer function.
+    pub fn to_transfer_function(&self) -> TransferFunction {
+        let (b, a) = self.sections().iter().fold((vec![1.0], vec![1.0]), |(b, a), section| {
+            (direct_convolve(&b, section.b()), direct_convolve(&a, section.a()))
+        });
+        TransferFunction::new(&b, &a)
+    }
+
+    /// Every section is stable.
+    pub fn is_stable(&self) -> bool {
+        self.sections().iter().all(|section| TransferFunction::new(section.b(), section.a()).is_stable())
+    }
+
+    /// See [`ZeroPoleGain::is_minimum_phase`].
+    pub fn is_minimum_phase(&self) -> bool {
+        self.to_zpk().is_minimum_phase()
+    }
+
+    /// Zeros and poles of all sections, found separately for every section.
+    pub fn to_zpk(&self) -> ZeroPoleGain {
+        let mut result = ZeroPoleGain::new(&[], &[], 1.0);
+        for section in self.sections() {
+            let zpk = TransferFunction::new(section.b(), section.a()).to_zpk();
+            result.zeros.extend(zpk.zeros);
+            result.poles.extend(zpk.poles);
+            result.gain *= zpk.gain;
+        }
+        result
+    }
+}
+
+/// Groups roots into pairs, complex roots with their conjugates and real roots with each other
+/// (the closest to unit circle together). Single real root is left alone in the last pair.
+fn pair_roots(roots: &[ComplexNumber]) -> Vec<Vec<ComplexNumber>> {
+    let mut pairs = vec![];
+    let mut real = vec![];
+    let mut lower = roots.iter().filter(|z| z.im() < 0.0).copied().collect::<Vec<ComplexNumber>>();
+    for z in roots {
+        if z.im() == 0.0 {
+            real.push(*z);
+        } else if z.im() > 0.0 {
+            let conjugate = (0..lower.len())
+                .min_by(|&i, &j| (&lower[i].conj() - z).module().total_cmp(&(&lower[j].conj() - z).module()))
+                .map(|i| lower.swap_remove(i));
+            pairs.push(vec![*z, conjugate.unwrap_or(z.conj())]);
+        }
+    }
+    // roots below real axis without conjugate are paired with their own conjugates
+    pairs.extend(lower.iter().map(|z| vec![z.conj(), *z]));
+    real.sort_by(|p, q| (1.0 - p.module()).abs().total_cmp(&(1.0 - q.module()).abs()));
+    pairs.extend(real.chunks(2).map(|pair| pair.to_vec()));
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_close;
+    use crate::{BiquadForm, StreamFilter};
+
+    fn impulse_response(filter: &mut SosFilter, len: usize) -> Vec<f64> {
+        (0..len).map(|n| filter.process_sample(if n == 0 { 1.0 } else { 0.0 })).collect()
+    }
+
+    fn tf_impulse_response(tf: &TransferFunction, len: usize) -> Vec<f64> {
+        let mut output: Vec<f64> = vec![];
+        for n in 0..len {
+            let mut y = tf.b().get(n).copied().unwrap_or(0.0);
+            for (i, a) in tf.a().iter().enumerate().skip(1).filter(|(i, _)| *i <= n) {
+                y -= a * output[n - i];
+            }
+            output.push(y);
+        }
+        output
+    }
+
+    /// 4th order low-pass filter with poles at different distance from unit circle.
+    fn transfer_function() -> TransferFunction {
+        let zpk = ZeroPoleGain::new(
+            &[ComplexNumber::new(-1.0, 0.0), ComplexNumber::new(-1.0, 0.0), ComplexNumber::new(0.2, 0.9), ComplexNumber::new(0.2, -0.9)],
+            &[ComplexNumber::new(0.5, 0.3), ComplexNumber::new(0.5, -0.3), ComplexNumber::new(0.6, 0.75), ComplexNumber::new(0.6, -0.75)],
+            0.05);
+        zpk.to_transfer_function()
+    }
+
+    #[test]
+    fn transfer_function_shall_be_normalized_and_trimmed() {
+        let tf = TransferFunction::new(&[2.0, 1.0, 0.0], &[2.0, -1.0, 0.0, 0.0]);
+
+        assert_eq!(tf.b(), &[1.0, 0.5]);
+        assert_eq!(tf.a(), &[1.0, -0.5]);
+        assert_eq!(tf.order(), 1);
+    }
+
+    #[test]
+    fn zpk_shall_round_trip_through_transfer_function() {
+        let tf = transfer_function();
+        let zpk = tf.to_zpk();
+        let back = zpk.to_transfer_function();
+
+        assert_eq!(zpk.zeros().len(), 4);
+        assert_eq!(zpk.poles().len(), 4);
+        assert!((zpk.gain() - 0.05).abs() < 1e-15);
+        assert!(zpk.poles().iter().any(|p| (p - &ComplexNumber::new(0.6, 0.75)).module() < 1e-9));
+        assert_close(back.b(), tf.b(), 1e-9);
+        assert_close(back.a(), tf.a(), 1e-12);
+    }
+
+    #[test]
+    fn sos_shall_have_the_same_response_as_transfer_function() {
+        let tf = transfer_function();
+        let expected = tf_impulse_response(&tf, 60);
+
+        for form in [BiquadForm::DirectFormI, BiquadForm::DirectFormII, BiquadForm::TransposedDirectFormII] {
+            let mut sos = tf.to_sos().set_form(form);
+            assert_eq!(sos.sections().len(), 2);
+            assert_close(&impulse_response(&mut sos, 60), &expected, 1e-9);
+        }
+        assert_close(tf.to_sos().to_transfer_function().a(), tf.a(), 1e-12);
+    }
+
+    #[test]
+    fn poles_closest_to_unit_circle_shall_be_in_the_last_section() {
+        let sos = transfer_function().to_sos();
+        let last = sos.sections()[1].a();
+
+        // poles 0.6 +- 0.75j
+        assert!((last[1] + 1.2).abs() < 1e-9 && (last[2] - 0.9225).abs() < 1e-9, "{:?}", last);
+        // zeros 0.2 +- 0.9j are the nearest to these poles
+        let b = sos.sections()[1].b();
+        assert!((b[1] / b[0] + 0.4).abs() < 1e-9 && (b[2] / b[0] - 0.85).abs() < 1e-9, "{:?}", b);
+    }
+
+    #[test]
+    fn odd_order_and_delay_shall_be_kept_in_sections() {
+        let tf = TransferFunction::new(&[0.0, 0.3, 0.2], &[1.0, -0.5, 0.1, 0.05]);
+        let mut sos = tf.to_sos();
+
+        assert_eq!(sos.sections().len(), 2);
+        assert_close(&impulse_response(&mut sos, 40), &tf_impulse_response(&tf, 40), 1e-9);
+    }
+
+    #[test]
+    fn fir_transfer_function_shall_be_converted_to_sos() {
+        let tf = TransferFunction::new(&[1.0, 2.0, 3.0, 2.0, 1.0], &[1.0]);
+        let mut sos = tf.to_sos();
+
+        // zeros are double, so they are found less accurately
+        assert_close(&impulse_response(&mut sos, 6), &[1.0, 2.0, 3.0, 2.0, 1.0, 0.0], 1e-7);
+        let zpk = sos.to_zpk();
+        assert!(zpk.poles().iter().all(|p| p.module() == 0.0));
+        assert!((zpk.gain() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn gain_and_short_fir_shall_round_trip_through_sos() {
+        let gain = TransferFunction::new(&[2.0], &[1.0]);
+        let mut sos = gain.to_sos();
+        assert_eq!(sos.sections().len(), 1);
+        assert_close(&impulse_response(&mut sos, 4), &[2.0, 0.0, 0.0, 0.0], 1e-12);
+        assert_close(sos.to_transfer_function().b(), gain.b(), 1e-12);
+
+        for taps in [vec![1.0, 0.5], vec![0.5, -0.2, 0.3]] {
+            let fir = TransferFunction::new(&taps, &[1.0]);
+            let mut sos = fir.to_sos();
+            let mut expected = taps.clone();
+            expected.resize(5, 0.0);
+            assert_close(&impulse_response(&mut sos, 5), &expected, 1e-12);
+            let back = sos.to_transfer_function();
+            assert_close(back.b(), fir.b(), 1e-12);
+            assert_close(back.a(), fir.a(), 1e-12);
+        }
+    }
+
+    #[test]
+    fn stability_and_minimum_phase_shall_follow_roots() {
+        assert!(TransferFunction::new(&[1.0, -0.5], &[1.0, -0.9, 0.2]).is_minimum_phase());
+        assert!(!TransferFunction::new(&[1.0, -2.0], &[1.0, -0.9, 0.2]).is_minimum_phase());
+        // pure delay has zero at infinity
+        assert!(!TransferFunction::new(&[0.0, 1.0], &[1.0, -0.5]).is_minimum_phase());
+        assert!(!TransferFunction::new(&[1.0], &[1.0, -2.5, 1.0]).is_stable());
+        assert!(!TransferFunction::new(&[1.0], &[1.0, -2.5, 1.0]).is_minimum_phase());
+        assert!(!TransferFunction::new(&[1.0], &[1.0, 0.0, 1.0]).is_stable());
+
+        let sos = SosFilter::from_coefficients(&[[1.0, 0.2, 0.0, 1.0, -0.5, 0.1], [1.0, 0.0, 0.25, 1.0, 1.2, 0.5]]);
+        assert!(sos.is_stable() && sos.is_minimum_phase());
+        let sos = SosFilter::from_coefficients(&[[1.0, 0.2, 0.0, 1.0, -0.5, 0.1], [1.0, 0.0, 1.0, 1.0, 1.2, 1.1]]);
+        assert!(!sos.is_stable() && !sos.is_minimum_phase());
+        assert!(!SosFilter::from_coefficients(&[[1.0, -2.0, 1.0, 1.0, 0.0, 0.0]]).is_minimum_phase());
+    }
+}
@@ -0,0 +1,574 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::{DescreteSignal, MultichannelSignal, SignalMetadata};
+
+const WAVE_FORMAT_PCM: u16 = 0x0001;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 0x0003;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+/// Tail of KSDATAFORMAT_SUBTYPE GUIDs, first two bytes hold the format tag.
+const SUBFORMAT_GUID_TAIL: [u8; 14] = [0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71];
+
+/// Sample encoding in WAV file. 8-bit PCM is unsigned, other integer formats are signed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WavSampleFormat {
+    Pcm8,
+    Pcm16,
+    Pcm24,
+    Pcm32,
+    Float32,
+    Float64,
+}
+
+impl WavSampleFormat {
+    pub fn bits_per_sample(&self) -> u16 {
+        match self {
+            WavSampleFormat::Pcm8 => 8,
+            WavSampleFormat::Pcm16 => 16,
+            WavSampleFormat::Pcm24 => 24,
+            WavSampleFormat::Pcm32 | WavSampleFormat::Float32 => 32,
+            WavSampleFormat::Float64 => 64,
+        }
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, WavSampleFormat::Float32 | WavSampleFormat::Float64)
+    }
+
+    fn bytes_per_sample(&self) -> usize {
+        self.bits_per_sample() as usize / 8
+    }
+
+    fn from_tag(tag: u16, bits: u16) -> Option<Self> {
+        match (tag, bits) {
+            (WAVE_FORMAT_PCM, 8) => Some(WavSampleFormat::Pcm8),
+            (WAVE_FORMAT_PCM, 16) => Some(WavSampleFormat::Pcm16),
+            (WAVE_FORMAT_PCM, 24) => Some(WavSampleFormat::Pcm24),
+            (WAVE_FORMAT_PCM, 32) => Some(WavSampleFormat::Pcm32),
+            (WAVE_FORMAT_IEEE_FLOAT, 32) => Some(WavSampleFormat::Float32),
+            (WAVE_FORMAT_IEEE_FLOAT, 64) => Some(WavSampleFormat::Float64),
+            _ => None,
+        }
+    }
+
+    fn tag(&self) -> u16 {
+        if self.is_float() {
+            WAVE_FORMAT_IEEE_FLOAT
+        } else {
+            WAVE_FORMAT_PCM
+        }
+    }
+
+    /// Decodes one sample into range -1.0..1.0 (float formats are returned unchanged).
+    fn decode(&self, bytes: &[u8]) -> f64 {
+        match self {
+            WavSampleFormat::Pcm8 => (bytes[0] as f64 - 128.0) / 128.0,
+            WavSampleFormat::Pcm16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f64 / 32768.0,
+            WavSampleFormat::Pcm24 => {
+                (i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8) as f64 / 8388608.0
+            }
+            WavSampleFormat::Pcm32 => {
+                i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64 / 2147483648.0
+            }
+            WavSampleFormat::Float32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+            WavSampleFormat::Float64 => {
+                f64::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]])
+            }
+        }
+    }
+
+    /// Encodes one already quantized sample.
+    fn encode(&self, value: f64, bytes: &mut Vec<u8>) {
+        match self {
+            WavSampleFormat::Pcm8 => bytes.push((value as i32 + 128) as u8),
+            WavSampleFormat::Pcm16 => bytes.extend_from_slice(&(value as i16).to_le_bytes()),
+            WavSampleFormat::Pcm24 => bytes.extend_from_slice(&(value as i32).to_le_bytes()[..3]),
+            WavSampleFormat::Pcm32 => bytes.extend_from_slice(&(value as i32).to_le_bytes()),
+            WavSampleFormat::Float32 => bytes.extend_from_slice(&(value as f32).to_le_bytes()),
+            WavSampleFormat::Float64 => bytes.extend_from_slice(&value.to_le_bytes()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum WavError {
+    Io(std::io::Error),
+    /// File is not a RIFF/WAVE file or its structure is broken.
+    InvalidFile(String),
+    /// Format tag and bits per sample combination that is not supported.
+    UnsupportedFormat { tag: u16, bits_per_sample: u16 },
+    /// Signals written to one file shall have equal length.
+    ChannelLengthMismatch,
+    NoChannels,
+    /// Sample rate is not given and the one derived from signal is not positive integer, or
+    /// byte rate with it does not fit into the header.
+    InvalidSampleRate(f64),
+    /// Number of channels does not fit into the header.
+    TooManyChannels(usize),
+    /// Data chunk would exceed 4 GiB limit of the format.
+    DataTooLarge(usize),
+}
+
+impl Display for WavError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WavError::Io(e) => write!(f, "I/O error: {}", e),
+            WavError::InvalidFile(reason) => write!(f, "invalid WAV file: {}", reason),
+            WavError::UnsupportedFormat { tag, bits_per_sample } => {
+                write!(f, "unsupported WAV format tag {:#06x} with {} bits per sample", tag, bits_per_sample)
+            }
+            WavError::ChannelLengthMismatch => write!(f, "all channels shall have the same number of samples"),
+            WavError::NoChannels => write!(f, "there shall be at least one channel"),
+            WavError::InvalidSampleRate(rate) => write!(f, "sample rate {} cannot be written to WAV header", rate),
+            WavError::TooManyChannels(channels) => write!(f, "{} channels cannot be written to WAV header", channels),
+            WavError::DataTooLarge(len) => write!(f, "{} bytes of data exceed size limit of WAV file", len),
+        }
+    }
+}
+
+impl std::error::Error for WavError {}
+
+impl From<std::io::Error> for WavError {
+    fn from(e: std::io::Error) -> Self {
+        WavError::Io(e)
+    }
+}
+
+/// Content of WAV file, one signal per channel. Integer samples are scaled to range -1.0..1.0.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WavRecording {
+    sample_rate: u32,
+    format: WavSampleFormat,
+    channels: Vec<DescreteSignal>,
+}
+
+impl WavRecording {
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn format(&self) -> WavSampleFormat {
+        self.format
+    }
+
+    pub fn channels(&self) -> &[DescreteSignal] {
+        &self.channels
+    }
+
+    pub fn into_channels(self) -> Vec<DescreteSignal> {
+        self.channels
+    }
+
+    pub fn to_multichannel(&self) -> MultichannelSignal {
+        MultichannelSignal::from_signals(&self.channels)
+    }
+}
+
+pub fn read_wav<P: AsRef<Path>>(path: P) -> Result<WavRecording, WavError> {
+    let mut recording = read_wav_from(BufReader::new(File::open(&path)?))?;
+    let source = path.as_ref().display().to_string();
+    for ch in recording.channels.iter_mut() {
+        ch.set_metadata(ch.metadata().clone().set_property("source", &source));
+    }
+    Ok(recording)
+}
+
+pub fn read_wav_from<R: Read>(mut reader: R) -> Result<WavRecording, WavError> {
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(WavError::InvalidFile("missing RIFF/WAVE header".to_string()));
+    }
+
+    let mut fmt = None;
+    let mut data = None;
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let size = u32::from_le_bytes([bytes[pos + 4], bytes[pos + 5], bytes[pos + 6], bytes[pos + 7]]) as usize;
+        let body = &bytes[pos + 8..(pos + 8 + size).min(bytes.len())];
+        match id {
+            b"fmt " => fmt = Some(parse_fmt_chunk(body)?),
+            b"data" => data = Some(body),
+            _ => {}
+        }
+        pos += 8 + size + size % 2;
+    }
+
+    let (format, number_of_channels, sample_rate) = fmt.ok_or(WavError::InvalidFile("missing fmt chunk".to_string()))?;
+    let data = data.ok_or(WavError::InvalidFile("missing data chunk".to_string()))?;
+    let frame_size = format.bytes_per_sample() * number_of_channels;
+    let mut channels = vec![DescreteSignal::new(); number_of_channels];
+
+    for (i, frame) in data.chunks_exact(frame_size).enumerate() {
+        let t = i as f64 / sample_rate as f64;
+        for (ch, sample) in channels.iter_mut().zip(frame.chunks_exact(format.bytes_per_sample())) {
+            ch.push(t, format.decode(sample));
+        }
+    }
+    for (i, ch) in channels.iter_mut().enumerate() {
+        ch.set_metadata(SignalMetadata::new()
+            .set_time_unit("s")
+            .set_sample_rate(sample_rate as f64)
+            .set_property("channel", &i.to_string()));
+    }
+
+    Ok(WavRecording{sample_rate, format, channels})
+}
+
+fn parse_fmt_chunk(body: &[u8]) -> Result<(WavSampleFormat, usize, u32), WavError> {
+    if body.len() < 16 {
+        return Err(WavError::InvalidFile("fmt chunk is too short".to_string()));
+    }
+    let u16_at = |i: usize| u16::from_le_bytes([body[i], body[i + 1]]);
+    let mut tag = u16_at(0);
+    let channels = u16_at(2) as usize;
+    let sample_rate = u32::from_le_bytes([body[4], body[5], body[6], body[7]]);
+    let bits = u16_at(14);
+
+    if tag == WAVE_FORMAT_EXTENSIBLE {
+        if body.len() < 40 {
+            return Err(WavError::InvalidFile("extensible fmt chunk is too short".to_string()));
+        }
+        tag = u16_at(24);
+    }
+    if channels == 0 {
+        return Err(WavError::NoChannels);
+    }
+    if sample_rate == 0 {
+        return Err(WavError::InvalidFile("sample rate shall not be zero".to_string()));
+    }
+    let format = WavSampleFormat::from_tag(tag, bits)
+        .ok_or(WavError::UnsupportedFormat{tag, bits_per_sample: bits})?;
+
+    Ok((format, channels, sample_rate))
+}
+
+/// Noise added before quantization to integer formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Dither {
+    None,
+    /// Uniform noise of 1 LSB peak-to-peak.
+    Rectangular,
+    /// Triangular noise of 2 LSB peak-to-peak, sum of two rectangular sources.
+    Triangular,
+}
+
+/// Summary of written data.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WavWriteReport {
+    clipped: Vec<usize>,
+    peaks: Vec<f64>,
+}
+
+impl WavWriteReport {
+    /// Number of samples that exceeded full scale and were clipped, per channel.
+    /// Float formats are never clipped.
+    pub fn clipped_per_channel(&self) -> &[usize] {
+        &self.clipped
+    }
+
+    pub fn clipped_samples(&self) -> usize {
+        self.clipped.iter().sum()
+    }
+
+    /// Largest absolute value of every channel before quantization.
+    pub fn peaks(&self) -> &[f64] {
+        &self.peaks
+    }
+}
+
+/// Writes signals as channels of WAV file. Signal values are expected in range -1.0..1.0.
+/// Files with more than two channels or more than 16 bits per sample are written with
+/// WAVE_FORMAT_EXTENSIBLE header.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WavWriter {
+    format: WavSampleFormat,
+    dither: Dither,
+    sample_rate: Option<u32>,
+    seed: u64,
+}
+
+impl WavWriter {
+    pub fn new(format: WavSampleFormat) -> Self {
+        Self{format, dither: Dither::None, sample_rate: None, seed: 0x2545F4914F6CDD1D}
+    }
+
+    pub fn set_dither(mut self, dither: Dither) -> Self {
+        self.dither = dither;
+        self
+    }
+
+    /// Sample rate written to the header. By default it is taken from metadata of the first
+    /// signal, or from its sampling period when metadata does not hold it.
+    pub fn set_sample_rate(mut self, rate: u32) -> Self {
+        self.sample_rate = Some(rate);
+        self
+    }
+
+    /// Seed of pseudo-random generator used for dithering.
+    pub fn set_dither_seed(mut self, seed: u64) -> Self {
+        self.seed = seed.max(1);
+        self
+    }
+
+    pub fn write<P: AsRef<Path>>(&self, path: P, channels: &[DescreteSignal]) -> Result<WavWriteReport, WavError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let report = self.write_to(&mut writer, channels)?;
+        writer.flush()?;
+        Ok(report)
+    }
+
+    pub fn write_to<W: Write>(&self, mut writer: W, channels: &[DescreteSignal]) -> Result<WavWriteReport, WavError> {
+        let first = channels.first().ok_or(WavError::NoChannels)?;
+        if channels.iter().any(|ch| ch.len() != first.len()) {
+            return Err(WavError::ChannelLengthMismatch);
+        }
+        let sample_rate = match self.sample_rate {
+            Some(rate) => rate,
+            None => {
+                let rate = first.metadata().sample_rate().unwrap_or_else(|| {
+                    if first.len() > 1 { 1.0 / first.get_sampling_period() } else { f64::NAN }
+                }).round();
+                if !(rate >= 1.0 && rate <= u32::MAX as f64) {
+                    return Err(WavError::InvalidSampleRate(rate));
+                }
+                rate as u32
+            }
+        };
+
+        let mut report = WavWriteReport{clipped: vec![0; channels.len()], peaks: vec![0.0; channels.len()]};
+        let mut data = Vec::with_capacity(first.len() * channels.len() * self.format.bytes_per_sample());
+        let mut rng = self.seed;
+        for i in 0..first.len() {
+            for (ch, signal) in channels.iter().enumerate() {
+                let value = signal[i].1;
+                report.peaks[ch] = report.peaks[ch].max(value.abs());
+                let (quantized, clipped) = self.quantize(value, &mut rng);
+                if clipped {
+                    report.clipped[ch] += 1;
+                }
+                self.format.encode(quantized, &mut data);
+            }
+        }
+
+        writer.write_all(&self.header(channels.len(), sample_rate, data.len())?)?;
+        writer.write_all(&data)?;
+        if data.len() % 2 == 1 {
+            writer.write_all(&[0])?;
+        }
+
+        Ok(report)
+    }
+
+    /// Scales value to integer range of the format, adds dither and rounds it.
+    /// Returns quantized value and information whether it had to be clipped.
+    fn quantize(&self, value: f64, rng: &mut u64) -> (f64, bool) {
+        if self.format.is_float() {
+            return (value, false);
+        }
+        let scale = (1u64 << (self.format.bits_per_sample() - 1)) as f64;
+        let noise = match self.dither {
+            Dither::None => 0.0,
+            Dither::Rectangular => next_uniform(rng) - 0.5,
+            Dither::Triangular => next_uniform(rng) - next_uniform(rng),
+        };
+        let scaled = (value * scale + noise).round();
+        let clamped = scaled.clamp(-scale, scale - 1.0);
+
+        (clamped, clamped != scaled)
+    }
+
+    fn header(&self, channels: usize, sample_rate: u32, data_len: usize) -> Result<Vec<u8>, WavError> {
+        let extensible = channels > 2 || self.format.bits_per_sample() > 16;
+        let bits = self.format.bits_per_sample();
+        let block_align = u16::try_from(channels * self.format.bytes_per_sample())
+            .map_err(|_| WavError::TooManyChannels(channels))?;
+        let byte_rate = sample_rate.checked_mul(block_align as u32)
+            .ok_or(WavError::InvalidSampleRate(sample_rate as f64))?;
+        let fmt_len: u32 = if extensible { 40 } else { 16 };
+        let too_large = || WavError::DataTooLarge(data_len);
+        let data_chunk_len = u32::try_from(data_len).map_err(|_| too_large())?;
+        let riff_len = u32::try_from(data_len + data_len % 2).ok()
+            .and_then(|len| len.checked_add(4 + (8 + fmt_len) + 8))
+            .ok_or_else(too_large)?;
+
+        let mut h = Vec::with_capacity(68);
+        h.extend_from_slice(b"RIFF");
+        h.extend_from_slice(&riff_len.to_le_bytes());
+        h.extend_from_slice(b"WAVEfmt ");
+        h.extend_from_slice(&fmt_len.to_le_bytes());
+        h.extend_from_slice(&(if extensible { WAVE_FORMAT_EXTENSIBLE } else { self.format.tag() }).to_le_bytes());
+        h.extend_from_slice(&(channels as u16).to_le_bytes());
+        h.extend_from_slice(&sample_rate.to_le_bytes());
+        h.extend_from_slice(&byte_rate.to_le_bytes());
+        h.extend_from_slice(&block_align.to_le_bytes());
+        h.extend_from_slice(&bits.to_le_bytes());
+        if extensible {
+            let channel_mask: u32 = if channels < 32 { (1 << channels) - 1 } else { 0 };
+            h.extend_from_slice(&22u16.to_le_bytes());
+            h.extend_from_slice(&bits.to_le_bytes());
+            h.extend_from_slice(&channel_mask.to_le_bytes());
+            h.extend_from_slice(&self.format.tag().to_le_bytes());
+            h.extend_from_slice(&SUBFORMAT_GUID_TAIL);
+        }
+        h.extend_from_slice(b"data");
+        h.extend_from_slice(&data_chunk_len.to_le_bytes());
+        Ok(h)
+    }
+}
+
+/// Xorshift pseudo-random generator returning values in range 0.0..1.0.
+fn next_uniform(state: &mut u64) -> f64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    (*state >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_channels(len: usize, channels: usize) -> Vec<DescreteSignal> {
+        (0..channels).map(|ch| {
+            (0..len).map(|i| (i as f64 / 8000.0, ((i + ch) as f64 * 0.3).sin() * 0.9)).collect()
+        }).collect()
+    }
+
+    fn write_to_vec(writer: &WavWriter, channels: &[DescreteSignal]) -> (Vec<u8>, WavWriteReport) {
+        let mut bytes = vec![];
+        let report = writer.write_to(&mut bytes, channels).unwrap();
+        (bytes, report)
+    }
+
+    #[test]
+    fn written_file_shall_be_read_back_within_quantization_error() {
+        let formats = [(WavSampleFormat::Pcm8, 1.0 / 128.0), (WavSampleFormat::Pcm16, 1.0 / 32768.0),
+                       (WavSampleFormat::Pcm24, 1.0 / 8388608.0), (WavSampleFormat::Pcm32, 1.0 / 2147483648.0),
+                       (WavSampleFormat::Float32, 1e-7), (WavSampleFormat::Float64, 0.0)];
+        let channels = test_channels(50, 2);
+
+        for (format, error) in formats {
+            let (bytes, _) = write_to_vec(&WavWriter::new(format), &channels);
+            let recording = read_wav_from(bytes.as_slice()).unwrap();
+
+            assert_eq!(recording.format(), format);
+            assert_eq!(recording.sample_rate(), 8000);
+            assert_eq!(recording.channels().len(), 2);
+            for (read, written) in recording.channels().iter().zip(&channels) {
+                assert_eq!(read.len(), written.len());
+                for ((t1, y1), (t2, y2)) in read.iter().zip(written.iter()) {
+                    assert_eq!(t1, t2);
+                    assert!((y1 - y2).abs() <= error, "{:?}: {} != {}", format, y1, y2);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn read_channels_shall_carry_sample_rate_in_metadata() {
+        let (bytes, _) = write_to_vec(&WavWriter::new(WavSampleFormat::Pcm16).set_sample_rate(44100), &test_channels(4, 1));
+        let recording = read_wav_from(bytes.as_slice()).unwrap();
+
+        assert_eq!(recording.channels()[0].metadata().sample_rate(), Some(44100.0));
+        assert_eq!(recording.channels()[0].metadata().time_unit(), Some("s"));
+        assert_eq!(recording.channels()[0][1].0, 1.0 / 44100.0);
+    }
+
+    #[test]
+    fn multichannel_and_high_resolution_files_shall_use_extensible_format() {
+        let (bytes, _) = write_to_vec(&WavWriter::new(WavSampleFormat::Pcm16), &test_channels(4, 3));
+        assert_eq!(u16::from_le_bytes([bytes[20], bytes[21]]), WAVE_FORMAT_EXTENSIBLE);
+        assert_eq!(read_wav_from(bytes.as_slice()).unwrap().to_multichannel().number_of_channels(), 3);
+
+        let (bytes, _) = write_to_vec(&WavWriter::new(WavSampleFormat::Float32), &test_channels(4, 1));
+        assert_eq!(u16::from_le_bytes([bytes[20], bytes[21]]), WAVE_FORMAT_EXTENSIBLE);
+
+        let (bytes, _) = write_to_vec(&WavWriter::new(WavSampleFormat::Pcm16), &test_channels(4, 2));
+        assert_eq!(u16::from_le_bytes([bytes[20], bytes[21]]), WAVE_FORMAT_PCM);
+    }
+
+    #[test]
+    fn report_shall_count_clipped_samples() {
+        let loud = DescreteSignal::new_from_vec(vec![(0.0, 1.5), (1.0, 0.5), (2.0, -1.0), (3.0, -2.0)]);
+        let (bytes, report) = write_to_vec(&WavWriter::new(WavSampleFormat::Pcm16), std::slice::from_ref(&loud));
+        let read = read_wav_from(bytes.as_slice()).unwrap();
+
+        assert_eq!(report.clipped_per_channel(), &[2]);
+        assert_eq!(report.peaks(), &[2.0]);
+        assert_eq!(read.channels()[0].values().collect::<Vec<f64>>(), vec![32767.0 / 32768.0, 0.5, -1.0, -1.0]);
+
+        let (_, report) = write_to_vec(&WavWriter::new(WavSampleFormat::Float32), &[loud]);
+        assert_eq!(report.clipped_samples(), 0);
+    }
+
+    #[test]
+    fn dither_shall_add_noise_within_its_range() {
+        let silence = DescreteSignal::new_from_vec((0..1000).map(|i| (i as f64, 0.0)).collect());
+        let (bytes, _) = write_to_vec(&WavWriter::new(WavSampleFormat::Pcm16).set_dither(Dither::Triangular), &[silence]);
+        let values = read_wav_from(bytes.as_slice()).unwrap().channels()[0].values()
+            .map(|y| (y * 32768.0).round() as i32)
+            .collect::<Vec<i32>>();
+
+        assert!(values.iter().all(|v| v.abs() <= 1));
+        assert!(values.iter().any(|v| *v != 0));
+    }
+
+    #[test]
+    fn writing_channels_of_different_length_shall_fail() {
+        let channels = [DescreteSignal::new_from_vec(vec![(0.0, 0.0), (1.0, 0.0)]),
+                        DescreteSignal::new_from_vec(vec![(0.0, 0.0)])];
+
+        assert!(matches!(WavWriter::new(WavSampleFormat::Pcm16).write_to(vec![], &channels),
+                         Err(WavError::ChannelLengthMismatch)));
+        assert!(matches!(WavWriter::new(WavSampleFormat::Pcm16).write_to(vec![], &[]), Err(WavError::NoChannels)));
+    }
+
+    #[test]
+    fn sample_rate_and_sizes_that_do_not_fit_header_shall_fail() {
+        let writer = WavWriter::new(WavSampleFormat::Pcm16);
+        let single = [DescreteSignal::new_from_vec(vec![(0.0, 0.5)])];
+        assert!(matches!(writer.write_to(vec![], &single), Err(WavError::InvalidSampleRate(_))));
+        assert!(matches!(writer.write_to(vec![], &[DescreteSignal::new()]), Err(WavError::InvalidSampleRate(_))));
+        let reversed = [DescreteSignal::new_from_vec(vec![(1.0, 0.0), (0.0, 0.0)])];
+        assert!(matches!(writer.write_to(vec![], &reversed), Err(WavError::InvalidSampleRate(_))));
+        assert!(WavWriter::new(WavSampleFormat::Pcm16).set_sample_rate(8000).write_to(vec![], &single).is_ok());
+
+        let pcm32 = WavWriter::new(WavSampleFormat::Pcm32);
+        assert!(matches!(pcm32.header(2, u32::MAX / 4, 0), Err(WavError::InvalidSampleRate(_))));
+        assert!(matches!(pcm32.header(1, 8000, u32::MAX as usize + 1), Err(WavError::DataTooLarge(_))));
+        assert!(matches!(pcm32.header(1, 8000, u32::MAX as usize - 10), Err(WavError::DataTooLarge(_))));
+        assert!(matches!(pcm32.header(20000, 8000, 0), Err(WavError::TooManyChannels(20000))));
+    }
+
+    #[test]
+    fn reading_invalid_data_shall_fail() {
+        assert!(matches!(read_wav_from(&b"RIFX\0\0\0\0WAVE"[..]), Err(WavError::InvalidFile(_))));
+
+        let (mut bytes, _) = write_to_vec(&WavWriter::new(WavSampleFormat::Pcm16), &test_channels(4, 1));
+        bytes[34] = 12;
+        assert!(matches!(read_wav_from(bytes.as_slice()),
+                         Err(WavError::UnsupportedFormat{tag: WAVE_FORMAT_PCM, bits_per_sample: 12})));
+
+        let (mut bytes, _) = write_to_vec(&WavWriter::new(WavSampleFormat::Pcm16), &test_channels(4, 1));
+        bytes[24..28].copy_from_slice(&[0; 4]);
+        assert!(matches!(read_wav_from(bytes.as_slice()), Err(WavError::InvalidFile(_))));
+    }
+
+    #[test]
+    fn unknown_chunks_shall_be_skipped() {
+        let (bytes, _) = write_to_vec(&WavWriter::new(WavSampleFormat::Pcm16), &test_channels(4, 1));
+        let mut with_list = bytes[..36].to_vec();
+        with_list.extend_from_slice(b"LIST\x03\0\0\0abc\0");
+        with_list.extend_from_slice(&bytes[36..]);
+
+        assert_eq!(read_wav_from(with_list.as_slice()).unwrap(), read_wav_from(bytes.as_slice()).unwrap());
+    }
+}
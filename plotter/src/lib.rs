@@ -1,6 +1,7 @@
 use core::f64;
 
 use plotters::prelude::*;
+use dsp_lib::{DescreteSignal, SignalMetadata};
 
 const RESOLUTION : (u32, u32) = (2048, 1280);
 const WIDTH : u32 = 4;
@@ -8,7 +9,8 @@ const P_SIZE: u32 = 6;
 
 pub fn plot_data(data: &[(f64, f64)], title: &str, (x_axis, y_axis): (&str, &str)) -> Result<(), Box<dyn std::error::Error>> {
     let x_rng = (data[0].0, data.last().unwrap().0);
-    let y_rng = get_min_max(&data.iter().map(|(_, d)| *d).collect::<Vec<f64>>());
+    let signal = DescreteSignal::new_from_vec(data.to_vec());
+    let y_rng = (signal.min(), signal.max());
     let filename = &format!("{}.png", title.split(" ").collect::<Vec<&str>>().join("_"));
     let backend = BitMapBackend::new(filename, RESOLUTION).into_drawing_area();
     backend.fill(&WHITE)?;
@@ -25,42 +27,56 @@ pub fn plot_data(data: &[(f64, f64)], title: &str, (x_axis, y_axis): (&str, &str
         .y_desc(y_axis)
         .draw()?;
     chart.draw_series(LineSeries::new(
-            data.into_iter().map(|(x, y)| (*x, *y)),
+            data.iter().map(|(x, y)| (*x, *y)),
             MAGENTA.stroke_width(WIDTH).filled(),
         ).point_size(P_SIZE))?
         .label(title)
-        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &RED));
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RED));
 
         chart
         .configure_series_labels()
-        .background_style(&WHITE.mix(0.8))
-        .border_style(&BLACK)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
         .draw()?;
 
     Ok(())
 }
 
-fn get_min_max(data: &[f64]) -> (f64, f64) {
-    let mut min = f64::MAX;
-    let mut max = f64::MIN;
-    for x in data {
-        if *x > max {
-            max = *x;
-        }
-        if *x < min {
-            min = *x;
-        }
+/// Plots signal using its metadata: name is used as title, units are added to axis labels.
+pub fn plot_signal(signal: &DescreteSignal) -> Result<(), Box<dyn std::error::Error>> {
+    let meta = signal.metadata();
+    let (x_axis, y_axis) = get_axis_labels(meta);
+    plot_data(signal.get_data(), meta.name().unwrap_or("Signal"), (&x_axis, &y_axis))
+}
+
+fn get_axis_labels(meta: &SignalMetadata) -> (String, String) {
+    let x_quantity = match meta.time_unit() {
+        Some(unit) if unit.ends_with("Hz") || unit.starts_with("1/") => "Frequency",
+        _ => "time",
+    };
+    (get_axis_label(x_quantity, meta.time_unit()), get_axis_label("Signal value", meta.value_unit()))
+}
+
+fn get_axis_label(quantity: &str, unit: Option<&str>) -> String {
+    match unit {
+        Some(unit) => format!("{} [{}]", quantity, unit),
+        None => quantity.to_string(),
     }
-    (min, max)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     #[test]
-    fn get_min_max_shall_return_minimum_and_maximum_value_of_slice() {
-        assert_eq!(get_min_max(&[0.0, 1.1, 2.2, 3.3]), (0.0, 3.3));
-        assert_eq!(get_min_max(&[343525.5, -431421.32, 43.45, 44234.2352, 235232234.3, 0.1241241]),
-            (-431421.32, 235232234.3));
+    fn axis_labels_shall_contain_units_from_metadata() {
+        let meta = SignalMetadata::new().set_time_unit("s").set_value_unit("V");
+        assert_eq!(get_axis_labels(&meta), ("time [s]".to_string(), "Signal value [V]".to_string()));
+        assert_eq!(get_axis_labels(&SignalMetadata::new()), ("time".to_string(), "Signal value".to_string()));
+    }
+
+    #[test]
+    fn axis_labels_shall_describe_frequency_for_spectrum() {
+        let meta = SignalMetadata::new().set_time_unit("s").to_spectrum();
+        assert_eq!(get_axis_labels(&meta).0, "Frequency [Hz]");
     }
 }